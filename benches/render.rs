@@ -0,0 +1,132 @@
+//! Rendering and hashing of a full season, the work every loop iteration
+//! repeats for each weekend.
+
+use std::hint::black_box;
+
+use chrono::{TimeDelta, TimeZone, Utc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use f1_bot_types::{
+    Series, Session, SessionKind, SessionStatus, Weekend, WeekendStatus,
+};
+use f1_notif_bot::{
+    config::DisplayConfig,
+    util::{
+        render, weekend_message_content, weekend_message_hash,
+        CalendarVerbosity, FullWeekend, GridPenalty, GuildSettings,
+        WeekendLink, WeekendMeta,
+    },
+};
+
+const ROUNDS: u64 = 24;
+const SESSIONS: u64 = 5;
+
+/// A 24-round season with five sessions, links and a penalty per weekend.
+fn season() -> Vec<FullWeekend> {
+    let opener = Utc.with_ymd_and_hms(2026, 3, 6, 1, 30, 0).unwrap();
+    (0..ROUNDS)
+        .map(|round| {
+            let start = opener + TimeDelta::weeks(round as i64 * 2);
+            let sessions = (0..SESSIONS)
+                .map(|index| Session {
+                    id: (round * SESSIONS + index) as i64,
+                    weekend: round as i64,
+                    kind: SessionKind::from(index as i8),
+                    title: format!("Session {}", index + 1),
+                    start_date: start + TimeDelta::hours(index as i64 * 20),
+                    duration: 3600,
+                    status: SessionStatus::Open,
+                })
+                .collect();
+            FullWeekend {
+                weekend: Weekend {
+                    id: round,
+                    name: format!("Grand Prix {}", round + 1),
+                    icon: ":checkered_flag:".to_owned(),
+                    series: Series::F1,
+                    start_date: start,
+                    status: WeekendStatus::Open,
+                },
+                sessions,
+                links: vec![WeekendLink {
+                    id: round,
+                    weekend: round,
+                    label: "Tickets".to_owned(),
+                    url: "https://example.com/tickets".to_owned(),
+                }],
+                meta: WeekendMeta {
+                    weekend: round,
+                    timezone: Some("Europe/London".to_owned()),
+                    provisional: false,
+                    circuit: None,
+                },
+                penalties: vec![GridPenalty {
+                    id: round,
+                    weekend: round,
+                    driver: "Driver".to_owned(),
+                    penalty: "5 places".to_owned(),
+                    reason: Some("Power unit elements".to_owned()),
+                }],
+                round: round + 1,
+                circuit: None,
+                forecasts: vec![],
+            }
+        })
+        .collect()
+}
+
+fn render(c: &mut Criterion) {
+    let weekends = season();
+    let display = DisplayConfig::default();
+    let settings = GuildSettings {
+        sprint_footer: true,
+        lengths_footer: true,
+        verbose: false,
+        calendar: CalendarVerbosity::Full,
+    };
+
+    c.bench_function("weekend_text", |b| {
+        b.iter(|| {
+            for weekend in weekends.iter() {
+                black_box(render::weekend_text(weekend, true, &display, false));
+            }
+        })
+    });
+    c.bench_function("weekend_message_content", |b| {
+        b.iter(|| {
+            for weekend in weekends.iter() {
+                black_box(weekend_message_content(
+                    weekend, &display, &settings,
+                ));
+            }
+        })
+    });
+    c.bench_function("weekend_embed", |b| {
+        b.iter(|| {
+            for weekend in weekends.iter() {
+                black_box(render::weekend_embed(weekend, &display, &settings));
+            }
+        })
+    });
+    c.bench_function("calendar_text", |b| {
+        b.iter(|| {
+            for weekend in weekends.iter() {
+                black_box(render::calendar_text(
+                    weekend,
+                    true,
+                    &display,
+                    settings.calendar,
+                ));
+            }
+        })
+    });
+    c.bench_function("weekend_message_hash", |b| {
+        b.iter(|| {
+            for weekend in weekends.iter() {
+                black_box(weekend_message_hash(weekend, &display, &settings));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS pending_syncs (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    -- NULL for a new weekend, created from `series` and `name` on confirm
+    weekend BIGINT UNSIGNED NULL,
+    series TINYINT NULL,
+    name VARCHAR(255) NULL,
+    channel VARCHAR(64) NULL,
+    message VARCHAR(64) NULL,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS pending_changes (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    sync BIGINT UNSIGNED NOT NULL,
+    session BIGINT NULL,
+    kind TINYINT NOT NULL,
+    session_kind TINYINT NOT NULL DEFAULT 0,
+    title VARCHAR(255) NOT NULL,
+    start_date TIMESTAMP NULL,
+    previous_date TIMESTAMP NULL,
+    duration BIGINT NOT NULL DEFAULT 0,
+    FOREIGN KEY (sync) REFERENCES pending_syncs(id) ON DELETE CASCADE
+);
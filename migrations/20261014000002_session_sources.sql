@@ -0,0 +1,8 @@
+-- `sessions` rows are read straight into `f1_bot_types::Session`, so the
+-- origin of a session is tracked next to it instead of as an extra column.
+CREATE TABLE IF NOT EXISTS session_sources (
+    session BIGINT NOT NULL PRIMARY KEY,
+    source TINYINT NOT NULL DEFAULT 0
+);
+
+ALTER TABLE pending_syncs ADD COLUMN source TINYINT NOT NULL DEFAULT 1;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS notification_log (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    session BIGINT NOT NULL,
+    series TINYINT NOT NULL,
+    channel VARCHAR(64) NOT NULL,
+    message VARCHAR(64) NOT NULL,
+    -- when the notification window opened
+    intended TIMESTAMP NOT NULL,
+    sent TIMESTAMP NOT NULL,
+    -- seconds between `intended` and `sent`, negative when sent early
+    latency BIGINT NOT NULL
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS session_engagement (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    session BIGINT NOT NULL,
+    series TINYINT NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    weekend_name VARCHAR(255) NOT NULL,
+    reactions BIGINT UNSIGNED NOT NULL,
+    thread_messages BIGINT UNSIGNED NOT NULL,
+    recorded TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS weekend_links (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    weekend BIGINT UNSIGNED NOT NULL,
+    label VARCHAR(80) NOT NULL,
+    url VARCHAR(512) NOT NULL
+);
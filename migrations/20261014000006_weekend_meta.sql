@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS weekend_meta (
+    weekend BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    timezone VARCHAR(64) NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS milestones_sent (
+    session BIGINT NOT NULL,
+    offset_minutes BIGINT UNSIGNED NOT NULL,
+    sent TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (session, offset_minutes)
+);
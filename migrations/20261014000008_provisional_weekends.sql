@@ -0,0 +1 @@
+ALTER TABLE weekend_meta ADD COLUMN provisional BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+-- Last content rendered into a tracked message, keyed by `messages.id`.
+CREATE TABLE IF NOT EXISTS message_contents (
+    message BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    content TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS stage_instances (
+    session BIGINT NOT NULL PRIMARY KEY,
+    channel BIGINT UNSIGNED NOT NULL,
+    started TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
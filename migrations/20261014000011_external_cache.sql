@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS external_cache (
+    kind TINYINT NOT NULL,
+    cache_key VARCHAR(255) NOT NULL,
+    body MEDIUMTEXT NOT NULL,
+    fetched_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (kind, cache_key)
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS grid_penalties (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    weekend BIGINT UNSIGNED NOT NULL,
+    driver VARCHAR(64) NOT NULL,
+    penalty VARCHAR(64) NOT NULL,
+    reason VARCHAR(255) NULL,
+    INDEX (weekend)
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS guild_settings (
+    guild BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    value VARCHAR(255) NOT NULL,
+    PRIMARY KEY (guild, name)
+);
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS custom_session_kinds (
+    code TINYINT NOT NULL PRIMARY KEY,
+    name VARCHAR(64) NOT NULL UNIQUE
+);
@@ -0,0 +1,3 @@
+CREATE INDEX messages_kind_series ON messages (kind, series);
+CREATE INDEX sessions_weekend_start ON sessions (weekend, start_date);
+CREATE INDEX weekends_series_status_start ON weekends (series, status, start_date);
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS lights_out_sent (
+    session BIGINT NOT NULL PRIMARY KEY,
+    sent TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
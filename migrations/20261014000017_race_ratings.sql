@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS race_ratings (
+    session BIGINT NOT NULL PRIMARY KEY,
+    series TINYINT NOT NULL,
+    channel BIGINT UNSIGNED NOT NULL,
+    message BIGINT UNSIGNED NOT NULL,
+    posted TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    closes TIMESTAMP NOT NULL,
+    votes BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    total BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    collected BOOLEAN NOT NULL DEFAULT FALSE,
+    INDEX (series, posted)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE custom_session_kinds
+    ADD COLUMN low_priority BOOLEAN NOT NULL DEFAULT FALSE;
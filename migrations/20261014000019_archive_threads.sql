@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS archive_threads (
+    series TINYINT NOT NULL,
+    year INT NOT NULL,
+    thread BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (series, year)
+);
@@ -0,0 +1,9 @@
+-- Past contents of tracked messages, newest rows per message are kept.
+CREATE TABLE IF NOT EXISTS message_history (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    message BIGINT UNSIGNED NOT NULL,
+    content TEXT NOT NULL,
+    hash BIGINT UNSIGNED NOT NULL,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX (message)
+);
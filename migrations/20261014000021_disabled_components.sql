@@ -0,0 +1,6 @@
+-- Components paused at runtime with `/feature disable`.
+CREATE TABLE IF NOT EXISTS disabled_components (
+    name VARCHAR(32) NOT NULL PRIMARY KEY,
+    disabled_by VARCHAR(64) NOT NULL,
+    disabled_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,7 @@
+-- Weekends created or matched by the Jolpica sync, by season and round.
+CREATE TABLE IF NOT EXISTS jolpica_rounds (
+    season INT NOT NULL,
+    round INT NOT NULL,
+    weekend BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (season, round)
+);
@@ -0,0 +1,3 @@
+-- members holding the pinged role when the notification was sent, NULL
+-- without a ping or when the members couldn't be counted
+ALTER TABLE notification_log ADD COLUMN audience BIGINT UNSIGNED NULL;
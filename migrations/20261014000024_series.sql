@@ -0,0 +1,17 @@
+-- Per-series settings that override the config file, ids match the
+-- `series` column of `weekends`. A NULL channel or role keeps the
+-- configured one.
+CREATE TABLE IF NOT EXISTS series (
+    id TINYINT NOT NULL PRIMARY KEY,
+    name VARCHAR(64) NOT NULL,
+    icon VARCHAR(64) NOT NULL,
+    channel BIGINT UNSIGNED NULL,
+    role BIGINT UNSIGNED NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+INSERT IGNORE INTO series (id, name, icon) VALUES
+    (0, 'F1', ':red_car:'),
+    (1, 'F2', ':blue_car:'),
+    (2, 'F3', ':race_car:'),
+    (3, 'F1 Academy', ':checkered_flag:');
@@ -0,0 +1,7 @@
+-- Reminders already sent per session and offset, so each one pings once.
+CREATE TABLE IF NOT EXISTS reminders_sent (
+    session BIGINT NOT NULL,
+    offset_minutes BIGINT UNSIGNED NOT NULL,
+    sent TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (session, offset_minutes)
+);
@@ -0,0 +1,5 @@
+-- Values the bot keeps between runs, like the version it last ran as.
+CREATE TABLE IF NOT EXISTS bot_state (
+    name VARCHAR(64) NOT NULL PRIMARY KEY,
+    value VARCHAR(255) NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Community stream and watch-along links submitted during a live session.
+-- `approved` is set once an admin accepted the link, `channel`/`message`
+-- point at the post in the live thread afterwards.
+CREATE TABLE IF NOT EXISTS watch_links (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    session BIGINT NOT NULL,
+    label VARCHAR(80) NOT NULL,
+    url VARCHAR(512) NOT NULL,
+    submitter BIGINT UNSIGNED NOT NULL,
+    approved BOOLEAN NOT NULL DEFAULT FALSE,
+    channel BIGINT UNSIGNED NULL,
+    message BIGINT UNSIGNED NULL,
+    INDEX (session)
+);
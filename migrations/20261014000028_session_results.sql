@@ -0,0 +1,10 @@
+-- Results messages posted after a session, with the hash of the posted
+-- classification so revised results are edited in.
+CREATE TABLE IF NOT EXISTS session_results (
+    session BIGINT NOT NULL PRIMARY KEY,
+    series TINYINT NOT NULL,
+    channel BIGINT UNSIGNED NOT NULL,
+    message BIGINT UNSIGNED NOT NULL,
+    hash VARCHAR(64) NOT NULL,
+    posted TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,7 @@
+-- The championship standings message of each series, edited in place.
+CREATE TABLE IF NOT EXISTS standings_messages (
+    series TINYINT NOT NULL PRIMARY KEY,
+    channel BIGINT UNSIGNED NOT NULL,
+    message BIGINT UNSIGNED NOT NULL,
+    hash VARCHAR(64) NOT NULL
+);
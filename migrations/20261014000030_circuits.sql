@@ -0,0 +1,47 @@
+-- Circuits with the coordinates their weather forecast is fetched for.
+-- Weekends link to one through `weekend_meta.circuit`, unlinked weekends
+-- fall back to the circuit of the bundled facts matching their name.
+CREATE TABLE IF NOT EXISTS circuits (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(128) NOT NULL UNIQUE,
+    latitude DOUBLE NOT NULL,
+    longitude DOUBLE NOT NULL
+);
+
+ALTER TABLE weekend_meta ADD COLUMN circuit BIGINT UNSIGNED NULL;
+
+-- Daily forecast per circuit, replaced with every refresh.
+CREATE TABLE IF NOT EXISTS weather_forecasts (
+    circuit BIGINT UNSIGNED NOT NULL,
+    day DATE NOT NULL,
+    temperature DOUBLE NOT NULL,
+    rain_probability TINYINT UNSIGNED NOT NULL,
+    fetched TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (circuit, day)
+);
+
+INSERT IGNORE INTO circuits (name, latitude, longitude) VALUES
+    ('Bahrain International Circuit', 26.0325, 50.5106),
+    ('Jeddah Corniche Circuit', 21.6319, 39.1044),
+    ('Albert Park', -37.8497, 144.9680),
+    ('Suzuka', 34.8431, 136.5410),
+    ('Shanghai International Circuit', 31.3389, 121.2200),
+    ('Miami International Autodrome', 25.9581, -80.2389),
+    ('Imola', 44.3439, 11.7167),
+    ('Circuit de Monaco', 43.7347, 7.4206),
+    ('Circuit Gilles Villeneuve', 45.5000, -73.5228),
+    ('Circuit de Barcelona-Catalunya', 41.5700, 2.2611),
+    ('Red Bull Ring', 47.2197, 14.7647),
+    ('Silverstone', 52.0786, -1.0169),
+    ('Hungaroring', 47.5789, 19.2486),
+    ('Spa-Francorchamps', 50.4372, 5.9714),
+    ('Zandvoort', 52.3888, 4.5409),
+    ('Monza', 45.6156, 9.2811),
+    ('Baku City Circuit', 40.3725, 49.8533),
+    ('Marina Bay Street Circuit', 1.2914, 103.8640),
+    ('Circuit of the Americas', 30.1328, -97.6411),
+    ('Autódromo Hermanos Rodríguez', 19.4042, -99.0907),
+    ('Interlagos', -23.7036, -46.6997),
+    ('Las Vegas Strip Circuit', 36.1147, -115.1730),
+    ('Lusail International Circuit', 25.4900, 51.4542),
+    ('Yas Marina Circuit', 24.4672, 54.6031);
@@ -0,0 +1,32 @@
+-- Circuit details shown in weekend messages. `image` is the track layout,
+-- an http(s) URL or a path on the bot host that is attached when a weekend
+-- message is posted.
+ALTER TABLE circuits
+    ADD COLUMN country VARCHAR(64) NULL,
+    ADD COLUMN image VARCHAR(512) NULL,
+    ADD COLUMN laps SMALLINT UNSIGNED NULL;
+
+UPDATE circuits SET country = 'Bahrain', laps = 57 WHERE name = 'Bahrain International Circuit';
+UPDATE circuits SET country = 'Saudi Arabia', laps = 50 WHERE name = 'Jeddah Corniche Circuit';
+UPDATE circuits SET country = 'Australia', laps = 58 WHERE name = 'Albert Park';
+UPDATE circuits SET country = 'Japan', laps = 53 WHERE name = 'Suzuka';
+UPDATE circuits SET country = 'China', laps = 56 WHERE name = 'Shanghai International Circuit';
+UPDATE circuits SET country = 'United States', laps = 57 WHERE name = 'Miami International Autodrome';
+UPDATE circuits SET country = 'Italy', laps = 63 WHERE name = 'Imola';
+UPDATE circuits SET country = 'Monaco', laps = 78 WHERE name = 'Circuit de Monaco';
+UPDATE circuits SET country = 'Canada', laps = 70 WHERE name = 'Circuit Gilles Villeneuve';
+UPDATE circuits SET country = 'Spain', laps = 66 WHERE name = 'Circuit de Barcelona-Catalunya';
+UPDATE circuits SET country = 'Austria', laps = 71 WHERE name = 'Red Bull Ring';
+UPDATE circuits SET country = 'United Kingdom', laps = 52 WHERE name = 'Silverstone';
+UPDATE circuits SET country = 'Hungary', laps = 70 WHERE name = 'Hungaroring';
+UPDATE circuits SET country = 'Belgium', laps = 44 WHERE name = 'Spa-Francorchamps';
+UPDATE circuits SET country = 'Netherlands', laps = 72 WHERE name = 'Zandvoort';
+UPDATE circuits SET country = 'Italy', laps = 53 WHERE name = 'Monza';
+UPDATE circuits SET country = 'Azerbaijan', laps = 51 WHERE name = 'Baku City Circuit';
+UPDATE circuits SET country = 'Singapore', laps = 62 WHERE name = 'Marina Bay Street Circuit';
+UPDATE circuits SET country = 'United States', laps = 56 WHERE name = 'Circuit of the Americas';
+UPDATE circuits SET country = 'Mexico', laps = 71 WHERE name = 'Autódromo Hermanos Rodríguez';
+UPDATE circuits SET country = 'Brazil', laps = 71 WHERE name = 'Interlagos';
+UPDATE circuits SET country = 'United States', laps = 50 WHERE name = 'Las Vegas Strip Circuit';
+UPDATE circuits SET country = 'Qatar', laps = 57 WHERE name = 'Lusail International Circuit';
+UPDATE circuits SET country = 'United Arab Emirates', laps = 58 WHERE name = 'Yas Marina Circuit';
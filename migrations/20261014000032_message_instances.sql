@@ -0,0 +1,7 @@
+-- Deployment a tracked message belongs to, for instances sharing one
+-- database. Messages without a row belong to production.
+CREATE TABLE IF NOT EXISTS message_instances (
+    message BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    instance VARCHAR(64) NOT NULL,
+    INDEX (instance)
+);
@@ -0,0 +1,4 @@
+-- Stored message hashes switch to a hash that is stable across releases.
+-- The old values can't be converted, cleared ones are recomputed on the next
+-- tick, messages whose content is unchanged aren't edited for it.
+UPDATE messages SET hash = NULL WHERE hash IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Notifications sent per session and stage, kept apart from the session
+-- status so a restart mid-window neither pings twice nor skips a ping.
+CREATE TABLE IF NOT EXISTS notifications (
+    session BIGINT NOT NULL,
+    stage TINYINT NOT NULL,
+    sent TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    message BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (session, stage)
+);
+
+-- Sessions notified before this table existed.
+INSERT IGNORE INTO notifications (session, stage, sent, message)
+SELECT session, 0, MIN(sent), MIN(CAST(message AS UNSIGNED))
+FROM notification_log
+GROUP BY session;
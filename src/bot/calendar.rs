@@ -1 +1,148 @@
+//! Calendar updates, run beside the bot loop.
+//!
+//! Reserving or removing calendar messages is paced to stay clear of rate
+//! limits and can take a while, so it never runs inside a tick where it
+//! would hold back due notifications.
 
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use serenity::all::Http;
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    util::{
+        acquire, alerts, clear_calendar, create_calendar, create_calendar_for,
+        edit_calendar, edit_calendar_for, fetch_full_weekends_for_channel,
+        CalendarVerbosity, SeasonWindow,
+    },
+};
+
+/// Set while an update runs, so a slow one isn't started twice.
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Clears [RUNNING] when the update task ends, also when it panics.
+struct Running;
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::Release);
+    }
+}
+
+/// Brings every calendar up to date in its own task, unless the previous
+/// update is still running.
+pub fn spawn_update(
+    pool: MySqlPool,
+    http: Arc<Http>,
+    conf: &'static Config<'static>,
+    verbosity: CalendarVerbosity,
+    window: SeasonWindow,
+) {
+    if RUNNING.swap(true, Ordering::AcqRel) {
+        info!("The last calendar update is still running");
+        return;
+    }
+    let running = Running;
+    tokio::spawn(async move {
+        let _running = running;
+        match acquire(&pool).await {
+            Ok(mut db_conn) => {
+                update(db_conn.as_mut(), &http, conf, verbosity, window).await
+            },
+            Err(why) => error!("{why:#?}"),
+        }
+    });
+}
+
+async fn update(
+    db_conn: &mut MySqlConnection,
+    http: &Http,
+    conf: &Config<'_>,
+    verbosity: CalendarVerbosity,
+    window: SeasonWindow,
+) {
+    for (channel, group) in conf.calendar_groups() {
+        if let [series] = group[..] {
+            if let Err(why) = create_calendar(
+                db_conn,
+                http,
+                series,
+                channel,
+                conf.discord.admin_channel,
+                window,
+            )
+            .await
+            {
+                error!("{why}");
+            } else {
+                info!("Created {series} Calendar");
+            }
+
+            if let Err(why) = edit_calendar(
+                db_conn,
+                http,
+                series,
+                &conf.display,
+                verbosity,
+                window,
+            )
+            .await
+            {
+                error!("{why:#?}");
+                alerts::report("Updating the calendar", &why);
+            }
+            continue;
+        }
+
+        // Shared channels get one combined calendar, owned by the first
+        // series posting into it.
+        let owner = group[0];
+        for series in group[1..].iter() {
+            if let Err(why) = clear_calendar(db_conn, http, *series).await {
+                error!("{why:#?}");
+            }
+        }
+        match fetch_full_weekends_for_channel(db_conn, &group, window).await {
+            Ok(weekends) => {
+                if let Err(why) = create_calendar_for(
+                    db_conn,
+                    http,
+                    owner,
+                    channel,
+                    conf.discord.admin_channel,
+                    weekends,
+                )
+                .await
+                {
+                    error!("{why}");
+                } else {
+                    info!("Created combined Calendar");
+                }
+            },
+            Err(why) => error!("{why:#?}"),
+        }
+        match fetch_full_weekends_for_channel(db_conn, &group, window).await {
+            Ok(weekends) => {
+                if let Err(why) = edit_calendar_for(
+                    db_conn,
+                    http,
+                    owner,
+                    weekends,
+                    true,
+                    &conf.display,
+                    verbosity,
+                )
+                .await
+                {
+                    error!("{why:#?}");
+                }
+            },
+            Err(why) => error!("{why:#?}"),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Sessions that started while the bot was down, paused or had its
+//! notifications switched off. They are past the notification window, so
+//! the loop would never notify them or mark them done, and their weekend
+//! would never finish.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::{MessageKind, SessionStatus};
+use serenity::all::{CacheHttp, ChannelId, CreateMessage};
+use sqlx::MySqlConnection;
+use tracing::info;
+
+use crate::{
+    config::Config,
+    error::Error,
+    util::{
+        fetch_live_sessions, fetch_sessions_starting_between, fetch_weekend,
+        insert_expiring_message, is_in_notify_window, is_notification_sent,
+        mark_notification_sent, mark_session_done, session_end,
+        NotificationStage, SessionDuration,
+    },
+};
+
+/// Posts a notice for every live session that wasn't notified and marks it
+/// done, so the loop archives the weekend once its last session is over.
+/// Sessions that started `since` and are over already are only marked done.
+/// Returns the number of sessions caught up on.
+pub async fn run(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+    since: Option<DateTime<Utc>>,
+) -> Result<usize, Error> {
+    let now = Utc::now();
+    let active = config.active_series();
+    let mut caught_up = 0;
+    if let Some(since) = since {
+        for session in fetch_sessions_starting_between(db_conn, since, now)
+            .await?
+            .into_iter()
+            .filter(|f| {
+                matches!(f.status, SessionStatus::Open | SessionStatus::Delayed)
+            })
+        {
+            let end =
+                session_end(session.start_date, SessionDuration::of(&session));
+            if end > now {
+                continue;
+            }
+            let Some(weekend) =
+                fetch_weekend(db_conn, session.weekend as u64).await?
+            else {
+                continue;
+            };
+            if active.contains(&weekend.series) {
+                mark_session_done(db_conn, &session).await?;
+                caught_up += 1;
+            }
+        }
+    }
+    for session in fetch_live_sessions(db_conn, now).await? {
+        // the loop still notifies a session that started a moment ago
+        if session.status == SessionStatus::Finished
+            || is_in_notify_window(session.start_date, now, TimeDelta::zero())
+        {
+            continue;
+        }
+        // notified right before a restart, only the status flip is missing
+        if is_notification_sent(db_conn, session.id).await? {
+            mark_session_done(db_conn, &session).await?;
+            continue;
+        }
+        let Some(weekend) =
+            fetch_weekend(db_conn, session.weekend as u64).await?
+        else {
+            continue;
+        };
+        if !active.contains(&weekend.series) {
+            continue;
+        }
+        let minutes =
+            now.signed_duration_since(session.start_date).num_minutes();
+        info!(
+            "{} `{}` started {minutes}m before the bot",
+            weekend.name, session.title
+        );
+        if config.catch_up.notice {
+            let channel = config.channel(weekend.series);
+            let mention = match config.role(weekend.series) {
+                role if role != 0 && config.catch_up.ping => {
+                    format!("<@&{role}> ")
+                },
+                _ => String::new(),
+            };
+            let posted = ChannelId::new(channel)
+                .send_message(
+                    http.http(),
+                    CreateMessage::new().content(format!(
+                        "{mention}🔴 **{} {} {}** is live (started {minutes}m \
+                         ago)",
+                        weekend.icon, weekend.name, session.title
+                    )),
+                )
+                .await?;
+            insert_expiring_message(
+                db_conn,
+                channel,
+                posted.id.get(),
+                MessageKind::Notification,
+                weekend.series,
+                session_end(session.start_date, SessionDuration::of(&session)),
+            )
+            .await?;
+            mark_notification_sent(
+                db_conn,
+                session.id,
+                NotificationStage::CatchUp,
+                posted.id.get(),
+            )
+            .await?;
+        }
+        mark_session_done(db_conn, &session).await?;
+        caught_up += 1;
+    }
+    Ok(caught_up)
+}
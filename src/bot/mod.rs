@@ -1,38 +1,58 @@
 pub mod calendar;
+pub mod catchup;
 pub mod notifs;
+pub mod schedule;
+pub mod worker;
+
+use notifs::{batch_notifications, PendingNotification, PingThrottle};
+use worker::{owns_guild, WORKER};
 
 use crate::{
+    commands,
     config::Config,
+    events::{BotEvent, EVENTS},
+    health, sync,
     util::{
-        check_expired_messages, check_expired_weekend, create_calendar,
-        create_new_notifications_msg_db, edit_calendar,
-        fetch_next_full_weekend_for_series, fetch_weekend_message_for_series,
-        insert_weekend_message, mark_message_expired, mark_session_done,
-        mark_weekend_done, mark_weekend_message_for_series_expired,
-        post_weekend_message, send_notification, update_message_hash,
-        update_weekend_message,
+        acquire, alerts, archive_weekend, audience, changelog,
+        check_expired_messages, check_expired_weekend, clock, crash,
+        create_new_notifications_msg_db, dst, ensure_connected,
+        fetch_guild_settings, fetch_next_full_weekend_for_series,
+        fetch_notified_sessions, fetch_weekend_message_for_series, ical,
+        insert_weekend_message, log_notification, mark_message_expired,
+        mark_notification_sent, mark_session_done, mark_weekend_done,
+        mark_weekend_message_for_series_expired, post_weekend_message,
+        quarantine, reconcile, registry, retention, send_lights_out,
+        send_merged_notification, send_milestones, send_reminders,
+        switches::{self, Component},
+        update_message_hash, update_stage, update_weekend_message_if_changed,
+        weekend_message_hash, GuildSettings, NotificationStage, SeasonWindow,
+        NOTIFY_WINDOW,
     },
+    watchalong,
 };
 use std::{
-    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
     sync::atomic::{AtomicBool, Ordering},
-    time::{Duration, Instant},
+    time::Instant,
 };
 
-use f1_bot_types::Series;
+use chrono::{TimeDelta, Utc};
 use serenity::{
-    all::{GuildId, Ready},
+    all::{
+        ChannelId, ConnectionStage, GuildId, Interaction, Ready,
+        ShardStageUpdateEvent,
+    },
     async_trait,
     prelude::*,
 };
 
-use tracing::{error, info};
+use tracing::{error, info, info_span, Instrument};
 
 pub struct Bot {
     pub is_mainthread_running: AtomicBool,
     pub config: &'static Config<'static>,
     pub database: &'static sqlx::MySqlPool,
-    pub cat: &'static [u8],
+    pub cat: &'static Path,
 }
 
 #[cfg(debug_assertions)]
@@ -52,10 +72,17 @@ impl EventHandler for Bot {
         ctx: Context,
         _guilds: Vec<GuildId>,
     ) {
+        // every shard gets here, only the one with the guild runs the loop
+        let shard_count = ctx.cache.shard_count();
+        let guild = GuildId::new(self.config.discord.guild);
+        if !owns_guild(ctx.shard_id, shard_count, guild) {
+            return;
+        }
         // prevent double-starting threads
         if self.is_mainthread_running.load(Ordering::Relaxed) {
             return;
         }
+        WORKER.pin(ctx.shard_id, shard_count);
         self.is_mainthread_running.swap(true, Ordering::Relaxed);
         set_presence(&ctx);
 
@@ -63,86 +90,350 @@ impl EventHandler for Bot {
         let http = ctx.http.clone();
         let conf = self.config;
         let cat = self.cat;
-        let mut db_conn = pool.acquire().await.unwrap();
+        let mut db_conn = match acquire(&pool).await {
+            Ok(db_conn) => db_conn,
+            Err(why) => {
+                error!("{why:#?}");
+                self.is_mainthread_running.swap(false, Ordering::Relaxed);
+                return;
+            },
+        };
+        // a pause kept across the restart holds back the tasks below too
+        if let Err(why) = switches::refresh(db_conn.as_mut()).await {
+            error!("{why:#?}");
+        }
 
-        tokio::spawn(async move {
+        tokio::spawn(clock::watch(
+            http.clone(),
+            &conf.clock,
+            conf.discord.admin_channel,
+        ));
+        tokio::spawn(retention::run(pool.clone(), &conf.retention));
+        tokio::spawn(alerts::run(
+            http.clone(),
+            &conf.alerts,
+            conf.discord.admin_channel,
+        ));
+        // mirrors only render, everything below writes shared state
+        if !conf.is_mirror() {
+            tokio::spawn(changelog::run(
+                pool.clone(),
+                http.clone(),
+                conf.discord.admin_channel,
+            ));
+            #[cfg(feature = "jolpica")]
+            tokio::spawn(sync::jolpica::run(pool.clone(), http.clone(), conf));
+            #[cfg(feature = "openf1")]
+            tokio::spawn(sync::openf1::run(pool.clone(), http.clone(), conf));
+            #[cfg(feature = "weather")]
+            tokio::spawn(crate::weather::run(pool.clone(), conf));
+            #[cfg(feature = "results")]
+            tokio::spawn(crate::results::run(pool.clone(), http.clone(), conf));
+            #[cfg(feature = "ics")]
+            tokio::spawn(crate::ingest::ics::import_all(
+                pool.clone(),
+                http.clone(),
+                conf,
+            ));
+        }
+        let bot_loop = tokio::spawn(async move {
+            if let Err(why) = quarantine::scan(db_conn.as_mut()).await {
+                error!("{why:#?}");
+            }
+            if let Err(why) = registry::refresh(db_conn.as_mut()).await {
+                error!("{why:#?}");
+            }
+            if switches::is_enabled(Component::Notifications)
+                && !conf.is_mirror()
+            {
+                match catchup::run(db_conn.as_mut(), &http, conf, None).await {
+                    Ok(0) => {},
+                    Ok(count) => info!("Caught up on {count} live sessions"),
+                    Err(why) => error!("{why:#?}"),
+                }
+            }
+            // when notifications were paused or switched off, to catch up
+            // once they resume
+            let mut held_since =
+                (!switches::is_enabled(Component::Notifications))
+                    .then(Utc::now);
             let mut last_weekend_ids = [0, 0, 0, 0u64];
             let mut last_invocation = Instant::now();
+            let batch_window =
+                TimeDelta::seconds(conf.notifications.batch_window as i64);
+            let mut throttle = PingThrottle::new(
+                conf.notifications.max_pings,
+                TimeDelta::seconds(conf.notifications.ping_window as i64),
+            );
+            let mut wake_at = Utc::now();
             loop {
                 info!("LWIs: {last_weekend_ids:?}");
-                tokio::time::sleep(Duration::from_secs(5)).await;
+                schedule::sleep_until(wake_at).await;
+                // replaced once the tick ran, skipped ticks try again soon
+                wake_at = Utc::now() + schedule::RETRY;
                 // This gives us the ability to abort the task if we want or need to.
                 
                 tokio::task::yield_now().await;
-                if let Err(why) =
-                    check_expired_messages(db_conn.as_mut(), &http).await
-                {
+                // every query of the tick would fail on a dropped connection
+                if let Err(why) = ensure_connected(&pool, &mut db_conn).await {
                     error!("{why:#?}");
+                    alerts::report("Reconnecting to the database", &why);
+                    continue;
                 }
-
-                if Instant::now().duration_since(last_invocation).as_secs()
-                    > 60 * 5
-                {
-                    last_invocation = Instant::now();
-                    info!("Doing Calendar");
-                    for val in Series::F1.i8()..=Series::F1Academy.i8() {
-                        let series: Series = val.into();
-                        if let Err(why) = create_calendar(
-                            db_conn.as_mut(),
-                            &http,
-                            val.into(),
-                            conf.channel(series),
-                        )
-                        .await
-                        {
-                            error!("{why}");
-                        } else {
-                            info!("Created {series} Calendar");
-                        }
-
+                health::record_query();
+                let tick = Instant::now();
+                async {
+                    if let Err(why) = switches::refresh(db_conn.as_mut()).await {
+                        error!("{why:#?}");
+                    }
+                    match switches::end_pause(db_conn.as_mut(), Utc::now()).await
+                    {
+                        Ok(true) => {
+                            info!("The pause ran out, resuming");
+                            let admin_channel = conf.discord.admin_channel;
+                            if admin_channel != 0 {
+                                if let Err(why) = ChannelId::new(admin_channel)
+                                    .say(
+                                        &http,
+                                        "▶️ The pause ran out, the bot posts again.",
+                                    )
+                                    .await
+                                {
+                                    error!("{why:#?}");
+                                }
+                            }
+                        },
+                        Ok(false) => {},
+                        Err(why) => error!("{why:#?}"),
+                    }
+                    let notifying =
+                        switches::is_enabled(Component::Notifications);
+                    match held_since {
+                        None if !notifying => held_since = Some(Utc::now()),
+                        Some(since) if notifying => {
+                            held_since = None;
+                            // the last tick before may have been a while ago
+                            let since = since - NOTIFY_WINDOW;
+                            if !conf.is_mirror() {
+                                match catchup::run(
+                                    db_conn.as_mut(),
+                                    &http,
+                                    conf,
+                                    Some(since),
+                                )
+                                .await
+                                {
+                                    Ok(0) => {},
+                                    Ok(count) => {
+                                        info!("Caught up on {count} sessions")
+                                    },
+                                    Err(why) => error!("{why:#?}"),
+                                }
+                            }
+                        },
+                        _ => {},
+                    }
+                    // nothing reaches Discord while paused
+                    if switches::is_paused() {
+                        return;
+                    }
+                    if let Err(why) = registry::refresh(db_conn.as_mut()).await {
+                        error!("{why:#?}");
+                    }
+                    if let Err(why) =
+                        reconcile::run(db_conn.as_mut(), &http, conf).await
+                    {
+                        error!("{why:#?}");
+                    }
+                    if let Err(why) =
+                        check_expired_messages(db_conn.as_mut(), &http).await
+                    {
+                        error!("{why:#?}");
+                    }
+                    if let Err(why) = watchalong::tick(db_conn.as_mut()).await {
+                        error!("{why:#?}");
+                    }
+                    if switches::is_enabled(Component::Stage) && !conf.is_mirror() {
                         if let Err(why) =
-                            edit_calendar(db_conn.as_mut(), &http, series).await
+                            update_stage(db_conn.as_mut(), &http, &conf.stage)
+                                .await
                         {
                             error!("{why:#?}");
                         }
                     }
-                }
-                for val in Series::F1.i8()..=Series::F1Academy.i8() {
-                    let series: Series = val.into();
-                    let role = conf.role(series);
-                    let channel = conf.channel(series);
-                    #[allow(unused)]
-                    let last_weekend_id = &mut last_weekend_ids[val as usize];
-                    let full_weekend = match fetch_next_full_weekend_for_series(
+
+                    let settings = match fetch_guild_settings(
                         db_conn.as_mut(),
-                        series,
+                        conf.discord.guild,
                     )
                     .await
                     {
-                        Ok(Some(d)) => d,
-                        Ok(None) => {
-                            let weekend_msg = match fetch_weekend_message_for_series(db_conn.as_mut(), series).await {
-                                Ok(Some(msg)) => msg,
-                                Ok(None) => continue,
-                                Err(why) => {
-                                    error!("{why:#?}");
-                                    continue;
-                                }
-                            };
-                            if let Err(why) = mark_message_expired(db_conn.as_mut(), weekend_msg.id, None).await {
+                        Ok(settings) => settings,
+                        Err(why) => {
+                            error!("{why:#?}");
+                            GuildSettings::default()
+                        },
+                    };
+                    if Instant::now().duration_since(last_invocation).as_secs()
+                        > 60 * 5
+                    {
+                        last_invocation = Instant::now();
+                        if let Err(why) = quarantine::scan(db_conn.as_mut()).await
+                        {
+                            error!("{why:#?}");
+                        }
+                        info!("Doing Calendar");
+                        let window = SeasonWindow::around(&conf.season, Utc::now());
+                        if !conf.is_mirror() {
+                            #[cfg(feature = "predictions")]
+                            if let Err(why) = crate::polls::tick(
+                                db_conn.as_mut(),
+                                &http,
+                                &conf.polls,
+                            )
+                            .await
+                            {
                                 error!("{why:#?}");
                             }
-                            continue;
-                        },
+                            if let Err(why) = dst::check(
+                                db_conn.as_mut(),
+                                &http,
+                                conf.discord.admin_channel,
+                                window,
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                            }
+                        }
+                        if !conf.ics_export.directory.is_empty() {
+                            if let Err(why) = ical::write_feeds(
+                                db_conn.as_mut(),
+                                &conf.ics_export.directory,
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                            }
+                        }
+                        if switches::is_enabled(Component::Calendar) {
+                            calendar::spawn_update(
+                                pool.clone(),
+                                http.clone(),
+                                conf,
+                                settings.calendar,
+                                window,
+                            );
+                        }
+                    }
+                    // a minute back covers the whole notification window
+                    let notified = match fetch_notified_sessions(
+                        db_conn.as_mut(),
+                        Utc::now() - TimeDelta::minutes(1),
+                    )
+                    .await
+                    {
+                        Ok(notified) => Some(notified),
                         Err(why) => {
                             error!("{why:#?}");
-                            continue;
+                            None
                         },
                     };
-                    if *last_weekend_id == 0 {
-                        *last_weekend_id = full_weekend.weekend.id;
-                    }
-                    if full_weekend.is_done() {
+                    let mut pending = Vec::new();
+                    for series in conf.active_series() {
+                        let role = conf.role(series);
+                        let channel = conf.channel(series);
+                        #[allow(unused)]
+                        let last_weekend_id = &mut last_weekend_ids[series.i8() as usize];
+                        let full_weekend = match fetch_next_full_weekend_for_series(
+                            db_conn.as_mut(),
+                            series,
+                        )
+                        .await
+                        {
+                            Ok(Some(d)) => d,
+                            Ok(None) => {
+                                let weekend_msg = match fetch_weekend_message_for_series(db_conn.as_mut(), series).await {
+                                    Ok(Some(msg)) => msg,
+                                    Ok(None) => continue,
+                                    Err(why) => {
+                                        error!("{why:#?}");
+                                        continue;
+                                    }
+                                };
+                                if let Err(why) = mark_message_expired(db_conn.as_mut(), weekend_msg.id, None).await {
+                                    error!("{why:#?}");
+                                }
+                                continue;
+                            },
+                            Err(why) => {
+                                error!("{why:#?}");
+                                alerts::report("Loading the next weekend", &why);
+                                continue;
+                            },
+                        };
+                        if *last_weekend_id == 0 {
+                            *last_weekend_id = full_weekend.weekend.id;
+                        }
+                        if switches::is_enabled(Component::Notifications)
+                            && !conf.is_mirror()
+                        {
+                            if let Err(why) = send_milestones(
+                                db_conn.as_mut(),
+                                &http,
+                                &conf.milestones,
+                                &full_weekend,
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                                alerts::report("Sending milestones", &why);
+                            }
+                            if let Err(why) = send_reminders(
+                                db_conn.as_mut(),
+                                &http,
+                                &conf.reminders,
+                                &full_weekend,
+                                channel,
+                                role,
+                                // shares the limit with the notifications
+                                || throttle.allow(series, Utc::now()),
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                                alerts::report("Sending reminders", &why);
+                            }
+                            if let Err(why) = send_lights_out(
+                                db_conn.as_mut(),
+                                &http,
+                                &conf.lights_out,
+                                &full_weekend,
+                                channel,
+                                role,
+                                || throttle.allow(series, Utc::now()),
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                                alerts::report("Sending lights out", &why);
+                            }
+                        }
+                        // production archives the weekend and marks it done,
+                        // a mirror moves on once it did
+                        if full_weekend.is_done() && !conf.is_mirror() {
+                            if let Err(why) = archive_weekend(
+                                db_conn.as_mut(),
+                                &http,
+                                &conf.archive,
+                                &full_weekend,
+                                &conf.display,
+                                &settings,
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                            }
                             if let Err(why) = mark_weekend_done(
                                 db_conn.as_mut(),
                                 &full_weekend.weekend,
@@ -161,181 +452,390 @@ impl EventHandler for Bot {
                             {
                                 error!("{why:#?}");
                             }
-                    }
+                        }
 
-                    match fetch_weekend_message_for_series(
-                        db_conn.as_mut(),
-                        series,
-                    )
-                    .await
-                    {
-                        Ok(Some(msg)) => {
-                            if let Some(hash) = msg.hash {
-                                let mut hasher = DefaultHasher::new();
-                                full_weekend.hash(&mut hasher);
-                                let new_hash = hasher.finish();
-                                if new_hash != hash.parse::<u64>().unwrap() {
-                                    if *last_weekend_id
-                                        != full_weekend.weekend.id
-                                    {
-                                        if let Err(why) = mark_message_expired(
+                        if switches::is_enabled(Component::WeekendMessages) {
+                            match fetch_weekend_message_for_series(
+                                db_conn.as_mut(),
+                                series,
+                            )
+                            .await
+                            {
+                                Ok(Some(msg)) => {
+                                    let new_hash = weekend_message_hash(
+                                        &full_weekend,
+                                        &conf.display,
+                                        &settings,
+                                    );
+                                    // missing or unreadable hashes count as changed,
+                                    // unchanged weekends are never rendered
+                                    let unchanged = msg
+                                        .hash
+                                        .as_ref()
+                                        .and_then(|f| f.parse::<u64>().ok())
+                                        == Some(new_hash);
+                                    if !unchanged {
+                                        if *last_weekend_id != full_weekend.weekend.id {
+                                            if let Err(why) = mark_message_expired(
+                                                db_conn.as_mut(),
+                                                msg.id,
+                                                None,
+                                            )
+                                            .await
+                                            {
+                                                error!("{why:#?}");
+                                            }
+                                            *last_weekend_id = full_weekend.weekend.id;
+                                            continue;
+                                        }
+                                        if let Err(why) =
+                                            update_weekend_message_if_changed(
+                                                db_conn.as_mut(),
+                                                &http,
+                                                &full_weekend,
+                                                &msg,
+                                                &conf.display,
+                                                &settings,
+                                            )
+                                            .await
+                                        {
+                                            error!("{why:#?}");
+                                            alerts::report(
+                                                "Updating the weekend message",
+                                                &why,
+                                            );
+                                        }
+                                        if let Err(why) = update_message_hash(
                                             db_conn.as_mut(),
                                             msg.id,
-                                            None,
+                                            new_hash,
                                         )
                                         .await
                                         {
                                             error!("{why:#?}");
                                         }
-                                        *last_weekend_id =
-                                            full_weekend.weekend.id;
-                                        continue;
                                     }
-                                    if let Err(why) = update_weekend_message(
+                                },
+                                Ok(None) => {
+                                    match post_weekend_message(
                                         &http,
                                         &full_weekend,
                                         channel,
-                                        msg.message.parse().unwrap(),
+                                        &conf.display,
+                                        &settings,
                                     )
                                     .await
                                     {
-                                        error!("{why:#?}");
-                                    }
-                                    if let Err(why) = update_message_hash(
-                                        db_conn.as_mut(),
-                                        msg.id,
-                                        new_hash,
-                                    )
-                                    .await
-                                    {
-                                        error!("{why:#?}");
-                                    }
-                                }
-                            } else {
-                                if *last_weekend_id != full_weekend.weekend.id {
-                                    if let Err(why) = mark_message_expired(
-                                        db_conn.as_mut(),
-                                        msg.id,
-                                        None,
-                                    )
-                                    .await
-                                    {
-                                        error!("{why:#?}");
+                                        Ok(msg) => {
+                                            if let Err(why) = insert_weekend_message(
+                                                db_conn.as_mut(),
+                                                channel,
+                                                msg.into(),
+                                                &full_weekend,
+                                                &conf.display,
+                                                &settings,
+                                            )
+                                            .await
+                                            {
+                                                error!("{why:#?}");
+                                            }
+                                            // Keep shared channels in series order by
+                                            // reposting the messages below this one.
+                                            for later in
+                                                conf.later_series_in_channel(series)
+                                            {
+                                                if let Err(why) =
+                                                mark_weekend_message_for_series_expired(
+                                                    db_conn.as_mut(),
+                                                    later,
+                                                )
+                                                .await
+                                                {
+                                                    error!("{why:#?}");
+                                                }
+                                            }
+                                        },
+                                        Err(why) => {
+                                            error!("{why:#?}");
+                                            alerts::report(
+                                                "Posting the weekend message",
+                                                &why,
+                                            );
+                                        },
                                     }
-                                    *last_weekend_id = full_weekend.weekend.id;
-                                    continue;
-                                }
-                                if let Err(why) = update_weekend_message(
+                                },
+                                Err(why) => {
+                                    error!("{why:#?}");
+                                },
+                            }
+                        }
+
+                        if !switches::is_enabled(Component::Notifications)
+                            || conf.is_mirror()
+                        {
+                            continue;
+                        }
+                        // without it sessions could be pinged twice
+                        let Some(notified) = notified.as_ref() else {
+                            continue;
+                        };
+                        if let Some(session) =
+                            full_weekend.upcoming_session(batch_window, notified)
+                        {
+                            pending.push(PendingNotification {
+                                series,
+                                weekend: full_weekend,
+                                session,
+                                channel,
+                                role,
+                            });
+                        }
+                    }
+
+                    for batch in batch_notifications(pending, batch_window) {
+                        let roles = throttle.roles(&batch, Utc::now());
+                        let entries = batch
+                            .iter()
+                            .zip(roles)
+                            .map(|(f, role)| {
+                                (&f.weekend.weekend, f.session(), role)
+                            })
+                            .collect::<Vec<_>>();
+                        let msg_id = match send_merged_notification(
+                            &http,
+                            &entries,
+                            batch[0].channel,
+                            cat,
+                            &conf.webhooks,
+                        )
+                        .await
+                        {
+                            Ok(d) => d,
+                            Err(why) => {
+                                error!("{why:#?}");
+                                alerts::report("Sending a notification", &why);
+                                continue;
+                            },
+                        };
+                        let sent = Utc::now();
+                        for (notification, (_, _, role)) in
+                            batch.iter().zip(entries.iter())
+                        {
+                            let series = notification.series;
+                            let channel = notification.channel;
+                            let full_weekend = &notification.weekend;
+                            let session = notification.session();
+                            // recorded first, a restart from here on must not
+                            // ping again
+                            if let Err(why) = mark_notification_sent(
+                                db_conn.as_mut(),
+                                session.id,
+                                NotificationStage::Start,
+                                msg_id.get(),
+                            )
+                            .await
+                            {
+                                error!("{why:#?}");
+                                alerts::report("Recording a notification", &why);
+                            }
+                            // only for notifications that actually went out
+                            EVENTS.publish(BotEvent::SessionStarting {
+                                series: series.to_string(),
+                                weekend: full_weekend.weekend.name.clone(),
+                                session: session.title.clone(),
+                                start: session.start_date,
+                            });
+                            // counted after sending, a cold count takes a while
+                            let audience = match *role {
+                                0 => None,
+                                role => audience::role_members(
                                     &http,
-                                    &full_weekend,
-                                    channel,
-                                    msg.message.parse().unwrap(),
+                                    conf.discord.guild,
+                                    role,
                                 )
                                 .await
-                                {
-                                    error!("{why:#?}");
-                                }
+                                .inspect_err(|why| error!("{why:#?}"))
+                                .ok(),
+                            };
+                            EVENTS.publish(BotEvent::NotificationSent {
+                                series: series.to_string(),
+                                session: session.id,
+                                channel,
+                                message: msg_id.get(),
+                            });
+                            if let Err(why) =
+                                mark_session_done(db_conn.as_mut(), session).await
+                            {
+                                error!("{why:#?}");
                             }
-                        },
-                        Ok(None) => {
-                            match post_weekend_message(
-                                &http,
-                                &full_weekend,
+                            if let Err(why) = create_new_notifications_msg_db(
+                                db_conn.as_mut(),
+                                session,
+                                series,
                                 channel,
+                                msg_id.into(),
                             )
                             .await
                             {
-                                Ok(msg) => {
-                                    if let Err(why) = insert_weekend_message(
-                                        db_conn.as_mut(),
-                                        channel,
-                                        msg.into(),
-                                        &full_weekend,
-                                    )
-                                    .await
-                                    {
-                                        error!("{why:#?}");
-                                    }
-                                },
-                                Err(why) => error!("{why:#?}"),
+                                error!("{why:#?}");
                             }
-                        },
-                        Err(why) => {
-                            error!("{why:#?}");
-                        },
-                    }
-
-                    let session = match full_weekend.next_session() {
-                        Some(s) => s,
-                        None => continue,
-                    };
-                    let msg_id = match send_notification(
-                        &http,
-                        &full_weekend.weekend,
-                        session,
-                        channel,
-                        cat,
-                        role,
-                    )
-                    .await
-                    {
-                        Ok(d) => d,
-                        Err(why) => {
-                            error!("{why:#?}");
-                            continue;
-                        },
-                    };
-                    if let Err(why) =
-                        mark_session_done(db_conn.as_mut(), session).await
-                    {
-                        error!("{why:#?}");
-                    }
-                    if let Err(why) = create_new_notifications_msg_db(
-                        db_conn.as_mut(),
-                        session,
-                        series,
-                        channel,
-                        msg_id.into(),
-                    )
-                    .await
-                    {
-                        error!("{why:#?}");
-                    }
-                    if full_weekend.check_is_done(session) {
-                            if let Err(why) = mark_weekend_done(
+                            if let Err(why) = log_notification(
                                 db_conn.as_mut(),
-                                &full_weekend.weekend,
+                                session,
+                                series,
+                                channel,
+                                msg_id.into(),
+                                sent,
+                                audience,
                             )
                             .await
                             {
                                 error!("{why:#?}");
-                                continue;
                             }
-                            if let Err(why) =
-                                mark_weekend_message_for_series_expired(
+                            if full_weekend.check_is_done(session) {
+                                if let Err(why) = archive_weekend(
                                     db_conn.as_mut(),
-                                    series,
+                                    &http,
+                                    &conf.archive,
+                                    full_weekend,
+                                    &conf.display,
+                                    &settings,
                                 )
                                 .await
-                            {
-                                error!("{why:#?}");
+                                {
+                                    error!("{why:#?}");
+                                }
+                                if let Err(why) = mark_weekend_done(
+                                    db_conn.as_mut(),
+                                    &full_weekend.weekend,
+                                )
+                                .await
+                                {
+                                    error!("{why:#?}");
+                                    continue;
+                                }
+                                if let Err(why) =
+                                    mark_weekend_message_for_series_expired(
+                                        db_conn.as_mut(),
+                                        series,
+                                    )
+                                    .await
+                                {
+                                    error!("{why:#?}");
+                                }
                             }
+                        }
                     }
                 }
+                .instrument(info_span!("tick"))
+                .await;
+                WORKER.record_tick(tick.elapsed().as_millis() as u64);
+                health::record_tick();
+                match schedule::next_wakeup(db_conn.as_mut(), conf, Utc::now())
+                    .await
+                {
+                    Ok(at) => wake_at = at,
+                    Err(why) => error!("{why:#?}"),
+                }
+                // resume right when the pause runs out
+                if let Some(until) = switches::paused_until() {
+                    wake_at = wake_at.min(until + schedule::RETRY);
+                }
+            }
+        });
+        // The panic itself was reported by the hook. Without the loop
+        // nothing gets posted anymore, so exit and leave the restart to the
+        // service manager.
+        tokio::spawn(async move {
+            if let Err(why) = bot_loop.await {
+                error!("The bot loop stopped: {why}");
+                crash::report(
+                    &conf.discord.bot_token,
+                    conf.discord.admin_channel,
+                    "The bot loop stopped, exiting.".to_owned(),
+                );
+                std::process::exit(1);
             }
         });
     }
 
+    /// Keeps the health checks up to date with the shard running the loop.
+    async fn shard_stage_update(
+        &self,
+        ctx: Context,
+        event: ShardStageUpdateEvent,
+    ) {
+        let shard_count = ctx.cache.shard_count();
+        let guild = GuildId::new(self.config.discord.guild);
+        if owns_guild(event.shard_id, shard_count, guild) {
+            health::set_connected(event.new == ConnectionStage::Connected);
+        }
+    }
+
+    async fn interaction_create(
+        &self,
+        ctx: Context,
+        interaction: Interaction,
+    ) {
+        match interaction {
+            Interaction::Command(command) => {
+                if let Err(why) =
+                    commands::run(&ctx, self.database, self.config, &command)
+                        .await
+                {
+                    error!("{why:#?}");
+                }
+            },
+            Interaction::Component(component)
+                if sync::is_sync_component(&component.data.custom_id) =>
+            {
+                if let Err(why) =
+                    sync::handle_component(&ctx, self.database, &component)
+                        .await
+                {
+                    error!("{why:#?}");
+                }
+            },
+            Interaction::Component(component)
+                if watchalong::is_watch_component(
+                    &component.data.custom_id,
+                ) =>
+            {
+                if let Err(why) = watchalong::handle_component(
+                    &ctx,
+                    self.database,
+                    &component,
+                )
+                .await
+                {
+                    error!("{why:#?}");
+                }
+            },
+            _ => {},
+        }
+    }
+
     async fn ready(
         &self,
-        _ctx: Context,
+        ctx: Context,
         ready: Ready,
     ) {
+        let shard_count = ready.shard.map_or(1, |f| f.total);
+        let guild = GuildId::new(self.config.discord.guild);
+        if owns_guild(ctx.shard_id, shard_count, guild) {
+            health::set_connected(true);
+        }
         let user = &ready.user;
         if let Some(discriminator) = user.discriminator {
             info!("Connected as {}#{}", user.name, discriminator);
         } else {
             info!("Connected to discord as {}", user.name);
         }
+
+        if let Err(why) =
+            guild.set_commands(&ctx.http, commands::register()).await
+        {
+            error!("Error registering commands: {why}");
+        }
     }
 }
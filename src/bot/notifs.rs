@@ -1 +1,138 @@
+use std::collections::{HashMap, VecDeque};
 
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::{Series, Session};
+
+use crate::util::{is_in_notify_window, FullWeekend};
+
+/// A session that is about to be announced.
+pub struct PendingNotification {
+    pub series: Series,
+    pub weekend: FullWeekend,
+    /// Index into `weekend.sessions`.
+    pub session: usize,
+    pub channel: u64,
+    pub role: u64,
+}
+
+impl PendingNotification {
+    pub fn session(&self) -> &Session {
+        &self.weekend.sessions[self.session]
+    }
+
+    /// Whether the session is inside the regular notification window.
+    pub fn is_due(&self) -> bool {
+        is_in_notify_window(
+            self.session().start_date,
+            Utc::now(),
+            TimeDelta::zero(),
+        )
+    }
+}
+
+/// Groups notifications per channel.
+///
+/// A channel only gets a message once one of its sessions is due, sessions
+/// starting within `window` of the latest due one are announced with it so
+/// simultaneous starts don't produce back-to-back pings.
+pub fn batch_notifications(
+    pending: Vec<PendingNotification>,
+    window: TimeDelta,
+) -> Vec<Vec<PendingNotification>> {
+    let mut batches: Vec<Vec<PendingNotification>> = Vec::new();
+    for notification in pending.into_iter() {
+        match batches.iter_mut().find(|f| f[0].channel == notification.channel)
+        {
+            Some(batch) => batch.push(notification),
+            None => batches.push(vec![notification]),
+        }
+    }
+
+    batches
+        .into_iter()
+        .filter_map(|mut batch| {
+            let latest_due = batch
+                .iter()
+                .filter(|f| f.is_due())
+                .map(|f| f.session().start_date)
+                .max()?;
+            batch.retain(|f| {
+                f.is_due() || f.session().start_date <= latest_due + window
+            });
+            batch.sort_by_key(|f| f.session().start_date);
+            Some(batch)
+        })
+        .collect()
+}
+
+/// Limits role pings to `max` per series within `window`, sessions past the
+/// limit are still announced, just without a mention.
+pub struct PingThrottle {
+    max: usize,
+    window: TimeDelta,
+    sent: HashMap<i8, VecDeque<DateTime<Utc>>>,
+}
+
+impl PingThrottle {
+    /// A `max` of 0 never throttles.
+    pub fn new(
+        max: u32,
+        window: TimeDelta,
+    ) -> Self {
+        Self {
+            max: max as usize,
+            window,
+            sent: HashMap::new(),
+        }
+    }
+
+    /// Whether `series` may ping at `now`, counting the ping if so.
+    pub fn allow(
+        &mut self,
+        series: Series,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if self.max == 0 {
+            return true;
+        }
+        let sent = self.sent.entry(series.i8()).or_default();
+        while sent.front().is_some_and(|f| now - *f >= self.window) {
+            sent.pop_front();
+        }
+        if sent.len() >= self.max {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+
+    /// The role to mention for each notification in `batch`, 0 for the
+    /// throttled ones. A series pinging for several sessions at once only
+    /// counts once.
+    pub fn roles(
+        &mut self,
+        batch: &[PendingNotification],
+        now: DateTime<Utc>,
+    ) -> Vec<u64> {
+        let mut allowed: Vec<(i8, bool)> = Vec::new();
+        batch
+            .iter()
+            .map(|f| {
+                let series = f.series.i8();
+                let allow = match allowed.iter().find(|a| a.0 == series) {
+                    Some(a) => a.1,
+                    None => {
+                        let allow = self.allow(f.series, now);
+                        allowed.push((series, allow));
+                        allow
+                    },
+                };
+                if allow {
+                    f.role
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,102 @@
+//! When the bot loop runs next.
+//!
+//! Instead of polling every few seconds the loop sleeps until the next
+//! moment something is due: a notification window opening, a reminder or
+//! milestone, lights out, a session ending or a message expiring. Writes
+//! through the bot wake it right away, everything else is picked up within
+//! `scheduler.max_idle`.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::Session;
+use sqlx::MySqlConnection;
+
+use crate::{
+    config::Config,
+    util::{
+        fetch_live_sessions, fetch_next_expiry,
+        fetch_sessions_starting_between, lookups, session_end, SessionDuration,
+        NOTIFY_WINDOW,
+    },
+};
+
+/// Added to every due time, the checks expect to run just after it.
+const SLACK: TimeDelta = TimeDelta::seconds(1);
+
+/// Sleep after a failed tick, so errors don't turn into a busy loop.
+pub const RETRY: TimeDelta = TimeDelta::seconds(5);
+
+/// Every offset before a session start the loop acts at: the notification
+/// window, reminders and milestones.
+fn leads(config: &Config<'_>) -> Vec<TimeDelta> {
+    let batch_window =
+        TimeDelta::seconds(config.notifications.batch_window as i64);
+    let mut leads = vec![NOTIFY_WINDOW + batch_window];
+    let minutes = config
+        .reminders
+        .iter()
+        .flat_map(|f| f.offsets.iter())
+        .chain(config.milestones.offsets.iter());
+    leads.extend(minutes.map(|f| TimeDelta::minutes(*f as i64)));
+    leads
+}
+
+/// Earliest moment after `now` one of `sessions` needs the loop.
+pub fn next_due(
+    config: &Config<'_>,
+    sessions: &[Session],
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let leads = leads(config);
+    let delays = config
+        .lights_out
+        .iter()
+        .map(|f| TimeDelta::seconds(f.delay as i64))
+        .collect::<Vec<_>>();
+    sessions
+        .iter()
+        .flat_map(|session| {
+            let start = session.start_date;
+            let end = session_end(start, SessionDuration::of(session));
+            leads
+                .iter()
+                .map(move |f| start - *f)
+                .chain(delays.iter().map(move |f| start + *f))
+                .chain([start, end])
+        })
+        .filter(|f| *f > now)
+        .min()
+}
+
+/// When the loop runs next, at most `scheduler.max_idle` from `now`.
+pub async fn next_wakeup(
+    db_conn: &mut MySqlConnection,
+    config: &Config<'_>,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    let idle = TimeDelta::seconds(config.scheduler.max_idle as i64);
+    let horizon = leads(config).into_iter().max().unwrap_or_default() + idle;
+    let mut sessions = fetch_live_sessions(db_conn, now).await?;
+    sessions.extend(
+        fetch_sessions_starting_between(db_conn, now, now + horizon).await?,
+    );
+    let expiry = fetch_next_expiry(db_conn).await?;
+    let due = [next_due(config, &sessions, now), expiry]
+        .into_iter()
+        .flatten()
+        .min()
+        .map(|f| f + SLACK);
+    Ok(due.map_or(now + idle, |f| f.min(now + idle)))
+}
+
+/// Sleeps until `at`, or until the bot writes something that may change
+/// what is due.
+pub async fn sleep_until(at: DateTime<Utc>) {
+    let wait =
+        at.signed_duration_since(Utc::now()).to_std().unwrap_or(Duration::ZERO);
+    tokio::select! {
+        _ = tokio::time::sleep(wait) => {},
+        _ = lookups::changed() => {},
+    }
+}
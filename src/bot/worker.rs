@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use serenity::all::{GuildId, ShardId};
+
+/// Counters of the bot loop, read by `/status`.
+#[derive(Debug)]
+pub struct WorkerStats {
+    shard: AtomicU32,
+    shard_count: AtomicU32,
+    ticks: AtomicU64,
+    last_tick_ms: AtomicU64,
+}
+
+pub static WORKER: WorkerStats = WorkerStats {
+    shard: AtomicU32::new(0),
+    shard_count: AtomicU32::new(0),
+    ticks: AtomicU64::new(0),
+    last_tick_ms: AtomicU64::new(0),
+};
+
+/// A copy of [WorkerStats] at one point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerSnapshot {
+    pub shard: u32,
+    /// 0 until the loop has started.
+    pub shard_count: u32,
+    pub ticks: u64,
+    pub last_tick_ms: u64,
+}
+
+impl WorkerStats {
+    pub fn pin(
+        &self,
+        shard: ShardId,
+        shard_count: u32,
+    ) {
+        self.shard.store(shard.0, Ordering::Relaxed);
+        self.shard_count.store(shard_count, Ordering::Relaxed);
+    }
+
+    pub fn record_tick(
+        &self,
+        millis: u64,
+    ) {
+        self.ticks.fetch_add(1, Ordering::Relaxed);
+        self.last_tick_ms.store(millis, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> WorkerSnapshot {
+        WorkerSnapshot {
+            shard: self.shard.load(Ordering::Relaxed),
+            shard_count: self.shard_count.load(Ordering::Relaxed),
+            ticks: self.ticks.load(Ordering::Relaxed),
+            last_tick_ms: self.last_tick_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Whether `shard` receives the events of `guild`, so only that shard runs
+/// the work for it.
+pub fn owns_guild(
+    shard: ShardId,
+    shard_count: u32,
+    guild: GuildId,
+) -> bool {
+    shard_count <= 1 || serenity::utils::shard_id(guild, shard_count) == shard.0
+}
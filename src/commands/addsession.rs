@@ -0,0 +1,184 @@
+use chrono::TimeDelta;
+use chrono_tz::Tz;
+use f1_bot_types::WeekendStatus;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    util::{
+        fetch_custom_kinds, fetch_full_weekend, insert_manual_session,
+        is_valid_kind, parse_start, KindCode, SessionDuration,
+    },
+};
+
+use super::{integer_option, respond_ephemeral, string_option};
+
+pub const NAME: &str = "addsession";
+
+/// Sessions starting further from the weekend's first day are most likely
+/// typos.
+const MAX_DAYS_INTO_WEEKEND: i64 = 7;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Add a session to a weekend")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "weekend",
+                "Id of the weekend",
+            )
+            .min_int_value(0)
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "kind",
+                "Stored kind value, see `/kind list` for custom ones",
+            )
+            .min_int_value(0)
+            .max_int_value(i8::MAX as u64)
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "date",
+                "Start as YYYY-MM-DD HH:MM, circuit time if the weekend has a \
+                 timezone",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "duration",
+                "Length in minutes",
+            )
+            .min_int_value(1)
+            .max_int_value(24 * 60)
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "title",
+                "Title, like `Free Practice 1`",
+            )
+            .max_length(255)
+            .required(true),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let (Some(weekend), Some(kind), Some(date), Some(duration), Some(title)) = (
+        integer_option(&options, "weekend"),
+        integer_option(&options, "kind"),
+        string_option(&options, "date"),
+        integer_option(&options, "duration"),
+        string_option(&options, "title").map(str::trim),
+    ) else {
+        return respond_ephemeral(ctx, command, "Missing options.").await;
+    };
+    if title.is_empty() {
+        return respond_ephemeral(ctx, command, "The title can't be empty.")
+            .await;
+    }
+    let duration = match SessionDuration::from_minutes(duration) {
+        Ok(duration) => duration,
+        Err(why) => {
+            return respond_ephemeral(
+                ctx,
+                command,
+                format!("Invalid duration, {why}."),
+            )
+            .await;
+        },
+    };
+
+    let mut db_conn = database.acquire().await?;
+    let Some(weekend) =
+        fetch_full_weekend(db_conn.as_mut(), weekend as u64).await?
+    else {
+        return respond_ephemeral(ctx, command, "No weekend with that id.")
+            .await;
+    };
+    if weekend.weekend.status == WeekendStatus::Done {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "That weekend is already over.",
+        )
+        .await;
+    }
+    let kind = kind as i8;
+    let custom = fetch_custom_kinds(db_conn.as_mut()).await?;
+    if !is_valid_kind(KindCode(kind), &custom) {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("`{kind}` isn't a known kind."),
+        )
+        .await;
+    }
+    let tz = weekend.tz().unwrap_or(Tz::UTC);
+    let Some(start_date) = parse_start(date, tz) else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("`{date}` is not a YYYY-MM-DD HH:MM time in `{tz}`."),
+        )
+        .await;
+    };
+    let offset = start_date.signed_duration_since(weekend.weekend.start_date);
+    if offset < TimeDelta::days(-1)
+        || offset > TimeDelta::days(MAX_DAYS_INTO_WEEKEND)
+    {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!(
+                "<t:{}:f> is too far from the start of {}.",
+                start_date.timestamp(),
+                weekend.weekend.name
+            ),
+        )
+        .await;
+    }
+
+    let id = insert_manual_session(
+        db_conn.as_mut(),
+        weekend.weekend.id,
+        kind,
+        title,
+        start_date,
+        duration,
+    )
+    .await?;
+    info!(
+        "{} added session {id} `{title}` to {}",
+        command.user.name, weekend.weekend.name
+    );
+    respond_ephemeral(
+        ctx,
+        command,
+        format!(
+            "Added {title} to {} at <t:{}:f> as session `{id}`.",
+            weekend.weekend.name,
+            start_date.timestamp()
+        ),
+    )
+    .await
+}
@@ -0,0 +1,123 @@
+use chrono_tz::Tz;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    util::{insert_weekend, parse_start, set_weekend_timezone},
+};
+
+use super::{respond_ephemeral, series_choice, series_option, string_option};
+
+pub const NAME: &str = "addweekend";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Add a weekend to the calendar")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(series_choice("series", "Series racing").required(true))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "name",
+                "Name of the weekend, like `Japanese Grand Prix`",
+            )
+            .max_length(255)
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "start",
+                "First day as YYYY-MM-DD, the year is taken from it",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "icon",
+                "Emoji in front of the name, a flag by default",
+            )
+            .max_length(64),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "timezone",
+            "IANA timezone of the circuit, like `Asia/Tokyo`",
+        ))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let (Some(series), Some(name), Some(start)) = (
+        series_option(&options, "series"),
+        string_option(&options, "name").map(str::trim),
+        string_option(&options, "start"),
+    ) else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "Missing series, name or start.",
+        )
+        .await;
+    };
+    if name.is_empty() {
+        return respond_ephemeral(ctx, command, "The name can't be empty.")
+            .await;
+    }
+    let icon = string_option(&options, "icon")
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .unwrap_or(":checkered_flag:");
+    let timezone = string_option(&options, "timezone");
+    let tz = match timezone {
+        None => Tz::UTC,
+        Some(timezone) => match timezone.parse::<Tz>() {
+            Ok(tz) => tz,
+            Err(_) => {
+                return respond_ephemeral(
+                    ctx,
+                    command,
+                    format!("`{timezone}` is not a known timezone."),
+                )
+                .await;
+            },
+        },
+    };
+    // a bare date is midnight at the circuit
+    let Some(start_date) = parse_start(start, tz) else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("`{start}` is not a YYYY-MM-DD date."),
+        )
+        .await;
+    };
+
+    let mut db_conn = database.acquire().await?;
+    let id = insert_weekend(db_conn.as_mut(), name, icon, series, start_date)
+        .await?;
+    if timezone.is_some() {
+        set_weekend_timezone(db_conn.as_mut(), id, timezone).await?;
+    }
+    info!("{} added {series} weekend {id} `{name}`", command.user.name);
+    respond_ephemeral(
+        ctx,
+        command,
+        format!(
+            "Added {icon} {name} ({series}) starting <t:{}:D> as weekend \
+             `{id}`, add its sessions with `/addsession`.",
+            start_date.timestamp()
+        ),
+    )
+    .await
+}
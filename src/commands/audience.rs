@@ -0,0 +1,49 @@
+use serenity::all::{
+    CommandInteraction, Context, CreateCommand, EditInteractionResponse,
+    Permissions,
+};
+
+use crate::{config::Config, error::Error, util::audience};
+
+use super::{respond_ephemeral, series_choice, series_option};
+
+pub const NAME: &str = "audience";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Show how many members a series ping reaches")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(series_choice("series", "Series to count").required(true))
+}
+
+pub async fn run(
+    ctx: &Context,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let Some(series) = series_option(&options, "series") else {
+        return respond_ephemeral(ctx, command, "Pick a series.").await;
+    };
+    let role = config.role(series);
+    if role == 0 {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("{series} notifications don't ping a role."),
+        )
+        .await;
+    }
+    // paging through the member list can take a while on a cold cache
+    command.defer_ephemeral(&ctx.http).await?;
+    let count = audience::role_members(ctx, config.discord.guild, role).await?;
+    command
+        .edit_response(
+            &ctx.http,
+            EditInteractionResponse::new().content(format!(
+                "{series} notifications ping <@&{role}>, {count} members."
+            )),
+        )
+        .await?;
+    Ok(())
+}
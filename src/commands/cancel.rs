@@ -0,0 +1,133 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    events::{BotEvent, EVENTS},
+    util::{cancel_sessions, fetch_full_weekend, fetch_session},
+};
+
+use super::{integer_option, respond_ephemeral, subcommand};
+
+pub const NAME: &str = "cancel";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description(
+            "Cancel sessions, they are struck through and not notified",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "session",
+                "Cancel a single session",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "session",
+                    "Id of the session",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "weekend",
+                "Cancel every remaining session of a weekend",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "weekend",
+                    "Id of the weekend",
+                )
+                .min_int_value(0)
+                .required(true),
+            ),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let mut db_conn = database.acquire().await?;
+
+    let (weekend, ids) = match name {
+        "session" => {
+            let Some(id) = integer_option(&options, "session") else {
+                return respond_ephemeral(ctx, command, "Missing session.")
+                    .await;
+            };
+            let Some(session) = fetch_session(db_conn.as_mut(), id).await?
+            else {
+                return respond_ephemeral(
+                    ctx,
+                    command,
+                    "No session with that id.",
+                )
+                .await;
+            };
+            (session.weekend as u64, vec![id])
+        },
+        _ => {
+            let Some(id) = integer_option(&options, "weekend") else {
+                return respond_ephemeral(ctx, command, "Missing weekend.")
+                    .await;
+            };
+            (id as u64, vec![])
+        },
+    };
+    let Some(weekend) = fetch_full_weekend(db_conn.as_mut(), weekend).await?
+    else {
+        return respond_ephemeral(ctx, command, "No weekend with that id.")
+            .await;
+    };
+    let ids = match ids.is_empty() {
+        true => weekend.sessions.iter().map(|f| f.id).collect(),
+        false => ids,
+    };
+
+    let cancelled = cancel_sessions(db_conn.as_mut(), &weekend, &ids).await?;
+    if cancelled.is_empty() {
+        return respond_ephemeral(ctx, command, "Nothing left to cancel.")
+            .await;
+    }
+    EVENTS.publish(BotEvent::ScheduleChanged {
+        weekend: weekend.weekend.id,
+        changes: weekend
+            .sessions
+            .iter()
+            .filter(|f| cancelled.contains(&f.id))
+            .map(|f| format!("{} cancelled", f.title))
+            .collect(),
+    });
+    info!(
+        "{} cancelled {} sessions of {}",
+        command.user.name,
+        cancelled.len(),
+        weekend.weekend.name
+    );
+
+    let mut content = "Cancelled:".to_owned();
+    for session in weekend.sessions.iter().filter(|f| cancelled.contains(&f.id))
+    {
+        content += &format!(
+            "\n> `{:>12}` ~~<t:{}:f>~~",
+            session.title,
+            session.start_date.timestamp()
+        );
+    }
+    respond_ephemeral(ctx, command, content).await
+}
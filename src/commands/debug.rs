@@ -0,0 +1,104 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{fetch_message_by_discord_id, fetch_message_history},
+};
+
+use super::{respond_ephemeral, string_option, subcommand};
+
+pub const NAME: &str = "debug";
+
+/// Discord refuses messages longer than this.
+const MAX_CONTENT: usize = 2000;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Look into what the bot did")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "message",
+                "Recent edits of a tracked message",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "message",
+                    "Id of the Discord message",
+                )
+                .required(true),
+            ),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some(("message", options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let Some(Ok(message)) =
+        string_option(&options, "message").map(|f| f.trim().parse::<u64>())
+    else {
+        return respond_ephemeral(ctx, command, "That's not a message id.")
+            .await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let Some(tracked) =
+        fetch_message_by_discord_id(db_conn.as_mut(), message).await?
+    else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("Message `{message}` isn't tracked."),
+        )
+        .await;
+    };
+    let history = fetch_message_history(db_conn.as_mut(), tracked.id).await?;
+
+    let mut content = format!("**History of message `{message}`**");
+    if history.is_empty() {
+        content += "\n*No edits recorded*";
+    }
+    // newest first, each entry shows what changed against the one before it
+    for (i, revision) in history.iter().enumerate() {
+        let previous = history.get(i + 1).map(|f| f.content.as_str());
+        let entry = format!(
+            "\n<t:{}:f> hash `{:016x}`\n```diff\n{}```",
+            revision.created.timestamp(),
+            revision.hash,
+            line_diff(previous.unwrap_or_default(), &revision.content)
+        );
+        if content.len() + entry.len() > MAX_CONTENT {
+            break;
+        }
+        content += &entry;
+    }
+    respond_ephemeral(ctx, command, content).await
+}
+
+/// Lines only in `old` prefixed with `-`, lines only in `new` with `+`.
+fn line_diff(
+    old: &str,
+    new: &str,
+) -> String {
+    let mut diff = String::new();
+    for line in old.lines().filter(|f| !new.lines().any(|n| n == *f)) {
+        diff += &format!("- {}\n", line.replace("```", "'''"));
+    }
+    for line in new.lines().filter(|f| !old.lines().any(|o| o == *f)) {
+        diff += &format!("+ {}\n", line.replace("```", "'''"));
+    }
+    if diff.is_empty() {
+        diff += "  (no visible change)\n";
+    }
+    diff
+}
@@ -0,0 +1,137 @@
+use f1_bot_types::SessionStatus;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    events::{BotEvent, EVENTS},
+    util::{delay_session, fetch_full_weekend, fetch_session},
+};
+
+use super::{
+    boolean_option, integer_option, respond_ephemeral, series_choice,
+    series_option,
+};
+
+pub const NAME: &str = "delay";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Push a session back in time")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            series_choice("series", "Series the session belongs to")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "session",
+                "Id of the session",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "minutes",
+                "How far to push the session back",
+            )
+            .min_int_value(1)
+            .max_int_value(24 * 60)
+            .required(true),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "cascade",
+            "Also move the later sessions of that day",
+        ))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let (Some(series), Some(id), Some(minutes)) = (
+        series_option(&options, "series"),
+        integer_option(&options, "session"),
+        integer_option(&options, "minutes"),
+    ) else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "Missing series, session or minutes.",
+        )
+        .await;
+    };
+    let cascade = boolean_option(&options, "cascade").unwrap_or(false);
+
+    let mut db_conn = database.acquire().await?;
+    let Some(session) = fetch_session(db_conn.as_mut(), id).await? else {
+        return respond_ephemeral(ctx, command, "No session with that id.")
+            .await;
+    };
+    let Some(weekend) =
+        fetch_full_weekend(db_conn.as_mut(), session.weekend as u64).await?
+    else {
+        return respond_ephemeral(ctx, command, "The session has no weekend.")
+            .await;
+    };
+    // guards against delaying the right id in the wrong series
+    if weekend.weekend.series.i8() != series.i8() {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!(
+                "Session `{id}` belongs to {}, not {series}.",
+                weekend.weekend.series
+            ),
+        )
+        .await;
+    }
+
+    if !matches!(session.status, SessionStatus::Open | SessionStatus::Delayed) {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("`{}` is already over or cancelled.", session.title),
+        )
+        .await;
+    }
+
+    let moved =
+        delay_session(db_conn.as_mut(), &weekend, &session, minutes, cascade)
+            .await?;
+    EVENTS.publish(BotEvent::ScheduleChanged {
+        weekend: weekend.weekend.id,
+        changes: weekend
+            .sessions
+            .iter()
+            .filter(|f| moved.contains(&f.id))
+            .map(|f| format!("{} delayed by {minutes}m", f.title))
+            .collect(),
+    });
+    info!(
+        "{} delayed {} sessions of {} by {minutes}m",
+        command.user.name,
+        moved.len(),
+        weekend.weekend.name
+    );
+
+    let mut content = format!("Delayed by {minutes} minutes:");
+    for moved in weekend.sessions.iter().filter(|f| moved.contains(&f.id)) {
+        content += &format!(
+            "\n> `{:>12}` <t:{}:f>",
+            moved.title,
+            (moved.start_date + chrono::TimeDelta::minutes(minutes))
+                .timestamp()
+        );
+    }
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,77 @@
+use chrono::{Datelike, Utc};
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{fetch_session_engagement_for_year, names::ALL_SERIES},
+};
+
+use super::{integer_option, respond_ephemeral};
+
+pub const NAME: &str = "engagement";
+
+/// How many sessions the report lists.
+const TOP_SESSIONS: usize = 10;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Show which sessions got the most reactions and replies")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "year",
+            "Season to report on, defaults to the current one",
+        ))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let year = integer_option(&command.data.options(), "year")
+        .map(|f| f as i32)
+        .unwrap_or(Utc::now().year());
+    let mut db_conn = database.acquire().await?;
+    let engagement =
+        fetch_session_engagement_for_year(db_conn.as_mut(), year).await?;
+    if engagement.is_empty() {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("No engagement recorded in {year}."),
+        )
+        .await;
+    }
+
+    let mut content = format!("**Engagement {year}**");
+    for series in ALL_SERIES {
+        let (sessions, reactions, replies) = engagement
+            .iter()
+            .filter(|f| f.series == series)
+            .fold((0, 0, 0), |(s, r, t), f| {
+                (s + 1, r + f.reactions, t + f.thread_messages)
+            });
+        if sessions > 0 {
+            content += &format!(
+                "\n> **{series}**: {reactions} reactions, {replies} thread messages over {sessions} sessions"
+            );
+        }
+    }
+
+    content += "\n**Top sessions**";
+    for entry in engagement.iter().take(TOP_SESSIONS) {
+        content += &format!(
+            "\n> {} {}: {} reactions, {} thread messages",
+            entry.weekend_name,
+            entry.title,
+            entry.reactions,
+            entry.thread_messages
+        );
+    }
+    respond_ephemeral(ctx, command, content).await
+}
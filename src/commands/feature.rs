@@ -0,0 +1,103 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::warn;
+
+use crate::{
+    error::Error,
+    util::switches::{self, Component},
+};
+
+use super::{respond_ephemeral, string_option, subcommand};
+
+pub const NAME: &str = "feature";
+
+fn component_option() -> CreateCommandOption {
+    Component::ALL.into_iter().fold(
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "component",
+            "Part of the bot",
+        )
+        .required(true),
+        |option, f| option.add_string_choice(f.name(), f.name()),
+    )
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Pause or resume parts of the bot")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "disable",
+                "Pause a component until it is enabled again",
+            )
+            .add_sub_option(component_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "enable",
+                "Resume a paused component",
+            )
+            .add_sub_option(component_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "List the paused components",
+        ))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let mut db_conn = database.acquire().await?;
+
+    let content = match name {
+        "disable" | "enable" => {
+            let Some(component) = string_option(&options, "component")
+                .and_then(Component::from_name)
+            else {
+                return respond_ephemeral(ctx, command, "Unknown component.")
+                    .await;
+            };
+            let enabled = name == "enable";
+            switches::set_enabled(
+                db_conn.as_mut(),
+                component,
+                enabled,
+                &command.user.name,
+            )
+            .await?;
+            warn!("{} {name}d {}", command.user.name, component.name());
+            format!("`{}` is now {name}d.", component.name())
+        },
+        _ => {
+            let disabled = switches::fetch_disabled(db_conn.as_mut()).await?;
+            let mut content = "**Paused components**".to_owned();
+            if disabled.is_empty() {
+                content += "\n*None*";
+            }
+            for f in disabled.iter() {
+                content += &format!(
+                    "\n> `{}` by {} <t:{}:R>",
+                    f.name,
+                    f.disabled_by,
+                    f.disabled_at.timestamp()
+                );
+            }
+            content
+        },
+    };
+    respond_ephemeral(ctx, command, content).await
+}
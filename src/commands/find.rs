@@ -0,0 +1,78 @@
+use f1_bot_types::WeekendStatus;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{search, search_weekends},
+};
+
+use super::{respond_ephemeral, string_option};
+
+pub const NAME: &str = "find";
+
+/// Candidates loaded from the database before ranking.
+const CANDIDATES: u32 = 100;
+/// Weekends shown in the reply.
+const RESULTS: usize = 10;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME).description("Search weekends by name").add_option(
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "query",
+            "Part of the name, like `vegas`",
+        )
+        .min_length(2)
+        .max_length(64)
+        .required(true),
+    )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let Some(query) = string_option(&options, "query") else {
+        return respond_ephemeral(ctx, command, "Missing query.").await;
+    };
+
+    let mut db_conn = database.acquire().await?;
+    let candidates = search_weekends(
+        db_conn.as_mut(),
+        &search::like_pattern(query),
+        CANDIDATES,
+    )
+    .await?;
+    let mut ranked = candidates
+        .into_iter()
+        .filter_map(|f| Some((search::score(query, &f.name)?, f)))
+        .collect::<Vec<_>>();
+    // stable, equally good matches stay newest first
+    ranked.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut content = format!("**Weekends matching `{query}`**");
+    if ranked.is_empty() {
+        content += "\n*None*";
+    }
+    for (_, weekend) in ranked.iter().take(RESULTS) {
+        content += &format!(
+            "\n> `{}` {} {} ({}) <t:{}:D>{}",
+            weekend.id,
+            weekend.icon,
+            weekend.name,
+            weekend.series,
+            weekend.start_date.timestamp(),
+            match weekend.status {
+                WeekendStatus::Done => " *done*",
+                _ => "",
+            }
+        );
+    }
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,61 @@
+use serenity::all::{
+    CommandInteraction, Context, CreateCommand, EditInteractionResponse,
+    Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{config::Config, error::Error, ingest::ics};
+
+use super::respond_ephemeral;
+
+pub const NAME: &str = "import";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Import the configured ICS calendars now")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    if config.ics.is_empty() {
+        return respond_ephemeral(ctx, command, "No ICS calendars configured.")
+            .await;
+    }
+    // downloads can take longer than Discord waits for a reply
+    command.defer_ephemeral(&ctx.http).await?;
+    info!("{} started an ICS import", command.user.name);
+
+    let mut db_conn = database.acquire().await?;
+    let mut report = String::new();
+    for source in config.ics.iter() {
+        let line =
+            match ics::import(db_conn.as_mut(), ctx, config, source).await {
+                Ok(synced) => format!(
+                    "`{}` ({}): {synced} weekends synced",
+                    source.location, source.series
+                ),
+                Err(why) => {
+                    format!(
+                        "`{}` ({}): failed, {why}",
+                        source.location, source.series
+                    )
+                },
+            };
+        report.push_str(&line);
+        report.push('\n');
+    }
+
+    command
+        .edit_response(
+            &ctx.http,
+            EditInteractionResponse::new().content(report),
+        )
+        .await?;
+    Ok(())
+}
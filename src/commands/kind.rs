@@ -0,0 +1,101 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{fetch_custom_kinds, insert_custom_kind, kinds::set_low_priority},
+};
+
+use super::{boolean_option, respond_ephemeral, string_option, subcommand};
+
+pub const NAME: &str = "kind";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Manage custom session kinds")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Register a session kind, like a shakedown or media day",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "name",
+                    "Name of the kind",
+                )
+                .max_length(64)
+                .required(true),
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "hidden",
+                "Leave it out of weekend messages unless the server is verbose",
+            )),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "List the custom session kinds",
+        ))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let mut db_conn = database.acquire().await?;
+    let kinds = fetch_custom_kinds(db_conn.as_mut()).await?;
+
+    let content = match name {
+        "add" => {
+            let name = string_option(&options, "name").unwrap_or_default();
+            let hidden = boolean_option(&options, "hidden").unwrap_or(false);
+            if kinds.iter().any(|f| f.name.eq_ignore_ascii_case(name)) {
+                format!("`{name}` already exists.")
+            } else {
+                match insert_custom_kind(db_conn.as_mut(), name, hidden).await {
+                    Ok(code) => {
+                        set_low_priority(
+                            &fetch_custom_kinds(db_conn.as_mut()).await?,
+                        );
+                        format!("Registered `{name}` as kind `{code}`.")
+                    },
+                    Err(sqlx::Error::RowNotFound) => {
+                        "No free kind values are left.".to_owned()
+                    },
+                    Err(why) => return Err(why.into()),
+                }
+            }
+        },
+        _ => {
+            let mut content = "**Custom session kinds**".to_owned();
+            if kinds.is_empty() {
+                content += "\n*None*";
+            }
+            for kind in kinds.iter() {
+                content += &format!(
+                    "\n> `{}` {}{}",
+                    kind.code,
+                    kind.name,
+                    if kind.low_priority {
+                        " *(hidden)*"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            content
+        },
+    };
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,145 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{
+        delete_weekend_link, fetch_weekend, fetch_weekend_links,
+        insert_weekend_link,
+    },
+};
+
+use super::{integer_option, respond_ephemeral, string_option, subcommand};
+
+pub const NAME: &str = "links";
+
+/// Discord allows 25 buttons on a message.
+const MAX_LINKS: usize = 25;
+
+fn weekend_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "weekend",
+        "Id of the weekend",
+    )
+    .required(true)
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Manage the links shown under a weekend message")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Attach a link to a weekend",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "label",
+                    "Button label",
+                )
+                .max_length(80)
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "url",
+                    "Http(s) link",
+                )
+                .max_length(512)
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "remove",
+                "Remove a link from a weekend",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "label",
+                    "Label of the link",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "list",
+                "List the links of a weekend",
+            )
+            .add_sub_option(weekend_option()),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let Some(weekend) = integer_option(&options, "weekend") else {
+        return respond_ephemeral(ctx, command, "Missing weekend id.").await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let Some(weekend) = fetch_weekend(db_conn.as_mut(), weekend as u64).await?
+    else {
+        return respond_ephemeral(ctx, command, "No weekend with that id.")
+            .await;
+    };
+    let links = fetch_weekend_links(db_conn.as_mut(), weekend.id).await?;
+
+    let content = match name {
+        "add" => {
+            let label = string_option(&options, "label").unwrap_or_default();
+            let url = string_option(&options, "url").unwrap_or_default();
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                "Links have to start with `http://` or `https://`.".to_owned()
+            } else if links.len() >= MAX_LINKS {
+                format!("A weekend can have at most {MAX_LINKS} links.")
+            } else if links.iter().any(|f| f.label == label) {
+                format!("`{label}` already exists, remove it first.")
+            } else {
+                insert_weekend_link(db_conn.as_mut(), weekend.id, label, url)
+                    .await?;
+                format!("Added `{label}` to {}.", weekend.name)
+            }
+        },
+        "remove" => {
+            let label = string_option(&options, "label").unwrap_or_default();
+            match delete_weekend_link(db_conn.as_mut(), weekend.id, label).await
+            {
+                Ok(()) => format!("Removed `{label}` from {}.", weekend.name),
+                Err(sqlx::Error::RowNotFound) => {
+                    format!("{} has no link called `{label}`.", weekend.name)
+                },
+                Err(why) => return Err(why.into()),
+            }
+        },
+        _ => {
+            let mut content = format!("**Links for {}**", weekend.name);
+            if links.is_empty() {
+                content += "\n*None*";
+            }
+            for link in links.iter() {
+                content += &format!("\n> `{}` <{}>", link.label, link.url);
+            }
+            content
+        },
+    };
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,219 @@
+pub mod addsession;
+pub mod addweekend;
+pub mod audience;
+pub mod cancel;
+pub mod debug;
+pub mod delay;
+pub mod engagement;
+pub mod feature;
+pub mod find;
+#[cfg(feature = "ics")]
+pub mod import;
+pub mod kind;
+pub mod links;
+pub mod nextsession;
+pub mod pause;
+pub mod penalty;
+pub mod quarantine;
+pub mod release;
+pub mod schedule;
+pub mod selftest;
+pub mod settings;
+pub mod stats;
+pub mod status;
+pub mod submit_link;
+pub mod until;
+pub mod weekend;
+
+use f1_bot_types::Series;
+use serenity::all::{
+    Attachment, CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, CreateInteractionResponse,
+    CreateInteractionResponseMessage, ResolvedOption, ResolvedValue,
+};
+use sqlx::MySqlPool;
+
+use crate::{config::Config, error::Error};
+
+/// All slash commands the bot registers in its guild.
+pub fn register() -> Vec<CreateCommand> {
+    vec![
+        addsession::register(),
+        addweekend::register(),
+        audience::register(),
+        cancel::register(),
+        debug::register(),
+        delay::register(),
+        engagement::register(),
+        feature::register(),
+        find::register(),
+        #[cfg(feature = "ics")]
+        import::register(),
+        kind::register(),
+        links::register(),
+        nextsession::register(),
+        pause::register(),
+        penalty::register(),
+        quarantine::register(),
+        release::register(),
+        schedule::register(),
+        selftest::register(),
+        settings::register(),
+        stats::register(),
+        status::register(),
+        submit_link::register(),
+        until::register(),
+        weekend::register(),
+    ]
+}
+
+/// Routes a slash command to its module.
+///
+/// Commands that fail before replying still get an answer, so the invoking
+/// user isn't left with "The application did not respond".
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let result = dispatch(ctx, database, config, command).await;
+    if result.is_err() {
+        // fails if the command already replied, which is fine
+        let _ = respond_ephemeral(
+            ctx,
+            command,
+            "Something went wrong, the error has been logged.",
+        )
+        .await;
+    }
+    result
+}
+
+async fn dispatch(
+    ctx: &Context,
+    database: &MySqlPool,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    match command.data.name.as_str() {
+        addsession::NAME => addsession::run(ctx, database, command).await,
+        addweekend::NAME => addweekend::run(ctx, database, command).await,
+        audience::NAME => audience::run(ctx, config, command).await,
+        cancel::NAME => cancel::run(ctx, database, command).await,
+        debug::NAME => debug::run(ctx, database, command).await,
+        delay::NAME => delay::run(ctx, database, command).await,
+        engagement::NAME => engagement::run(ctx, database, command).await,
+        feature::NAME => feature::run(ctx, database, command).await,
+        find::NAME => find::run(ctx, database, command).await,
+        #[cfg(feature = "ics")]
+        import::NAME => import::run(ctx, database, config, command).await,
+        kind::NAME => kind::run(ctx, database, command).await,
+        links::NAME => links::run(ctx, database, command).await,
+        nextsession::NAME => nextsession::run(ctx, database, command).await,
+        pause::NAME => pause::run(ctx, database, config, command).await,
+        penalty::NAME => penalty::run(ctx, database, command).await,
+        quarantine::NAME => quarantine::run(ctx, database, command).await,
+        release::NAME => release::run(ctx, database, command).await,
+        schedule::NAME => schedule::run(ctx, database, config, command).await,
+        selftest::NAME => selftest::run(ctx, config, command).await,
+        settings::NAME => settings::run(ctx, database, command).await,
+        stats::NAME => stats::run(ctx, database, command).await,
+        status::NAME => status::run(ctx, database, command).await,
+        submit_link::NAME => {
+            submit_link::run(ctx, database, config, command).await
+        },
+        until::NAME => until::run(ctx, database, command).await,
+        weekend::NAME => weekend::run(ctx, database, command).await,
+        _ => Ok(()),
+    }
+}
+
+/// Replies to a command with a message only the invoking user can see.
+pub async fn respond_ephemeral(
+    ctx: &Context,
+    command: &CommandInteraction,
+    content: impl Into<String>,
+) -> Result<(), Error> {
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+/// The name and options of the invoked subcommand.
+pub fn subcommand<'a>(
+    command: &'a CommandInteraction
+) -> Option<(&'a str, Vec<ResolvedOption<'a>>)> {
+    command.data.options().into_iter().find_map(|f| match f.value {
+        ResolvedValue::SubCommand(options) => Some((f.name, options)),
+        _ => None,
+    })
+}
+
+pub fn integer_option(
+    options: &[ResolvedOption<'_>],
+    name: &str,
+) -> Option<i64> {
+    options.iter().find_map(|f| match f.value {
+        ResolvedValue::Integer(value) if f.name == name => Some(value),
+        _ => None,
+    })
+}
+
+/// An integer option choosing one [Series], read with [series_option].
+pub fn series_choice(
+    name: &str,
+    description: &str,
+) -> CreateCommandOption {
+    (Series::F1.i8()..=Series::F1Academy.i8()).fold(
+        CreateCommandOption::new(CommandOptionType::Integer, name, description),
+        |option, val| {
+            option.add_int_choice(Series::from(val).to_string(), val as i32)
+        },
+    )
+}
+
+pub fn series_option(
+    options: &[ResolvedOption<'_>],
+    name: &str,
+) -> Option<Series> {
+    integer_option(options, name).map(|f| Series::from(f as i8))
+}
+
+pub fn string_option<'a>(
+    options: &[ResolvedOption<'a>],
+    name: &str,
+) -> Option<&'a str> {
+    options.iter().find_map(|f| match f.value {
+        ResolvedValue::String(value) if f.name == name => Some(value),
+        _ => None,
+    })
+}
+
+pub fn boolean_option(
+    options: &[ResolvedOption<'_>],
+    name: &str,
+) -> Option<bool> {
+    options.iter().find_map(|f| match f.value {
+        ResolvedValue::Boolean(value) if f.name == name => Some(value),
+        _ => None,
+    })
+}
+
+pub fn attachment_option<'a>(
+    options: &[ResolvedOption<'a>],
+    name: &str,
+) -> Option<&'a Attachment> {
+    options.iter().find_map(|f| match f.value {
+        ResolvedValue::Attachment(value) if f.name == name => Some(value),
+        _ => None,
+    })
+}
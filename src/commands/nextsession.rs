@@ -0,0 +1,65 @@
+use chrono::Utc;
+use serenity::all::{CommandInteraction, Context, CreateCommand};
+use sqlx::MySqlPool;
+
+use crate::{error::Error, next::next_events};
+
+use super::{respond_ephemeral, series_choice, series_option};
+
+pub const NAME: &str = "nextsession";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Show the next session")
+        .add_option(series_choice("series", "Only look at this series"))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let series = series_option(&options, "series").map(|f| f.to_string());
+
+    let mut db_conn = database.acquire().await?;
+    let events = next_events(db_conn.as_mut(), Utc::now()).await?;
+    // soonest first, live sessions count as next
+    let Some(event) = events
+        .iter()
+        .find(|f| series.as_ref().is_none_or(|series| *series == f.series))
+    else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            match series {
+                Some(series) => format!("No {series} session is scheduled."),
+                None => "No session is scheduled.".to_owned(),
+            },
+        )
+        .await;
+    };
+
+    let when = match event.live {
+        true => "is live, started".to_owned(),
+        false => "starts".to_owned(),
+    };
+    let provisional = match event.provisional {
+        true => " (dates not confirmed)",
+        false => "",
+    };
+    respond_ephemeral(
+        ctx,
+        command,
+        format!(
+            "{} {} {}: {} {when} <t:{}:R> (<t:{}:f>){provisional}",
+            event.icon,
+            event.series,
+            event.weekend,
+            event.session,
+            event.start.timestamp(),
+            event.start.timestamp()
+        ),
+    )
+    .await
+}
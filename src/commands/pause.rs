@@ -0,0 +1,112 @@
+use chrono::{TimeDelta, Utc};
+use serenity::all::{
+    ChannelId, CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::{error, warn};
+
+use crate::{config::Config, error::Error, util::switches};
+
+use super::{respond_ephemeral, string_option};
+
+pub const NAME: &str = "pause";
+
+/// Longest pause, anything longer is more likely a typo.
+const MAX_PAUSE: TimeDelta = TimeDelta::days(7);
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Stop everything the bot posts for a while")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "duration",
+                "Like 30m, 2h or 1d, `off` resumes right away",
+            )
+            .required(true),
+        )
+}
+
+/// `30m`, `2h` or `1d`, bare numbers are minutes.
+pub fn parse_duration(input: &str) -> Option<TimeDelta> {
+    let input = input.trim().to_ascii_lowercase();
+    let (number, unit) = match input.find(|f: char| !f.is_ascii_digit()) {
+        Some(at) => input.split_at(at),
+        None => (input.as_str(), "m"),
+    };
+    let number = number.parse::<i64>().ok()?;
+    let duration = match unit.trim() {
+        "m" | "min" => TimeDelta::try_minutes(number)?,
+        "h" => TimeDelta::try_hours(number)?,
+        "d" => TimeDelta::try_days(number)?,
+        _ => return None,
+    };
+    (duration > TimeDelta::zero()).then_some(duration)
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let input = string_option(&options, "duration").unwrap_or_default();
+    let mut db_conn = database.acquire().await?;
+
+    let (content, notice) = if input.trim().eq_ignore_ascii_case("off") {
+        if !switches::resume(db_conn.as_mut()).await? {
+            return respond_ephemeral(ctx, command, "The bot isn't paused.")
+                .await;
+        }
+        warn!("{} resumed the bot", command.user.name);
+        (
+            "Resumed, the bot posts again.".to_owned(),
+            format!("▶️ {} resumed the bot.", command.user.name),
+        )
+    } else {
+        let Some(duration) = parse_duration(input) else {
+            return respond_ephemeral(
+                ctx,
+                command,
+                format!("`{input}` is not a duration like 30m, 2h or 1d."),
+            )
+            .await;
+        };
+        if duration > MAX_PAUSE {
+            return respond_ephemeral(
+                ctx,
+                command,
+                format!(
+                    "Pauses end after {} days at most.",
+                    MAX_PAUSE.num_days()
+                ),
+            )
+            .await;
+        }
+        let until = Utc::now() + duration;
+        switches::pause(db_conn.as_mut(), until).await?;
+        warn!("{} paused the bot until {until}", command.user.name);
+        let until = until.timestamp();
+        (
+            format!(
+                "Paused until <t:{until}:f>, `/pause off` resumes earlier."
+            ),
+            format!(
+                "⏸️ {} paused the bot until <t:{until}:f> (<t:{until}:R>).",
+                command.user.name
+            ),
+        )
+    };
+    let admin_channel = config.discord.admin_channel;
+    if admin_channel != 0 {
+        if let Err(why) =
+            ChannelId::new(admin_channel).say(&ctx.http, notice).await
+        {
+            error!("{why:#?}");
+        }
+    }
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,159 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{
+        delete_grid_penalty, fetch_grid_penalties, fetch_weekend,
+        insert_grid_penalty,
+    },
+};
+
+use super::{integer_option, respond_ephemeral, string_option, subcommand};
+
+pub const NAME: &str = "penalty";
+
+fn weekend_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "weekend",
+        "Id of the weekend",
+    )
+    .required(true)
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Manage the grid penalties shown under the race")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Add a grid penalty for the race of a weekend",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "driver",
+                    "Driver receiving the penalty",
+                )
+                .max_length(64)
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "penalty",
+                    "Like `5 places` or `back of the grid`",
+                )
+                .max_length(64)
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "reason",
+                    "Why the penalty was given",
+                )
+                .max_length(255),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "remove",
+                "Remove a grid penalty",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "id",
+                    "Id of the penalty, see `/penalty list`",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "list",
+                "List the grid penalties of a weekend",
+            )
+            .add_sub_option(weekend_option()),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let Some(weekend) = integer_option(&options, "weekend") else {
+        return respond_ephemeral(ctx, command, "Missing weekend id.").await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let Some(weekend) = fetch_weekend(db_conn.as_mut(), weekend as u64).await?
+    else {
+        return respond_ephemeral(ctx, command, "No weekend with that id.")
+            .await;
+    };
+
+    let content = match name {
+        "add" => {
+            let driver = string_option(&options, "driver").unwrap_or_default();
+            let penalty =
+                string_option(&options, "penalty").unwrap_or_default();
+            let reason = string_option(&options, "reason");
+            let id = insert_grid_penalty(
+                db_conn.as_mut(),
+                weekend.id,
+                driver,
+                penalty,
+                reason,
+            )
+            .await?;
+            format!("Added `{id}` {driver}: {penalty} to {}.", weekend.name)
+        },
+        "remove" => {
+            let id = integer_option(&options, "id").unwrap_or_default();
+            match delete_grid_penalty(db_conn.as_mut(), weekend.id, id as u64)
+                .await
+            {
+                Ok(()) => format!("Removed `{id}` from {}.", weekend.name),
+                Err(sqlx::Error::RowNotFound) => {
+                    format!("{} has no penalty `{id}`.", weekend.name)
+                },
+                Err(why) => return Err(why.into()),
+            }
+        },
+        _ => {
+            let penalties =
+                fetch_grid_penalties(db_conn.as_mut(), weekend.id).await?;
+            let mut content =
+                format!("**Grid penalties for {}**", weekend.name);
+            if penalties.is_empty() {
+                content += "\n*None*";
+            }
+            for penalty in penalties.iter() {
+                content += &format!(
+                    "\n> `{}` {}: {}",
+                    penalty.id, penalty.driver, penalty.penalty
+                );
+                if let Some(reason) = &penalty.reason {
+                    content += &format!(" ({reason})");
+                }
+            }
+            content
+        },
+    };
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,112 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    util::{
+        fetch_custom_kinds, is_valid_kind, quarantine, set_session_kind,
+        KindCode,
+    },
+};
+
+use super::{integer_option, respond_ephemeral, subcommand};
+
+pub const NAME: &str = "quarantine";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Sessions skipped because of an unknown kind")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "List the quarantined sessions",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "fix",
+                "Give a quarantined session a valid kind",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "session",
+                    "Id of the session",
+                )
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "kind",
+                    "Stored kind value, see `/kind list` for custom ones",
+                )
+                .min_int_value(0)
+                .max_int_value(i8::MAX as u64)
+                .required(true),
+            ),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let mut db_conn = database.acquire().await?;
+
+    let content = match name {
+        "fix" => {
+            let (Some(session), Some(kind)) = (
+                integer_option(&options, "session"),
+                integer_option(&options, "kind"),
+            ) else {
+                return respond_ephemeral(ctx, command, "Missing options.")
+                    .await;
+            };
+            let kind = kind as i8;
+            let custom = fetch_custom_kinds(db_conn.as_mut()).await?;
+            if !is_valid_kind(KindCode(kind), &custom) {
+                format!("`{kind}` isn't a known kind either.")
+            } else {
+                match set_session_kind(db_conn.as_mut(), session, kind).await {
+                    Ok(()) => {
+                        quarantine::release(session);
+                        info!(
+                            "{} set session {session} to kind {kind}",
+                            command.user.name
+                        );
+                        format!("Session `{session}` now has kind `{kind}`.")
+                    },
+                    Err(sqlx::Error::RowNotFound) => {
+                        format!("No session `{session}`.")
+                    },
+                    Err(why) => return Err(why.into()),
+                }
+            }
+        },
+        _ => {
+            let sessions = quarantine::scan(db_conn.as_mut()).await?;
+            let mut content = "**Quarantined sessions**".to_owned();
+            if sessions.is_empty() {
+                content += "\n*None*";
+            }
+            for session in sessions.iter() {
+                content += &format!(
+                    "\n> `{}` {} (weekend `{}`, kind `{}`)",
+                    session.id, session.title, session.weekend, session.kind
+                );
+            }
+            content
+        },
+    };
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,57 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    sync::SessionSource,
+    util::{fetch_session, set_session_source},
+};
+
+use super::{integer_option, respond_ephemeral};
+
+pub const NAME: &str = "release";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description(
+            "Let schedule syncs update a manually changed session again",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "session",
+                "Id of the session",
+            )
+            .required(true),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some(id) = integer_option(&command.data.options(), "session") else {
+        return respond_ephemeral(ctx, command, "Missing session id.").await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let Some(session) = fetch_session(db_conn.as_mut(), id).await? else {
+        return respond_ephemeral(ctx, command, "No session with that id.")
+            .await;
+    };
+
+    set_session_source(db_conn.as_mut(), session.id, SessionSource::Api)
+        .await?;
+    info!("{} released the override on {}", command.user.name, session.title);
+    respond_ephemeral(
+        ctx,
+        command,
+        format!("Released `{}`, syncs may update it again.", session.title),
+    )
+    .await
+}
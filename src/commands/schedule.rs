@@ -0,0 +1,162 @@
+use chrono::{NaiveDate, TimeDelta, Utc};
+use f1_bot_types::{Series, Session, SessionStatus, Weekend};
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    config::Config,
+    error::Error,
+    util::{
+        fetch_guild_settings, fetch_sessions_starting_between, fetch_weekend,
+        session_name, week_of, GuildSettings,
+    },
+};
+
+use super::{boolean_option, respond_ephemeral, string_option};
+
+pub const NAME: &str = "schedule";
+
+/// Discord refuses messages longer than this.
+const MAX_CONTENT: usize = 2000;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Show every session on one day or in its week")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "date",
+            "Day as YYYY-MM-DD in UTC, today by default",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "week",
+            "List the whole week of the day",
+        ))
+}
+
+/// The sessions among `sessions` starting on `day`, grouped by weekend in
+/// series order.
+fn day_lines(
+    config: &Config<'_>,
+    day: NaiveDate,
+    sessions: &[Session],
+    weekends: &[Weekend],
+) -> String {
+    let mut content = String::new();
+    let sessions = sessions
+        .iter()
+        .filter(|f| f.start_date.date_naive() == day)
+        .collect::<Vec<_>>();
+    for val in Series::F1.i8()..=Series::F1Academy.i8() {
+        for weekend in weekends.iter().filter(|f| f.series.i8() == val) {
+            let mut weekend_sessions = sessions
+                .iter()
+                .filter(|f| f.weekend as u64 == weekend.id)
+                .peekable();
+            if weekend_sessions.peek().is_none() {
+                continue;
+            }
+            content += &format!(
+                "\n{} **{}** {}",
+                weekend.icon, weekend.series, weekend.name
+            );
+            for session in weekend_sessions {
+                let strike = match session.status {
+                    SessionStatus::Cancelled => "~~",
+                    _ => "",
+                };
+                content += &format!(
+                    "\n> `{:>12}` {strike}<t:{}:t> (<t:{1}:R>){strike}",
+                    session_name(
+                        &config.display.names,
+                        weekend.series,
+                        session
+                    ),
+                    session.start_date.timestamp()
+                );
+            }
+        }
+    }
+    content
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let date = match string_option(&options, "date") {
+        None => Utc::now().date_naive(),
+        Some(date) => {
+            match NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+                Ok(date) => date,
+                Err(_) => {
+                    return respond_ephemeral(
+                        ctx,
+                        command,
+                        format!("`{date}` is not a YYYY-MM-DD date."),
+                    )
+                    .await;
+                },
+            }
+        },
+    };
+    let week = boolean_option(&options, "week").unwrap_or(false);
+
+    let mut db_conn = database.acquire().await?;
+    let settings = match command.guild_id {
+        Some(guild) => {
+            fetch_guild_settings(db_conn.as_mut(), guild.get()).await?
+        },
+        None => GuildSettings::default(),
+    };
+    let days = match week {
+        true => week_of(date, settings.week_start).collect::<Vec<_>>(),
+        false => vec![date],
+    };
+    let from = days[0].and_time(Default::default()).and_utc();
+    let sessions = fetch_sessions_starting_between(
+        db_conn.as_mut(),
+        from,
+        from + TimeDelta::days(days.len() as i64),
+    )
+    .await?;
+    let mut weekends: Vec<Weekend> = vec![];
+    for session in sessions.iter() {
+        let id = session.weekend as u64;
+        if weekends.iter().any(|f| f.id == id) {
+            continue;
+        }
+        if let Some(weekend) = fetch_weekend(db_conn.as_mut(), id).await? {
+            weekends.push(weekend);
+        }
+    }
+
+    let format = settings.date_format;
+    let mut content = match week {
+        true => {
+            format!("**Sessions in the week of {}**", format.format(days[0]))
+        },
+        false => format!("**Sessions on {}**", format.format(date)),
+    };
+    if sessions.is_empty() {
+        content += "\n*None*";
+    }
+    for day in days {
+        let mut lines = day_lines(config, day, &sessions, &weekends);
+        if week && !lines.is_empty() {
+            lines = format!("\n__{}__{lines}", format.format(day));
+        }
+        // busy weeks lose their last days rather than the whole reply
+        if content.len() + lines.len() > MAX_CONTENT {
+            break;
+        }
+        content += &lines;
+    }
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,250 @@
+use std::path::Path;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::{
+    Series, Session, SessionKind, SessionStatus, Weekend, WeekendStatus,
+};
+use serenity::all::{
+    ChannelId, CommandInteraction, Context, CreateCommand, CreateMessage,
+    EditInteractionResponse, EditMessage, MessageId, Permissions,
+};
+use tracing::info;
+
+use crate::{
+    config::{Config, CAT_PATH},
+    error::Error,
+    util::{
+        is_in_notify_window, is_session_over, post_weekend_message, render,
+        send_merged_notification, session_end, update_weekend_message,
+        CalendarVerbosity, FullWeekend, GuildSettings, SessionDuration,
+        WeekendMeta, NOTIFY_WINDOW,
+    },
+};
+
+use super::respond_ephemeral;
+
+pub const NAME: &str = "selftest";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Run a made-up weekend through the bot in the sandbox")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+}
+
+/// A weekend with sessions in the next hours that exists only in memory, so
+/// nothing the bot loop reads from the database is touched.
+fn sandbox_weekend(now: DateTime<Utc>) -> FullWeekend {
+    let sessions = (0..3)
+        .map(|index| Session {
+            id: -(index + 1),
+            weekend: 0,
+            kind: SessionKind::from(index as i8),
+            title: format!("Session {}", index + 1),
+            start_date: now + TimeDelta::hours(index + 1),
+            duration: 3600,
+            status: SessionStatus::Open,
+        })
+        .collect();
+    FullWeekend {
+        weekend: Weekend {
+            id: 0,
+            name: "Selftest Grand Prix".to_owned(),
+            icon: ":test_tube:".to_owned(),
+            series: Series::F1,
+            start_date: now,
+            status: WeekendStatus::Open,
+        },
+        sessions,
+        links: vec![],
+        meta: WeekendMeta {
+            weekend: 0,
+            timezone: None,
+            provisional: false,
+            circuit: None,
+        },
+        penalties: vec![],
+        round: 1,
+        circuit: None,
+        forecasts: vec![],
+    }
+}
+
+/// Messages posted by the stages, deleted again in the cleanup stage.
+struct Sandbox {
+    channel: ChannelId,
+    posted: Vec<MessageId>,
+}
+
+/// Posts the weekend message, then delays the first session and edits it.
+async fn weekend_stage(
+    ctx: &Context,
+    config: &Config<'_>,
+    sandbox: &mut Sandbox,
+    weekend: &mut FullWeekend,
+) -> Result<(), Error> {
+    let settings = GuildSettings::default();
+    let message = post_weekend_message(
+        &ctx.http,
+        weekend,
+        sandbox.channel.get(),
+        &config.display,
+        &settings,
+    )
+    .await?;
+    sandbox.posted.push(message);
+    weekend.sessions[0].start_date += TimeDelta::minutes(15);
+    weekend.sessions[0].status = SessionStatus::Delayed;
+    update_weekend_message(
+        &ctx.http,
+        weekend,
+        sandbox.channel.get(),
+        message.get(),
+        &config.display,
+        &settings,
+    )
+    .await
+}
+
+/// Posts the weekend's calendar entry, then moves the last session and
+/// edits it.
+async fn calendar_stage(
+    ctx: &Context,
+    config: &Config<'_>,
+    sandbox: &mut Sandbox,
+    weekend: &mut FullWeekend,
+) -> Result<(), Error> {
+    let content = render::calendar_text(
+        weekend,
+        true,
+        &config.display,
+        CalendarVerbosity::Full,
+    );
+    let mut message = sandbox
+        .channel
+        .send_message(&ctx.http, CreateMessage::new().content(content))
+        .await?;
+    sandbox.posted.push(message.id);
+    weekend.sessions[2].start_date += TimeDelta::minutes(30);
+    let content = render::calendar_text(
+        weekend,
+        true,
+        &config.display,
+        CalendarVerbosity::Full,
+    );
+    message.edit(&ctx.http, EditMessage::new().content(content)).await?;
+    Ok(())
+}
+
+/// Walks the clock up to the first session instead of waiting for it, then
+/// sends its notification.
+async fn notification_stage(
+    ctx: &Context,
+    config: &Config<'_>,
+    sandbox: &mut Sandbox,
+    weekend: &FullWeekend,
+) -> Result<(), Error> {
+    let lead = TimeDelta::seconds(config.notifications.batch_window as i64);
+    let session = &weekend.sessions[0];
+    let start = session.start_date;
+    let early = start - NOTIFY_WINDOW - lead - TimeDelta::minutes(1);
+    if is_in_notify_window(start, early, lead)
+        || !is_in_notify_window(start, start - TimeDelta::minutes(1), lead)
+    {
+        return Err(Error::NNF("notify window is off".into()));
+    }
+    // role 0 is never mentioned
+    let message = send_merged_notification(
+        &ctx.http,
+        &[(&weekend.weekend, session, 0)],
+        sandbox.channel.get(),
+        Path::new(CAT_PATH),
+        &[],
+    )
+    .await?;
+    sandbox.posted.push(message);
+    Ok(())
+}
+
+/// Checks the weekend is over after its last session and deletes every
+/// message the other stages posted.
+async fn cleanup_stage(
+    ctx: &Context,
+    sandbox: &mut Sandbox,
+    weekend: &FullWeekend,
+) -> Result<(), Error> {
+    let last_end = weekend
+        .sessions
+        .iter()
+        .map(|f| session_end(f.start_date, SessionDuration::of(f)))
+        .max()
+        .unwrap_or(weekend.weekend.start_date);
+    let after = last_end + TimeDelta::minutes(1);
+    // delete first, a failed check shouldn't leave messages behind
+    for message in sandbox.posted.drain(..) {
+        sandbox.channel.delete_message(&ctx.http, message).await?;
+    }
+    if !weekend.sessions.iter().all(|f| is_session_over(f, after)) {
+        return Err(Error::NNF("sessions are never over".into()));
+    }
+    Ok(())
+}
+
+fn report_line(
+    name: &str,
+    result: Result<(), Error>,
+) -> String {
+    match result {
+        Ok(()) => format!("\n> `pass` {name}"),
+        Err(why) => format!("\n> `FAIL` {name}: {why}"),
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    if config.discord.sandbox_channel == 0 {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "No sandbox channel is configured.",
+        )
+        .await;
+    }
+    // posting and deleting takes longer than Discord waits for a reply
+    command.defer_ephemeral(&ctx.http).await?;
+    info!("{} started a self-test", command.user.name);
+
+    let mut sandbox = Sandbox {
+        channel: ChannelId::new(config.discord.sandbox_channel),
+        posted: vec![],
+    };
+    let mut weekend = sandbox_weekend(Utc::now());
+
+    let mut report = "**Self-test**".to_owned();
+    report += &report_line(
+        "Weekend message",
+        weekend_stage(ctx, config, &mut sandbox, &mut weekend).await,
+    );
+    report += &report_line(
+        "Calendar",
+        calendar_stage(ctx, config, &mut sandbox, &mut weekend).await,
+    );
+    report += &report_line(
+        "Notification",
+        notification_stage(ctx, config, &mut sandbox, &weekend).await,
+    );
+    report += &report_line(
+        "Cleanup",
+        cleanup_stage(ctx, &mut sandbox, &weekend).await,
+    );
+
+    command
+        .edit_response(
+            &ctx.http,
+            EditInteractionResponse::new().content(report),
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateAttachment,
+    CreateCommand, CreateCommandOption, CreateInteractionResponse,
+    CreateInteractionResponseMessage, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{fetch_guild_settings, set_guild_setting, GuildSettings},
+};
+
+use super::{
+    attachment_option, boolean_option, respond_ephemeral, string_option,
+    subcommand,
+};
+
+pub const NAME: &str = "settings";
+
+/// Largest settings file read by `/settings import`, in bytes.
+const MAX_IMPORT_SIZE: u32 = 16 * 1024;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Change how the bot behaves in this server")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "footer",
+                "Toggle an explanation footer under weekend messages",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "footer",
+                    "Which footer",
+                )
+                .add_string_choice("Sprint format", "sprint_footer")
+                .add_string_choice("Session lengths", "lengths_footer")
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Show the footer",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "verbose",
+                "Also list low-priority sessions, like media days",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "List them",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "calendar",
+                "Choose how much of each weekend the calendar lists",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "verbosity",
+                    "What to list",
+                )
+                .add_string_choice("All sessions", "full")
+                .add_string_choice("Qualifying and races", "compact")
+                .add_string_choice("Weekend dates only", "minimal")
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "dates",
+                "Choose how /schedule lays out days",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "week_start",
+                    "First day of the week",
+                )
+                .add_string_choice("Monday", "monday")
+                .add_string_choice("Sunday", "sunday"),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "format",
+                    "How days are written",
+                )
+                .add_string_choice("Sunday, 9 March 2025", "long")
+                .add_string_choice("Sunday, March 9, 2025", "us")
+                .add_string_choice("2025-03-09", "iso"),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "show",
+            "Show the current settings",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "export",
+            "Download the current settings as a file",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "import",
+                "Replace the settings with an exported file",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Attachment,
+                    "file",
+                    "File from /settings export",
+                )
+                .required(true),
+            ),
+        )
+}
+
+/// `settings` as a JSON object of setting names and their stored values.
+fn export(settings: &GuildSettings) -> Result<String, Error> {
+    let pairs = settings.pairs().into_iter().collect::<BTreeMap<_, _>>();
+    serde_json::to_string_pretty(&pairs).map_err(|f| Error::NNF(f.into()))
+}
+
+/// The settings in an [export]ed file, settings missing from it keep their
+/// default. Errors name the first entry that can't be applied.
+fn import(file: &[u8]) -> Result<GuildSettings, String> {
+    let pairs = serde_json::from_slice::<BTreeMap<String, String>>(file)
+        .map_err(|why| format!("Not a settings file: {why}"))?;
+    let mut settings = GuildSettings::default();
+    for (name, value) in pairs.iter() {
+        if !settings.set(name, value) {
+            return Err(format!("Can't set `{name}` to `{value}`."));
+        }
+    }
+    Ok(settings)
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let Some(guild) = command.guild_id.map(|f| f.get()) else {
+        return respond_ephemeral(ctx, command, "Only usable in a server.")
+            .await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let mut settings = fetch_guild_settings(db_conn.as_mut(), guild).await?;
+
+    let content = match name {
+        "footer" => {
+            let footer = string_option(&options, "footer").unwrap_or_default();
+            let enabled = boolean_option(&options, "enabled").unwrap_or(false);
+            let value = enabled.to_string();
+            if !settings.set(footer, &value) {
+                return respond_ephemeral(ctx, command, "Unknown footer.")
+                    .await;
+            }
+            set_guild_setting(db_conn.as_mut(), guild, footer, &value).await?;
+            format!("Set `{footer}` to `{value}`.")
+        },
+        "verbose" => {
+            let value = boolean_option(&options, "enabled")
+                .unwrap_or(false)
+                .to_string();
+            settings.set("verbose", &value);
+            set_guild_setting(db_conn.as_mut(), guild, "verbose", &value)
+                .await?;
+            format!("Set `verbose` to `{value}`.")
+        },
+        "calendar" => {
+            let value =
+                string_option(&options, "verbosity").unwrap_or_default();
+            if !settings.set("calendar", value) {
+                return respond_ephemeral(ctx, command, "Unknown verbosity.")
+                    .await;
+            }
+            set_guild_setting(db_conn.as_mut(), guild, "calendar", value)
+                .await?;
+            format!(
+                "Set `calendar` to `{value}`, the calendar updates within a \
+                 few minutes."
+            )
+        },
+        "dates" => {
+            let mut changed = vec![];
+            for (option, name) in
+                [("week_start", "week_start"), ("format", "date_format")]
+            {
+                let Some(value) = string_option(&options, option) else {
+                    continue;
+                };
+                if !settings.set(name, value) {
+                    return respond_ephemeral(ctx, command, "Unknown value.")
+                        .await;
+                }
+                set_guild_setting(db_conn.as_mut(), guild, name, value).await?;
+                changed.push(format!("`{name}` to `{value}`"));
+            }
+            match changed.is_empty() {
+                true => "Nothing to change.".to_owned(),
+                false => format!("Set {}.", changed.join(" and ")),
+            }
+        },
+        "export" => {
+            let file =
+                CreateAttachment::bytes(export(&settings)?, "settings.json");
+            command
+                .create_response(
+                    &ctx.http,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(
+                                "The settings of this server, `/settings \
+                                 import` applies them elsewhere.",
+                            )
+                            .add_file(file)
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            return Ok(());
+        },
+        "import" => {
+            let Some(file) = attachment_option(&options, "file") else {
+                return Ok(());
+            };
+            if file.size > MAX_IMPORT_SIZE {
+                return respond_ephemeral(ctx, command, "File too large.")
+                    .await;
+            }
+            let imported = match import(&file.download().await?) {
+                Ok(imported) => imported,
+                Err(why) => return respond_ephemeral(ctx, command, why).await,
+            };
+            // every setting is written, missing ones go back to the default
+            for (name, value) in imported.pairs() {
+                set_guild_setting(db_conn.as_mut(), guild, name, &value)
+                    .await?;
+            }
+            "Imported the settings, see `/settings show`.".to_owned()
+        },
+        _ => {
+            let mut content = "**Settings**".to_owned();
+            for (name, value) in settings.pairs() {
+                content += &format!("\n> `{name}` {value}");
+            }
+            content
+        },
+    };
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,104 @@
+use chrono::{Datelike, Utc};
+use f1_bot_types::Series;
+use futures::StreamExt;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption,
+};
+use sqlx::MySqlPool;
+
+use crate::{error::Error, util::stream_notification_log_for_year};
+
+use super::{integer_option, respond_ephemeral};
+
+pub const NAME: &str = "stats";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Show how punctual notifications were over a season")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "year",
+            "Season to summarize, defaults to the current one",
+        ))
+}
+
+/// Seconds after the notification window opened a notification still
+/// counts as on time, the loop wakes up just after it.
+const ON_TIME: i64 = 30;
+
+/// Running totals of one series' notification latencies.
+#[derive(Default)]
+struct Delivery {
+    count: i64,
+    on_time: i64,
+    total: i64,
+    worst: Option<i64>,
+}
+
+fn fmt_secs(secs: i64) -> String {
+    let secs = secs.abs();
+    match secs / 60 {
+        0 => format!("{secs}s"),
+        m => format!("{m}m {}s", secs % 60),
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let year = integer_option(&command.data.options(), "year")
+        .map(|f| f as i32)
+        .unwrap_or(Utc::now().year());
+    let mut db_conn = database.acquire().await?;
+    let mut deliveries: [Delivery; 4] = Default::default();
+    let mut log = stream_notification_log_for_year(db_conn.as_mut(), year);
+    while let Some(entry) = log.next().await {
+        let entry = entry?;
+        let Some(delivery) =
+            usize::try_from(entry.series.i8() - Series::F1.i8())
+                .ok()
+                .and_then(|f| deliveries.get_mut(f))
+        else {
+            continue;
+        };
+        delivery.count += 1;
+        delivery.on_time += (entry.latency <= ON_TIME) as i64;
+        delivery.total += entry.latency;
+        delivery.worst = delivery.worst.max(Some(entry.latency));
+    }
+    if deliveries.iter().all(|f| f.count == 0) {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("No notifications were sent in {year}."),
+        )
+        .await;
+    }
+
+    let mut content = format!("**Notification delivery {year}**");
+    for val in Series::F1.i8()..=Series::F1Academy.i8() {
+        let series: Series = val.into();
+        let Some(delivery) = deliveries.get((val - Series::F1.i8()) as usize)
+        else {
+            continue;
+        };
+        if delivery.count == 0 {
+            continue;
+        }
+        let on_time = delivery.on_time;
+        let average = delivery.total / delivery.count;
+        let worst = delivery.worst.unwrap_or_default();
+        content += &format!(
+            "\n> **{series}**: {on_time}/{} on time, {} {} on average, worst {} {}",
+            delivery.count,
+            fmt_secs(average),
+            if average <= 0 { "early" } else { "late" },
+            fmt_secs(worst),
+            if worst <= 0 { "early" } else { "late" },
+        );
+    }
+    respond_ephemeral(ctx, command, content).await
+}
@@ -0,0 +1,61 @@
+use serenity::all::{CommandInteraction, Context, CreateCommand, Permissions};
+use sqlx::MySqlPool;
+
+use crate::{
+    bot::worker::WORKER,
+    error::Error,
+    util::{names::ALL_SERIES, quarantine, registry, retention},
+};
+
+use super::respond_ephemeral;
+
+pub const NAME: &str = "status";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Show the state of the bot")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let mut db_conn = database.acquire().await?;
+    let quarantined = quarantine::scan(db_conn.as_mut()).await?.len();
+
+    let mut content = "**Status**".to_owned();
+    content += &format!("\n> Quarantined sessions: {quarantined}");
+    if quarantined > 0 {
+        content += " (see `/quarantine list`)";
+    }
+    let worker = WORKER.snapshot();
+    if worker.shard_count == 0 {
+        content += "\n> Bot loop: not started";
+    } else {
+        content += &format!(
+            "\n> Bot loop: shard {} of {}, {} ticks, last took {}ms",
+            worker.shard, worker.shard_count, worker.ticks, worker.last_tick_ms
+        );
+    }
+    let series = registry::entries();
+    if !series.is_empty() {
+        content += "\n> Series:";
+    }
+    for entry in series.iter() {
+        content += &format!("\n> - {} {}", entry.icon, entry.name);
+        if !ALL_SERIES.iter().any(|f| f.i8() == entry.id) {
+            content += " (not supported by this build yet)";
+        } else if !entry.enabled {
+            content += " (disabled)";
+        }
+    }
+    if let (pruned, Some(last)) = retention::stats() {
+        content += &format!(
+            "\n> Pruned messages: {pruned} since startup, last run <t:{}:R>",
+            last.timestamp()
+        );
+    }
+    respond_ephemeral(ctx, command, content).await
+}
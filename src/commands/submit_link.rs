@@ -0,0 +1,121 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+
+use crate::{config::Config, error::Error, watchalong};
+
+use super::{respond_ephemeral, series_choice, series_option, string_option};
+
+pub const NAME: &str = "submit-link";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Suggest a stream or watch-along link for a live session")
+        .add_option(
+            series_choice("series", "Series of the live session")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "url",
+                "Http(s) link",
+            )
+            .max_length(512)
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "label",
+                "What the link is, e.g. the name of the stream",
+            )
+            .max_length(80)
+            .required(true),
+        )
+}
+
+/// Whether the invoking member may submit links.
+fn is_trusted(
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> bool {
+    let Some(member) = &command.member else {
+        return false;
+    };
+    member.permissions.is_some_and(|f| f.contains(Permissions::MANAGE_GUILD))
+        || member
+            .roles
+            .iter()
+            .any(|f| config.watch_along.roles.contains(&f.get()))
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    config: &Config<'_>,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    if !is_trusted(config, command) {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "You don't have a role that can submit links.",
+        )
+        .await;
+    }
+    if config.discord.admin_channel == 0 {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "Links need an admin channel to be approved in.",
+        )
+        .await;
+    }
+    let options = command.data.options();
+    let Some(series) = series_option(&options, "series") else {
+        return respond_ephemeral(ctx, command, "Pick a series.").await;
+    };
+    let url = string_option(&options, "url").unwrap_or_default();
+    let label = string_option(&options, "label").unwrap_or_default();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return respond_ephemeral(
+            ctx,
+            command,
+            "Links have to start with `http://` or `https://`.",
+        )
+        .await;
+    }
+    let mut db_conn = database.acquire().await?;
+    let Some(session) =
+        watchalong::live_session(db_conn.as_mut(), series).await?
+    else {
+        return respond_ephemeral(
+            ctx,
+            command,
+            format!("No {series} session is live right now."),
+        )
+        .await;
+    };
+    watchalong::submit(
+        db_conn.as_mut(),
+        ctx,
+        config.discord.admin_channel,
+        &session,
+        label,
+        url,
+        command.user.id.get(),
+    )
+    .await?;
+    respond_ephemeral(
+        ctx,
+        command,
+        format!(
+            "Thanks, `{label}` will be posted under {} once approved.",
+            session.title
+        ),
+    )
+    .await
+}
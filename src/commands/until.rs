@@ -0,0 +1,148 @@
+use chrono::{Datelike, TimeDelta, TimeZone, Utc};
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption,
+};
+use sqlx::MySqlPool;
+
+use crate::{
+    error::Error,
+    util::{
+        fetch_full_weekend, fetch_full_weekends_for_series, fetch_session,
+        session_end, FullWeekend, SeasonWindow, SessionDuration,
+    },
+};
+
+use super::{integer_option, respond_ephemeral};
+
+pub const NAME: &str = "until";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Exact time left until a session")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "session",
+                "Id of the session",
+            )
+            .required(true),
+        )
+}
+
+/// Like `3 days 4 hours 12 minutes`, leaving out zero parts.
+fn fmt_delta(delta: TimeDelta) -> String {
+    let plural = |n: i64, unit: &str| match n {
+        1 => format!("1 {unit}"),
+        n => format!("{n} {unit}s"),
+    };
+    let parts = [
+        (delta.num_days(), "day"),
+        (delta.num_hours() % 24, "hour"),
+        (delta.num_minutes() % 60, "minute"),
+    ]
+    .into_iter()
+    .filter(|f| f.0 != 0)
+    .map(|(n, unit)| plural(n, unit))
+    .collect::<Vec<_>>();
+    match parts.is_empty() {
+        true => "less than a minute".to_owned(),
+        false => parts.join(" "),
+    }
+}
+
+/// Time from the end of `previous` to the start of `next`.
+fn gap(
+    previous: &FullWeekend,
+    next: &FullWeekend,
+) -> TimeDelta {
+    let end = previous
+        .sessions
+        .iter()
+        .map(|f| session_end(f.start_date, SessionDuration::of(f)))
+        .max()
+        .unwrap_or(previous.weekend.start_date);
+    let start = next
+        .sessions
+        .iter()
+        .map(|f| f.start_date)
+        .min()
+        .unwrap_or(next.weekend.start_date);
+    start.signed_duration_since(end)
+}
+
+/// How the break before `weekend` compares to the other breaks of its
+/// season, [None] for the opener.
+fn break_str(
+    season: &[FullWeekend],
+    weekend: u64,
+) -> Option<String> {
+    let index = season.iter().position(|f| f.weekend.id == weekend)?;
+    let gaps = season.windows(2).map(|f| gap(&f[0], &f[1])).collect::<Vec<_>>();
+    let current = *gaps.get(index.checked_sub(1)?)?;
+    let mut line =
+        format!("Break since the last round: {}", fmt_delta(current));
+    if gaps.iter().all(|f| *f <= current) {
+        line += ", the longest of the season";
+    } else if let Some(before) = index.checked_sub(2).map(|f| gaps[f]) {
+        let diff = current - before;
+        line += &match diff < TimeDelta::zero() {
+            true => {
+                format!(", {} shorter than the one before", fmt_delta(-diff))
+            },
+            false => {
+                format!(", {} longer than the one before", fmt_delta(diff))
+            },
+        };
+    }
+    Some(line)
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let options = command.data.options();
+    let Some(id) = integer_option(&options, "session") else {
+        return respond_ephemeral(ctx, command, "Missing session.").await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let Some(session) = fetch_session(db_conn.as_mut(), id).await? else {
+        return respond_ephemeral(ctx, command, "No session with that id.")
+            .await;
+    };
+    let Some(weekend) =
+        fetch_full_weekend(db_conn.as_mut(), session.weekend as u64).await?
+    else {
+        return respond_ephemeral(ctx, command, "The session has no weekend.")
+            .await;
+    };
+
+    let remaining = session.start_date.signed_duration_since(Utc::now());
+    let mut content = format!(
+        "{} {} {}: ",
+        weekend.weekend.icon, weekend.weekend.name, session.title
+    );
+    content += &match remaining < TimeDelta::zero() {
+        true => format!("started {} ago", fmt_delta(-remaining)),
+        false => format!("starts in {}", fmt_delta(remaining)),
+    };
+    content += &format!(" (<t:{}:f>)", session.start_date.timestamp());
+
+    let year = weekend.weekend.start_date.year();
+    let window = SeasonWindow {
+        from: Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single(),
+        until: Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).single(),
+    };
+    let season = fetch_full_weekends_for_series(
+        db_conn.as_mut(),
+        weekend.weekend.series,
+        window,
+    )
+    .await?;
+    if let Some(gap) = break_str(&season, weekend.weekend.id) {
+        content += &format!("\n-# {gap}");
+    }
+    respond_ephemeral(ctx, command, content).await
+}
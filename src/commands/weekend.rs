@@ -0,0 +1,256 @@
+use chrono_tz::Tz;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, Permissions,
+};
+use sqlx::MySqlPool;
+use tracing::info;
+
+use crate::{
+    error::Error,
+    util::{
+        clone_weekend, fetch_circuit_by_name, fetch_full_weekend,
+        fetch_weekend, parse_start, set_weekend_circuit,
+        set_weekend_provisional, set_weekend_timezone,
+    },
+};
+
+use super::{integer_option, respond_ephemeral, string_option, subcommand};
+
+pub const NAME: &str = "weekend";
+
+fn weekend_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "weekend",
+        "Id of the weekend",
+    )
+    .required(true)
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new(NAME)
+        .description("Manage weekend metadata")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "timezone",
+                "Set the circuits timezone, leave it out to clear it",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "timezone",
+                "IANA timezone, e.g. Europe/London",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "circuit",
+                "Set the circuit the forecast is for, leave it out to go by \
+                 the name again",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "circuit",
+                "Name of the circuit, e.g. Silverstone",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "provisional",
+                "Mark a weekend as unconfirmed, it won't be notified",
+            )
+            .add_sub_option(weekend_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "confirm",
+                "Confirm a provisional weekend",
+            )
+            .add_sub_option(weekend_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "clone",
+                "Copy a weekend and its sessions to a new start date",
+            )
+            .add_sub_option(weekend_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "start",
+                    "New start, YYYY-MM-DD or YYYY-MM-DD HH:MM at the circuit",
+                )
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "name",
+                    "Name of the copy, the same name if left out",
+                )
+                .max_length(100),
+            ),
+        )
+}
+
+pub async fn run(
+    ctx: &Context,
+    database: &MySqlPool,
+    command: &CommandInteraction,
+) -> Result<(), Error> {
+    let Some((name, options)) = subcommand(command) else {
+        return Ok(());
+    };
+    let Some(weekend) = integer_option(&options, "weekend") else {
+        return respond_ephemeral(ctx, command, "Missing weekend id.").await;
+    };
+    let mut db_conn = database.acquire().await?;
+    let Some(weekend) = fetch_weekend(db_conn.as_mut(), weekend as u64).await?
+    else {
+        return respond_ephemeral(ctx, command, "No weekend with that id.")
+            .await;
+    };
+
+    match name {
+        "timezone" => {
+            let timezone = string_option(&options, "timezone");
+            if let Some(timezone) = timezone {
+                if timezone.parse::<Tz>().is_err() {
+                    return respond_ephemeral(
+                        ctx,
+                        command,
+                        format!("`{timezone}` is not a known timezone."),
+                    )
+                    .await;
+                }
+            }
+            set_weekend_timezone(db_conn.as_mut(), weekend.id, timezone)
+                .await?;
+            respond_ephemeral(
+                ctx,
+                command,
+                match timezone {
+                    Some(timezone) => {
+                        format!("{} is now in `{timezone}`.", weekend.name)
+                    },
+                    None => {
+                        format!("Cleared the timezone of {}.", weekend.name)
+                    },
+                },
+            )
+            .await
+        },
+        "circuit" => {
+            let circuit = match string_option(&options, "circuit") {
+                Some(name) => {
+                    match fetch_circuit_by_name(db_conn.as_mut(), name).await? {
+                        Some(circuit) => Some(circuit),
+                        None => {
+                            return respond_ephemeral(
+                                ctx,
+                                command,
+                                format!("`{name}` is not a known circuit."),
+                            )
+                            .await;
+                        },
+                    }
+                },
+                None => None,
+            };
+            set_weekend_circuit(
+                db_conn.as_mut(),
+                weekend.id,
+                circuit.as_ref().map(|f| f.id),
+            )
+            .await?;
+            respond_ephemeral(
+                ctx,
+                command,
+                match circuit {
+                    Some(circuit) => {
+                        format!("{} is now at {}.", weekend.name, circuit.name)
+                    },
+                    None => format!(
+                        "{} goes by its name for the circuit again.",
+                        weekend.name
+                    ),
+                },
+            )
+            .await
+        },
+        "provisional" | "confirm" => {
+            let provisional = name == "provisional";
+            set_weekend_provisional(db_conn.as_mut(), weekend.id, provisional)
+                .await?;
+            respond_ephemeral(
+                ctx,
+                command,
+                match provisional {
+                    true => format!("{} is now provisional.", weekend.name),
+                    false => format!("{} is confirmed.", weekend.name),
+                },
+            )
+            .await
+        },
+        "clone" => {
+            let Some(source) =
+                fetch_full_weekend(db_conn.as_mut(), weekend.id).await?
+            else {
+                return respond_ephemeral(
+                    ctx,
+                    command,
+                    "No weekend with that id.",
+                )
+                .await;
+            };
+            let start = string_option(&options, "start").unwrap_or_default();
+            let name = string_option(&options, "name")
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .unwrap_or(&weekend.name);
+            // a bare date is midnight at the circuit, like in /addweekend
+            let tz = source.tz().unwrap_or(Tz::UTC);
+            let Some(start_date) = parse_start(start, tz) else {
+                return respond_ephemeral(
+                    ctx,
+                    command,
+                    format!("`{start}` is not a YYYY-MM-DD date in `{tz}`."),
+                )
+                .await;
+            };
+            let (id, copied) =
+                clone_weekend(db_conn.as_mut(), &source, name, start_date)
+                    .await?;
+            info!(
+                "{} cloned weekend {} to {id} `{name}`",
+                command.user.name, weekend.id
+            );
+            let skipped = match source.sessions.len() - copied {
+                0 => String::new(),
+                n => {
+                    format!(", {n} sessions without a known kind were left out")
+                },
+            };
+            respond_ephemeral(
+                ctx,
+                command,
+                format!(
+                    "Copied {} to `{id}` {name} starting <t:{}:D> with \
+                     {copied} sessions{skipped}.",
+                    weekend.name,
+                    start_date.timestamp()
+                ),
+            )
+            .await
+        },
+        _ => Ok(()),
+    }
+}
@@ -3,10 +3,87 @@ use std::borrow::Cow;
 use f1_bot_types::Series;
 use serde::{Deserialize, Serialize};
 
+use crate::util::{names::ALL_SERIES, registry, templates};
+
+pub const CONFIG_PATH: &str = "./config/config.toml";
+/// Video attached to every session notification.
+pub const CAT_PATH: &str = "./config/cats.mp4";
+
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct Config<'a> {
     pub discord: DiscordConfig<'a>,
     pub database: DatabaseConfig<'a>,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub display: DisplayConfig,
+    #[serde(default)]
+    pub milestones: MilestoneConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+    #[serde(default)]
+    pub jolpica: JolpicaConfig,
+    #[serde(default)]
+    pub openf1: OpenF1Config,
+    #[serde(default)]
+    pub weather: WeatherConfig,
+    /// Calendars imported from ICS files (`ics` feature).
+    #[serde(default)]
+    pub ics: Vec<IcsSource>,
+    #[serde(default)]
+    pub stage: StageConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub season: SeasonConfig,
+    #[serde(default)]
+    pub clock: ClockConfig,
+    /// Second race ping at lights out, per series.
+    #[serde(default)]
+    pub lights_out: Vec<LightsOutConfig>,
+    /// Role pings ahead of every session, per series.
+    #[serde(default)]
+    pub reminders: Vec<ReminderConfig>,
+    #[serde(default)]
+    pub polls: PollConfig,
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    /// Notifications posted through a webhook instead of the bot, per
+    /// series.
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default)]
+    pub ics_export: IcsExportConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub templates: TemplateConfig,
+    #[serde(default)]
+    pub watch_along: WatchAlongConfig,
+    #[serde(default)]
+    pub results: ResultsConfig,
+    #[serde(default)]
+    pub standings: StandingsConfig,
+    /// Scopes the tracked messages, deployments sharing a database need
+    /// distinct ids. Empty for production.
+    #[serde(default)]
+    pub instance: String,
+    #[serde(default)]
+    pub mirror: MirrorConfig,
+    /// Sessions already running when the bot starts.
+    #[serde(default)]
+    pub catch_up: CatchUpConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub cadence: CadenceConfig,
+    /// Runtime errors posted to `discord.admin_channel`.
+    #[serde(default)]
+    pub alerts: AlertConfig,
 }
 
 impl Config<'_> {
@@ -20,10 +97,17 @@ impl Config<'_> {
         )
     }
 
+    /// Role pinged for `series`, the `series` table overrides the config.
     pub fn role(
         &self,
         series: Series,
     ) -> u64 {
+        if self.is_mirror() {
+            return 0;
+        }
+        if let Some(role) = registry::role(series) {
+            return role;
+        }
         match series {
             Series::F1 => self.discord.f1_role,
             Series::F2 => self.discord.f2_role,
@@ -32,10 +116,32 @@ impl Config<'_> {
         }
     }
 
+    /// Whether this deployment only mirrors the rendered messages, see
+    /// [MirrorConfig].
+    pub fn is_mirror(&self) -> bool {
+        self.mirror.channel != 0
+    }
+
+    /// Notification channel of `series`, the `series` table overrides the
+    /// config. Mirrors post everything into their staging channel.
     pub fn channel(
         &self,
         series: Series,
     ) -> u64 {
+        match self.production_channel(series) {
+            0 => 0,
+            _ if self.is_mirror() => self.mirror.channel,
+            channel => channel,
+        }
+    }
+
+    fn production_channel(
+        &self,
+        series: Series,
+    ) -> u64 {
+        if let Some(channel) = registry::channel(series) {
+            return channel;
+        }
         match series {
             Series::F1 => self.discord.f1_channel,
             Series::F2 => self.discord.f2_channel,
@@ -43,6 +149,112 @@ impl Config<'_> {
             Series::F1Academy => self.discord.f1a_channel,
         }
     }
+
+    /// Channel the calendar of `series` is posted to, the notification
+    /// channel unless one is configured.
+    pub fn calendar_channel(
+        &self,
+        series: Series,
+    ) -> u64 {
+        if self.is_mirror() {
+            return match self.channel(series) {
+                0 => 0,
+                _ => self.mirror.calendar_channel,
+            };
+        }
+        match self.configured_calendar_channel(series) {
+            0 => self.channel(series),
+            channel => channel,
+        }
+    }
+
+    fn configured_calendar_channel(
+        &self,
+        series: Series,
+    ) -> u64 {
+        match series {
+            Series::F1 => self.discord.f1_calendar_channel,
+            Series::F2 => self.discord.f2_calendar_channel,
+            Series::F3 => self.discord.f3_calendar_channel,
+            Series::F1Academy => self.discord.f1a_calendar_channel,
+        }
+    }
+
+    /// Enabled series with a notification channel, the others are skipped
+    /// by the bot loop and the calendar.
+    pub fn active_series(&self) -> Vec<Series> {
+        ALL_SERIES
+            .into_iter()
+            .filter(|f| registry::is_enabled(*f) && self.channel(*f) != 0)
+            .collect()
+    }
+
+    /// Calendar channels together with the series posting into them, in
+    /// display order.
+    pub fn calendar_groups(&self) -> Vec<(u64, Vec<Series>)> {
+        let mut groups: Vec<(u64, Vec<Series>)> = Vec::new();
+        for series in self.active_series() {
+            let channel = self.calendar_channel(series);
+            match groups.iter_mut().find(|f| f.0 == channel) {
+                Some((_, group)) => group.push(series),
+                None => groups.push((channel, vec![series])),
+            }
+        }
+        groups
+    }
+
+    /// Series sharing the notification channel of `series` that come after
+    /// it in display order.
+    pub fn later_series_in_channel(
+        &self,
+        series: Series,
+    ) -> Vec<Series> {
+        let channel = self.channel(series);
+        (series.i8() + 1..=Series::F1Academy.i8())
+            .map(Series::from)
+            .filter(|f| self.channel(*f) == channel)
+            .collect()
+    }
+
+    /// Rejects configured calendar channels that are also a notification
+    /// channel, calendar entries are reserved in bulk and would interleave
+    /// with weekend messages and pings there, and invalid templates.
+    pub fn validate(&self) -> Result<(), String> {
+        for series in ALL_SERIES {
+            let calendar = self.configured_calendar_channel(series);
+            if calendar == 0 {
+                continue;
+            }
+            if let Some(other) = ALL_SERIES
+                .into_iter()
+                .find(|f| self.production_channel(*f) == calendar)
+            {
+                return Err(format!(
+                    "The {series} calendar channel is the {other} \
+                     notification channel"
+                ));
+            }
+        }
+        if self.is_mirror() {
+            if self.instance.is_empty() {
+                return Err("A mirror needs its own `instance` id".to_owned());
+            }
+            if self.mirror.calendar_channel == 0
+                || self.mirror.calendar_channel == self.mirror.channel
+            {
+                return Err("A mirror needs a calendar channel other than \
+                            the mirror channel"
+                    .to_owned());
+            }
+        }
+        // an idle loop would fail the health checks between ticks
+        if self.http.stale_after <= self.scheduler.max_idle {
+            return Err("`http.stale_after` needs to be longer than \
+                        `scheduler.max_idle`"
+                .to_owned());
+        }
+        self.templates.validate()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -57,6 +269,584 @@ pub struct DiscordConfig<'a> {
     pub f3_role: u64,
     pub f1a_role: u64,
     pub f1a_channel: u64,
+    /// Channels for the calendars, 0 posts them into the notification
+    /// channel of the series.
+    #[serde(default)]
+    pub f1_calendar_channel: u64,
+    #[serde(default)]
+    pub f2_calendar_channel: u64,
+    #[serde(default)]
+    pub f3_calendar_channel: u64,
+    #[serde(default)]
+    pub f1a_calendar_channel: u64,
+    /// Channel for schedule previews and other admin notices, 0 disables.
+    #[serde(default)]
+    pub admin_channel: u64,
+    /// Channel `/selftest` posts its throwaway messages into, 0 disables the
+    /// command.
+    #[serde(default)]
+    pub sandbox_channel: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct SyncConfig {
+    /// Apply upstream schedule changes without waiting for a confirmation in
+    /// the admin channel.
+    pub auto_apply: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Hash)]
+pub struct DisplayConfig {
+    /// Show the circuits local time next to each session, for weekends with
+    /// a timezone set.
+    pub track_time: bool,
+    /// Add a circuit fact under each weekend message, it changes daily.
+    #[serde(default)]
+    pub circuit_facts: bool,
+    #[serde(default)]
+    pub names: SessionNameConfig,
+    #[serde(default)]
+    pub weekend_names: WeekendNameConfig,
+    /// Post weekend and calendar messages as plain text instead of embeds.
+    #[serde(default)]
+    pub plain_text: bool,
+    /// Show the forecast for each session day in the weekend message, needs
+    /// the `weather` feature to be filled in.
+    #[serde(default)]
+    pub weather: bool,
+    /// Link every upcoming session in the weekend message to a Google
+    /// Calendar event.
+    #[serde(default)]
+    pub calendar_links: bool,
+    /// Public URL the iCalendar feeds are served under, like
+    /// `https://example.org/calendar`. Weekend messages link the feed of
+    /// their series when set.
+    #[serde(default)]
+    pub feed_url: String,
+}
+
+/// Session names shown instead of the stored titles.
+#[derive(Serialize, Deserialize, Debug, Hash)]
+#[serde(default)]
+pub struct SessionNameConfig {
+    /// Locale the overrides are picked for.
+    pub locale: String,
+    pub overrides: Vec<SessionNameOverride>,
+}
+
+impl Default for SessionNameConfig {
+    fn default() -> Self {
+        Self {
+            locale: "en".to_owned(),
+            overrides: vec![],
+        }
+    }
+}
+
+/// Weekend names shown instead of the stored official ones, see
+/// [weekend_name](crate::util::names::weekend_name).
+#[derive(Serialize, Deserialize, Default, Debug, Hash)]
+#[serde(default)]
+pub struct WeekendNameConfig {
+    /// Names replaced as a whole, checked first.
+    pub renames: Vec<WeekendRename>,
+    /// Drop the `FORMULA 1` prefix, the `sponsors` and a trailing year from
+    /// every other name.
+    pub normalize: bool,
+    /// Title sponsors, like `Heineken` or `Qatar Airways`.
+    pub sponsors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Hash)]
+pub struct WeekendRename {
+    /// Stored name, compared ignoring case.
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Hash)]
+pub struct SessionNameOverride {
+    /// `f1`, `f2`, `f3`, `f1a` or `*` for every series.
+    pub series: String,
+    /// Stored session kind value.
+    pub kind: i8,
+    pub locale: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct MilestoneConfig {
+    /// Channel for the mentionless race countdown, 0 disables it.
+    pub channel: u64,
+    /// Minutes before the race start to post a milestone at.
+    pub offsets: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WebhookConfig {
+    /// `f1`, `f2`, `f3` or `f1a`.
+    pub series: String,
+    /// Webhook of the notification channel of the series, the bot still
+    /// needs Manage Messages there to clean up after the session.
+    pub url: String,
+    /// Name and avatar shown on the posts, empty keeps the webhook's own.
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub avatar_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReminderConfig {
+    /// `f1`, `f2`, `f3` or `f1a`.
+    pub series: String,
+    /// Minutes before each session start to ping at, on top of the
+    /// notification when it starts.
+    pub offsets: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LightsOutConfig {
+    /// `f1`, `f2`, `f3` or `f1a`.
+    pub series: String,
+    /// Seconds from the stored race start, usually the formation lap, to
+    /// lights out.
+    pub delay: u64,
+}
+
+/// Rate-your-race polls (`predictions` feature).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PollConfig {
+    /// Channel the polls are posted to, 0 disables them.
+    pub channel: u64,
+    /// Minutes after the race ends to post the poll.
+    pub delay: u64,
+    /// Hours the poll stays open.
+    pub duration: u64,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            channel: 0,
+            delay: 30,
+            duration: 24,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ArchiveConfig {
+    /// Channel with one thread per series and season that finished weekend
+    /// messages are copied to, 0 disables archiving.
+    pub channel: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct WatchAlongConfig {
+    /// Roles allowed to submit links with `/submit-link`, members who can
+    /// manage the server always can.
+    pub roles: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct RetentionConfig {
+    /// Days notification messages are kept in the database, 0 keeps them
+    /// forever.
+    pub notification_days: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CatchUpConfig {
+    /// Post that the session is live for sessions the bot missed the start
+    /// of, they are marked as notified either way.
+    pub notice: bool,
+    /// Mention the series role in that notice.
+    pub ping: bool,
+}
+
+impl Default for CatchUpConfig {
+    fn default() -> Self {
+        Self {
+            notice: true,
+            ping: false,
+        }
+    }
+}
+
+/// Wording of the bot messages, see [templates](crate::util::templates)
+/// for the syntax.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TemplateConfig {
+    /// One line per session of a notification. `{role}` mentions the role
+    /// in the line instead of above the first one.
+    pub notification: String,
+    /// First line of a weekend message.
+    pub weekend_title: String,
+    /// First line of a calendar entry, `{series.shared}` names the series in
+    /// calendars shared by several of them. `{weekend.round}` is "Round 7 — "
+    /// for the feeder series in both titles.
+    pub calendar_title: String,
+    /// Time of a session in weekend messages and calendars.
+    pub session: String,
+}
+
+impl TemplateConfig {
+    pub const NOTIFICATION_PLACEHOLDERS: &[&str] = &[
+        "role",
+        "series",
+        "weekend.icon",
+        "weekend.name",
+        "session.name",
+        "session.start",
+        "session.relative",
+    ];
+    pub const SESSION_PLACEHOLDERS: &[&str] = &[
+        "session.start",
+        "session.relative",
+        "session.track_time",
+        "session.weather",
+    ];
+    pub const TITLE_PLACEHOLDERS: &[&str] = &[
+        "series",
+        "series.shared",
+        "weekend.icon",
+        "weekend.name",
+        "weekend.provisional",
+        "weekend.round",
+    ];
+
+    fn validate(&self) -> Result<(), String> {
+        let checks = [
+            (
+                "notification",
+                &self.notification,
+                Self::NOTIFICATION_PLACEHOLDERS,
+            ),
+            ("weekend_title", &self.weekend_title, Self::TITLE_PLACEHOLDERS),
+            ("calendar_title", &self.calendar_title, Self::TITLE_PLACEHOLDERS),
+            ("session", &self.session, Self::SESSION_PLACEHOLDERS),
+        ];
+        for (name, template, allowed) in checks {
+            templates::check(template, allowed)
+                .map_err(|why| format!("templates.{name}: {why}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for TemplateConfig {
+    fn default() -> Self {
+        Self {
+            notification: "{weekend.icon} {weekend.name} {session.name} is \
+                           starting: {session.relative}"
+                .to_owned(),
+            weekend_title: "{weekend.icon} {weekend.round}{weekend.name}\
+                            {weekend.provisional}"
+                .to_owned(),
+            calendar_title: "{weekend.icon} **{weekend.round}{weekend.name}**\
+                             {series.shared}{weekend.provisional}"
+                .to_owned(),
+            session: "{session.start} ({session.relative}){session.track_time}{session.weather}"
+                .to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Address for the HTTP API (`http-api` feature), empty disables it.
+    pub bind: String,
+    /// Seconds without a bot loop tick or database query after which
+    /// `/healthz` and `/readyz` fail. Keep it above `scheduler.max_idle`.
+    pub stale_after: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            bind: String::new(),
+            stale_after: 5 * 60,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct JolpicaConfig {
+    /// Seconds between F1 calendar syncs from the Jolpica API (`jolpica`
+    /// feature), 0 disables them.
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ResultsConfig {
+    /// Seconds between checks for new or revised session results (`results`
+    /// feature), 0 disables them.
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct StandingsConfig {
+    /// Channel for the F1 championship standings message, refreshed with the
+    /// results (`results` feature). 0 disables it.
+    pub channel: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct OpenF1Config {
+    /// Seconds between race control checks during live F1 sessions (`openf1`
+    /// feature), 0 disables them.
+    pub interval: u64,
+}
+
+/// Observer-only deployment posting weekend messages and calendars into a
+/// private staging channel from the production database, without pings,
+/// notifications or any writes to the schedule.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct MirrorConfig {
+    /// Staging channel, 0 runs a regular deployment.
+    pub channel: u64,
+    /// Staging channel for the calendars, separate from `channel` for the
+    /// same reason as the production calendar channels.
+    pub calendar_channel: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct WeatherConfig {
+    /// Seconds between forecast refreshes from Open-Meteo (`weather`
+    /// feature), 0 disables them. A few times a day is plenty.
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct IcsExportConfig {
+    /// Directory the iCalendar feeds are written to with every calendar
+    /// update, empty disables writing them. The HTTP API serves them either
+    /// way.
+    pub directory: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IcsSource {
+    /// `f1`, `f2`, `f3` or `f1a`.
+    pub series: String,
+    /// `http(s)://` URL or path of the ICS file.
+    pub location: String,
+    /// Splits an event summary into the weekend name and the session title
+    /// at its last occurrence, `Bahrain Grand Prix - Race`.
+    #[serde(default = "default_ics_separator")]
+    pub separator: String,
+}
+
+fn default_ics_separator() -> String {
+    " - ".to_owned()
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MqttConfig {
+    /// Broker host (`mqtt` feature), empty disables publishing.
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    /// Leave empty for brokers without authentication.
+    pub username: String,
+    pub password: String,
+    /// Events are published to `<topic_prefix>/<series>`.
+    pub topic_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 1883,
+            client_id: "f1-notif-bot".to_owned(),
+            username: String::new(),
+            password: String::new(),
+            topic_prefix: "f1-notif-bot".to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct StageConfig {
+    /// Stage channel opened while a race is live, 0 disables it.
+    pub channel: u64,
+    /// Stage topic, `{weekend}` and `{session}` are filled in.
+    pub topic: String,
+}
+
+impl Default for StageConfig {
+    fn default() -> Self {
+        Self {
+            channel: 0,
+            topic: "{weekend}: {session}".to_owned(),
+        }
+    }
+}
+
+/// When the bot loop runs, see [schedule](crate::bot::schedule).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Longest sleep in seconds between two iterations with nothing due,
+    /// for the stage, watch-alongs and changes made outside the bot.
+    pub max_idle: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_idle: 60,
+        }
+    }
+}
+
+/// How often the syncs and live-status checks poll around sessions, see
+/// [cadence](crate::util::cadence).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CadenceConfig {
+    /// Seconds before a session start and after its end that count as near
+    /// the session.
+    pub window: u64,
+    /// Longest interval in seconds near a session, shorter configured
+    /// intervals are kept. 0 disables it.
+    pub near: u64,
+    /// Seconds before a session start and after its end that count as the
+    /// weekend, polling runs at the configured intervals.
+    pub weekend: u64,
+    /// Shortest interval in seconds between weekends, longer configured
+    /// intervals are kept.
+    pub idle: u64,
+}
+
+impl Default for CadenceConfig {
+    fn default() -> Self {
+        Self {
+            window: 30 * 60,
+            near: 5 * 60,
+            weekend: 24 * 60 * 60,
+            idle: 60 * 60,
+        }
+    }
+}
+
+/// Batching of runtime errors, see [alerts](crate::util::alerts).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Seconds errors are collected for before they're posted, 0 disables
+    /// the alerts.
+    pub interval: u64,
+    /// Batches posted per hour, further errors are only counted.
+    pub max_posts: u32,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            interval: 60,
+            max_posts: 6,
+        }
+    }
+}
+
+/// How long fetched third-party data is reused before fetching again, in
+/// seconds.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub schedule_ttl: u64,
+    pub results_ttl: u64,
+    pub standings_ttl: u64,
+    /// Next weekend lookups of the HTTP API and commands, 0 always reads
+    /// the database.
+    pub lookup_ttl: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            schedule_ttl: 6 * 60 * 60,
+            results_ttl: 10 * 60,
+            standings_ttl: 60 * 60,
+            lookup_ttl: 0,
+        }
+    }
+}
+
+/// Seasons the calendars and weekend messages consider, older and later
+/// weekends stay in the database but aren't loaded.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SeasonConfig {
+    /// Past seasons to keep loading next to the current one.
+    pub years_behind: u32,
+    /// Upcoming seasons to load ahead of the current one.
+    pub years_ahead: u32,
+}
+
+impl Default for SeasonConfig {
+    fn default() -> Self {
+        Self {
+            years_behind: 0,
+            years_ahead: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Seconds between clock checks, 0 disables them.
+    pub interval: u64,
+    /// Drift in seconds the admin channel is warned about.
+    pub max_drift: u64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            interval: 60 * 60,
+            max_drift: 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotificationConfig {
+    /// Sessions posting into the same channel that start within this many
+    /// seconds of each other are announced in one message.
+    pub batch_window: u64,
+    /// Role pings per series within `ping_window`, later notifications go
+    /// out without a mention. 0 never throttles.
+    #[serde(default)]
+    pub max_pings: u32,
+    /// Rolling window for `max_pings`, in seconds.
+    #[serde(default = "default_ping_window")]
+    pub ping_window: u64,
+}
+
+fn default_ping_window() -> u64 {
+    6 * 60 * 60
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            batch_window: 60,
+            max_pings: 0,
+            ping_window: default_ping_window(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -65,6 +855,21 @@ pub struct DatabaseConfig<'a> {
     pub username: Cow<'a, str>,
     pub password: Cow<'a, str>,
     pub database: Cow<'a, str>,
+    /// Attempts at connecting after a transient error before giving up.
+    #[serde(default = "default_db_retries")]
+    pub retries: u32,
+    /// First delay between attempts in milliseconds, doubled with every
+    /// attempt up to [MAX_RETRY_DELAY](crate::util::MAX_RETRY_DELAY).
+    #[serde(default = "default_db_retry_delay")]
+    pub retry_delay: u64,
+}
+
+fn default_db_retries() -> u32 {
+    5
+}
+
+fn default_db_retry_delay() -> u64 {
+    250
 }
 
 impl Default for DatabaseConfig<'_> {
@@ -74,6 +879,8 @@ impl Default for DatabaseConfig<'_> {
             username: "notifbot".into(),
             password: "password".into(),
             database: "notifbot".into(),
+            retries: default_db_retries(),
+            retry_delay: default_db_retry_delay(),
         }
     }
 }
@@ -91,6 +898,7 @@ impl Default for DiscordConfig<'_> {
             f3_role: 1033311726889861244,
             f1a_channel: 1002285400095719524,
             f1a_role: 1033311726889861244,
+            admin_channel: 0,
         }
     }
 }
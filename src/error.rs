@@ -17,6 +17,11 @@ pub enum Error {
     NotSameLen,
     ParseInt(std::num::ParseIntError),
     NNF(Box<dyn StdError>),
+    /// A stored value that doesn't map to any variant of `kind`.
+    UnknownValue {
+        kind: &'static str,
+        value: i64,
+    },
 }
 
 impl From<sqlx::Error> for Error {
@@ -71,6 +76,10 @@ impl fmt::Display for Error {
             },
             Self::ParseInt(inner) => fmt::Display::fmt(&inner, f),
             Self::NNF(inner) => fmt::Display::fmt(&inner, f),
+            Self::UnknownValue {
+                kind,
+                value,
+            } => write!(f, "Unknown {kind} value `{value}`"),
         }
     }
 }
@@ -86,6 +95,9 @@ impl StdError for Error {
             Self::NotSameLen => None,
             Self::ParseInt(inner) => Some(inner),
             Self::NNF(inner) => inner.source(),
+            Self::UnknownValue {
+                ..
+            } => None,
         }
     }
 }
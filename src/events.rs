@@ -0,0 +1,66 @@
+//! In-process bus for things the bot does, for consumers outside the main
+//! loop like the WebSocket stream.
+
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Events a lagging subscriber can fall behind by before it misses some.
+const CAPACITY: usize = 256;
+
+pub static EVENTS: LazyLock<EventBus> = LazyLock::new(EventBus::new);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum BotEvent {
+    SessionStarting {
+        series: String,
+        weekend: String,
+        session: String,
+        start: DateTime<Utc>,
+    },
+    /// A notified session is over and its notification got removed.
+    SessionEnded {
+        series: String,
+        weekend: String,
+        session: String,
+        end: DateTime<Utc>,
+    },
+    ScheduleChanged {
+        weekend: u64,
+        changes: Vec<String>,
+    },
+    NotificationSent {
+        series: String,
+        session: i64,
+        channel: u64,
+        message: u64,
+    },
+}
+
+pub struct EventBus {
+    sender: broadcast::Sender<BotEvent>,
+}
+
+impl EventBus {
+    fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+        }
+    }
+
+    /// Sends `event` to every current subscriber, it's dropped if there are
+    /// none.
+    pub fn publish(
+        &self,
+        event: BotEvent,
+    ) {
+        _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BotEvent> {
+        self.sender.subscribe()
+    }
+}
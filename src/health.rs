@@ -0,0 +1,106 @@
+//! Liveness and readiness of the bot, served as `/healthz` and `/readyz`
+//! by the HTTP API so a container orchestrator can restart a wedged bot.
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicI64, Ordering},
+    OnceLock,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+use crate::config::HttpConfig;
+
+/// When the process started and how old ticks and queries may get.
+static LIMITS: OnceLock<(DateTime<Utc>, TimeDelta)> = OnceLock::new();
+
+/// Whether the shard running the bot loop is connected to the gateway.
+static GATEWAY: AtomicBool = AtomicBool::new(false);
+
+/// Unix timestamps, 0 until the first one.
+static LAST_QUERY: AtomicI64 = AtomicI64::new(0);
+static LAST_TICK: AtomicI64 = AtomicI64::new(0);
+
+/// Applies `config`, before the bot connects.
+pub fn configure(config: &HttpConfig) {
+    let _ =
+        LIMITS.set((Utc::now(), TimeDelta::seconds(config.stale_after as i64)));
+}
+
+pub fn set_connected(connected: bool) {
+    GATEWAY.store(connected, Ordering::Relaxed);
+}
+
+/// Called after a query of the bot loop went through.
+pub fn record_query() {
+    LAST_QUERY.store(Utc::now().timestamp(), Ordering::Relaxed);
+}
+
+/// Called after every iteration of the bot loop.
+pub fn record_tick() {
+    LAST_TICK.store(Utc::now().timestamp(), Ordering::Relaxed);
+}
+
+fn load(timestamp: &AtomicI64) -> Option<DateTime<Utc>> {
+    match timestamp.load(Ordering::Relaxed) {
+        0 => None,
+        f => DateTime::from_timestamp(f, 0),
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Health {
+    pub gateway: bool,
+    pub last_query: Option<DateTime<Utc>>,
+    pub last_tick: Option<DateTime<Utc>>,
+    /// The bot loop ticked recently, or the bot is still starting.
+    pub live: bool,
+    /// Live, connected and the database answered recently.
+    pub ready: bool,
+}
+
+impl Health {
+    /// Health at `now` for a bot started at `started`, ticks and queries
+    /// older than `stale_after` count as missing.
+    pub fn at(
+        gateway: bool,
+        last_query: Option<DateTime<Utc>>,
+        last_tick: Option<DateTime<Utc>>,
+        started: DateTime<Utc>,
+        stale_after: TimeDelta,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let recent = |f: Option<DateTime<Utc>>| {
+            f.is_some_and(|f| now - f <= stale_after)
+        };
+        // a loop that never ticked gets as long as a tick may take
+        let live = match last_tick {
+            Some(_) => recent(last_tick),
+            None => now - started <= stale_after,
+        };
+        Self {
+            gateway,
+            last_query,
+            last_tick,
+            live,
+            ready: live && gateway && recent(last_query) && recent(last_tick),
+        }
+    }
+}
+
+/// The current [Health].
+pub fn check() -> Health {
+    let now = Utc::now();
+    let (started, stale_after) = LIMITS.get().copied().unwrap_or((
+        now,
+        TimeDelta::seconds(HttpConfig::default().stale_after as i64),
+    ));
+    Health::at(
+        GATEWAY.load(Ordering::Relaxed),
+        load(&LAST_QUERY),
+        load(&LAST_TICK),
+        started,
+        stale_after,
+        now,
+    )
+}
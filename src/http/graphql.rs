@@ -0,0 +1,210 @@
+//! GraphQL view of the schedule, served on `/graphql`.
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, Object, Result, Schema,
+};
+use chrono::{DateTime, Utc};
+use f1_bot_types::{Message, Series, Session, Weekend};
+use sqlx::MySqlPool;
+
+use crate::util::{
+    fetch_messages_page, fetch_sessions, fetch_weekend, fetch_weekends,
+    KindCode, Page, SeasonWindow,
+};
+
+pub type ScheduleSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema(database: MySqlPool) -> ScheduleSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(database)
+        .finish()
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "Series")]
+pub enum GqlSeries {
+    F1,
+    F2,
+    F3,
+    F1Academy,
+}
+
+impl From<Series> for GqlSeries {
+    fn from(value: Series) -> Self {
+        match value {
+            Series::F1 => Self::F1,
+            Series::F2 => Self::F2,
+            Series::F3 => Self::F3,
+            Series::F1Academy => Self::F1Academy,
+        }
+    }
+}
+
+impl From<GqlSeries> for Series {
+    fn from(value: GqlSeries) -> Self {
+        match value {
+            GqlSeries::F1 => Self::F1,
+            GqlSeries::F2 => Self::F2,
+            GqlSeries::F3 => Self::F3,
+            GqlSeries::F1Academy => Self::F1Academy,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Weekends in start order. `status` takes the stored status value.
+    async fn weekends(
+        &self,
+        ctx: &Context<'_>,
+        series: Option<GqlSeries>,
+        year: Option<i32>,
+        status: Option<i8>,
+    ) -> Result<Vec<WeekendObject>> {
+        let mut conn = ctx.data::<MySqlPool>()?.acquire().await?;
+        let weekends = fetch_weekends(conn.as_mut(), SeasonWindow::ALL).await?;
+        Ok(weekends
+            .into_iter()
+            .filter(|f| {
+                series.is_none_or(|s| f.series.i8() == Series::from(s).i8())
+            })
+            .filter(|f| year.is_none_or(|y| i64::from(f.year) == i64::from(y)))
+            .filter(|f| status.is_none_or(|s| f.status.i8() == s))
+            .map(WeekendObject)
+            .collect())
+    }
+
+    async fn weekend(
+        &self,
+        ctx: &Context<'_>,
+        id: u64,
+    ) -> Result<Option<WeekendObject>> {
+        let mut conn = ctx.data::<MySqlPool>()?.acquire().await?;
+        Ok(fetch_weekend(conn.as_mut(), id).await?.map(WeekendObject))
+    }
+
+    /// Messages the bot keeps track of, newest first. `kind` takes the
+    /// stored kind value, `limit` is capped at 100.
+    async fn messages(
+        &self,
+        ctx: &Context<'_>,
+        series: Option<GqlSeries>,
+        kind: Option<i8>,
+        #[graphql(default = 25)] limit: u32,
+        #[graphql(default)] offset: u64,
+    ) -> Result<Vec<MessageObject>> {
+        let mut conn = ctx.data::<MySqlPool>()?.acquire().await?;
+        let messages = fetch_messages_page(
+            conn.as_mut(),
+            series.map(Series::from),
+            kind,
+            Page::new(limit, offset),
+        )
+        .await?;
+        Ok(messages.into_iter().map(MessageObject).collect())
+    }
+}
+
+pub struct WeekendObject(Weekend);
+
+#[Object(name = "Weekend")]
+impl WeekendObject {
+    async fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn icon(&self) -> &str {
+        &self.0.icon
+    }
+
+    async fn start_date(&self) -> DateTime<Utc> {
+        self.0.start_date
+    }
+
+    async fn status(&self) -> i8 {
+        self.0.status.i8()
+    }
+
+    async fn series(&self) -> GqlSeries {
+        self.0.series.into()
+    }
+
+    async fn year(&self) -> i64 {
+        i64::from(self.0.year)
+    }
+
+    async fn sessions(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<SessionObject>> {
+        let mut conn = ctx.data::<MySqlPool>()?.acquire().await?;
+        let sessions = fetch_sessions(conn.as_mut(), &self.0).await?;
+        Ok(sessions.into_iter().map(SessionObject).collect())
+    }
+}
+
+pub struct SessionObject(Session);
+
+#[Object(name = "Session")]
+impl SessionObject {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    async fn weekend(&self) -> i64 {
+        self.0.weekend
+    }
+
+    /// Stored kind value, null for kinds the bot doesn't know.
+    async fn kind(&self) -> Option<i8> {
+        KindCode::of(&self.0.kind).map(|f| f.0)
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn start_date(&self) -> DateTime<Utc> {
+        self.0.start_date
+    }
+
+    /// Length in seconds.
+    async fn duration(&self) -> i64 {
+        self.0.duration
+    }
+
+    async fn status(&self) -> i8 {
+        self.0.status.i8()
+    }
+}
+
+pub struct MessageObject(Message);
+
+#[Object(name = "Message")]
+impl MessageObject {
+    async fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    async fn channel(&self) -> &str {
+        &self.0.channel
+    }
+
+    async fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    async fn kind(&self) -> i8 {
+        self.0.kind.i8()
+    }
+
+    async fn series(&self) -> GqlSeries {
+        self.0.series.into()
+    }
+}
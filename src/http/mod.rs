@@ -0,0 +1,148 @@
+//! Read-only HTTP endpoints for overlays and widgets.
+
+#[cfg(feature = "graphql")]
+pub mod graphql;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        Path, State, WebSocketUpgrade,
+    },
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use sqlx::MySqlPool;
+use tokio::{net::TcpListener, sync::broadcast::error::RecvError};
+use tracing::{error, info, warn};
+
+use crate::{
+    error::Error,
+    events::EVENTS,
+    health::{self, Health},
+    next::{next_events, NextEvent},
+    util::ical,
+};
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub database: MySqlPool,
+}
+
+pub fn router(state: ApiState) -> Router {
+    let router = Router::new()
+        .route("/next", get(next))
+        .route("/events", get(events))
+        .route("/calendar/:feed", get(calendar))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz));
+    #[cfg(feature = "graphql")]
+    let router = router.route_service(
+        "/graphql",
+        async_graphql_axum::GraphQL::new(graphql::schema(
+            state.database.clone(),
+        )),
+    );
+    router.with_state(state)
+}
+
+/// Serves the API on `bind` until the listener fails.
+pub async fn serve(
+    bind: &str,
+    database: MySqlPool,
+) -> Result<(), Error> {
+    let listener = TcpListener::bind(bind).await?;
+    info!("Serving HTTP API on {bind}");
+    axum::serve(
+        listener,
+        router(ApiState {
+            database,
+        }),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn next(
+    State(state): State<ApiState>
+) -> Result<Json<Vec<NextEvent>>, StatusCode> {
+    let mut conn = state.database.acquire().await.map_err(|why| {
+        error!("{why:#?}");
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+    next_events(conn.as_mut(), Utc::now()).await.map(Json).map_err(|why| {
+        error!("{why:#?}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// `/calendar/<series>.ics`, or `/calendar/all.ics` for every series.
+async fn calendar(
+    State(state): State<ApiState>,
+    Path(feed): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let series = feed
+        .strip_suffix(".ics")
+        .and_then(ical::feed_series)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let mut conn = state.database.acquire().await.map_err(|why| {
+        error!("{why:#?}");
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+    let content = ical::feed(conn.as_mut(), series).await.map_err(|why| {
+        error!("{why:#?}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], content))
+}
+
+/// 503 once the bot loop stopped ticking, restarting is the only fix.
+async fn healthz() -> (StatusCode, Json<Health>) {
+    let health = health::check();
+    (status(health.live), Json(health))
+}
+
+/// 503 while the gateway is down or the database doesn't answer.
+async fn readyz() -> (StatusCode, Json<Health>) {
+    let health = health::check();
+    (status(health.ready), Json(health))
+}
+
+fn status(ok: bool) -> StatusCode {
+    match ok {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+async fn events(ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(stream_events)
+}
+
+/// Forwards every [BotEvent](crate::events::BotEvent) as JSON text until the
+/// client goes away.
+async fn stream_events(mut socket: WebSocket) {
+    let mut events = EVENTS.subscribe();
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                warn!("WebSocket client missed {missed} events");
+                continue;
+            },
+            Err(RecvError::Closed) => return,
+        };
+        let json = match serde_json::to_string(&event) {
+            Ok(json) => json,
+            Err(why) => {
+                error!("{why:#?}");
+                continue;
+            },
+        };
+        if socket.send(Message::Text(json)).await.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Calendars from ICS files, as published for F2, F3 and F1 Academy.
+//!
+//! Every `VEVENT` is one session, its summary holds the weekend name and the
+//! session title. Events are grouped into weekends by name and go through
+//! [sync_weekend] like the other upstream sources.
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serenity::all::CacheHttp;
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{Config, IcsSource},
+    error::Error,
+    sync::{sync_weekend, IncomingSession, SessionSource},
+    util::{
+        acquire, fetch_kind_for_title, fetch_weekend_by_name, names, switches,
+        SessionDuration,
+    },
+};
+
+/// Used for events with neither `DTEND` nor `DURATION`.
+const DEFAULT_DURATION: i64 = 3600;
+
+#[derive(Debug, Default)]
+struct Event {
+    summary: String,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    duration: Option<i64>,
+    cancelled: bool,
+}
+
+/// Joins folded lines, continuations start with a space or a tab.
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in input.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=VALUE:value`, colons in quoted parameters don't end
+/// the name.
+fn split_line(line: &str) -> Option<(&str, &str, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        },
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name, params, value))
+}
+
+fn param<'a>(
+    params: &'a str,
+    key: &str,
+) -> Option<&'a str> {
+    params.split(';').find_map(|f| {
+        let (name, value) = f.split_once('=')?;
+        name.eq_ignore_ascii_case(key).then(|| value.trim_matches('"'))
+    })
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => {},
+        }
+    }
+    out
+}
+
+/// UTC (`Z`), `TZID` and floating times, floating ones are taken as UTC.
+/// All-day dates aren't sessions and return [None].
+fn parse_time(
+    params: &str,
+    value: &str,
+) -> Option<DateTime<Utc>> {
+    if param(params, "VALUE") == Some("DATE") {
+        return None;
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(naive.and_utc());
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    match param(params, "TZID") {
+        Some(tzid) => tzid
+            .parse::<Tz>()
+            .ok()?
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|f| f.with_timezone(&Utc)),
+        None => Some(naive.and_utc()),
+    }
+}
+
+/// Seconds of a `DURATION` value such as `PT1H30M` or `P1DT2H`.
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let mut rest = value.strip_prefix('P')?;
+    let mut seconds = 0;
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let designator = rest[digits..].chars().next()?;
+        let unit = match (designator, in_time) {
+            ('W', false) => 604800,
+            ('D', false) => 86400,
+            ('H', true) => 3600,
+            ('M', true) => 60,
+            ('S', true) => 1,
+            _ => return None,
+        };
+        seconds += amount * unit;
+        rest = &rest[digits + designator.len_utf8()..];
+    }
+    Some(seconds)
+}
+
+fn parse_events(input: &str) -> Vec<Event> {
+    let mut events = vec![];
+    let mut current: Option<Event> = None;
+    for line in unfold(input) {
+        let Some((name, params, value)) = split_line(&line) else {
+            continue;
+        };
+        match (name.to_ascii_uppercase().as_str(), current.as_mut()) {
+            ("BEGIN", None) if value == "VEVENT" => {
+                current = Some(Event::default());
+            },
+            ("END", Some(_)) if value == "VEVENT" => {
+                events.extend(current.take());
+            },
+            ("SUMMARY", Some(event)) => event.summary = unescape(value),
+            ("DTSTART", Some(event)) => event.start = parse_time(params, value),
+            ("DTEND", Some(event)) => event.end = parse_time(params, value),
+            ("DURATION", Some(event)) => event.duration = parse_duration(value),
+            ("STATUS", Some(event)) => {
+                event.cancelled = value.eq_ignore_ascii_case("CANCELLED")
+            },
+            _ => {},
+        }
+    }
+    events
+}
+
+async fn read(location: &str) -> Result<String, Error> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let response = reqwest::get(location)
+            .await
+            .and_then(|f| f.error_for_status())
+            .map_err(|f| Error::NNF(f.into()))?;
+        return response.text().await.map_err(|f| Error::NNF(f.into()));
+    }
+    tokio::fs::read_to_string(location).await.map_err(|f| Error::NNF(f.into()))
+}
+
+/// Imports one calendar, returns the number of weekends it touched.
+pub async fn import(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+    source: &IcsSource,
+) -> Result<usize, Error> {
+    let Some(series) = names::series_from_key(&source.series) else {
+        return Err(Error::NNF(
+            format!("Unknown series `{}`", source.series).into(),
+        ));
+    };
+    let events = parse_events(&read(&source.location).await?);
+
+    // (weekend name, sessions) in calendar order
+    let mut weekends: Vec<(String, Vec<IncomingSession>)> = vec![];
+    for event in events {
+        let Some(start_date) = event.start else {
+            continue;
+        };
+        let Some((weekend, title)) =
+            event.summary.rsplit_once(&source.separator)
+        else {
+            warn!(
+                "Couldn't split `{}` into weekend and session",
+                event.summary
+            );
+            continue;
+        };
+        let (weekend, title) = (weekend.trim(), title.trim());
+        let Some(kind) = fetch_kind_for_title(db_conn, series, title).await?
+        else {
+            warn!("No stored session `{title}` to take a kind from, skipped");
+            continue;
+        };
+        let duration = event
+            .end
+            .map(|f| (f - start_date).num_seconds())
+            .or(event.duration)
+            .filter(|f| *f > 0)
+            .unwrap_or(DEFAULT_DURATION);
+        // longer events are multi-day entries rather than sessions
+        let duration = match SessionDuration::from_secs(duration) {
+            Ok(duration) => duration,
+            Err(why) => {
+                warn!("`{}`: {why}, skipped", event.summary);
+                continue;
+            },
+        };
+        let session = IncomingSession {
+            kind,
+            title: title.to_owned(),
+            start_date,
+            duration,
+            cancelled: event.cancelled,
+        };
+        match weekends.iter_mut().find(|f| f.0 == weekend) {
+            Some((_, sessions)) => sessions.push(session),
+            None => weekends.push((weekend.to_owned(), vec![session])),
+        }
+    }
+
+    let mut synced = 0;
+    let http = &http;
+    for (name, incoming) in weekends {
+        let year = incoming
+            .iter()
+            .map(|f| f.start_date.year())
+            .min()
+            .unwrap_or_default();
+        let existing =
+            fetch_weekend_by_name(db_conn, series, &name, year).await?;
+        if sync_weekend(
+            db_conn,
+            http,
+            config,
+            existing,
+            series,
+            &name,
+            SessionSource::Ics,
+            incoming,
+        )
+        .await?
+        .is_some()
+        {
+            if existing.is_none() {
+                info!("Added {series} weekend `{name}` from ICS");
+            }
+            synced += 1;
+        }
+    }
+    Ok(synced)
+}
+
+/// Imports every configured calendar, errors are logged per calendar. Waits
+/// for a `/pause` to end first.
+pub async fn import_all(
+    pool: MySqlPool,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+) {
+    while let Some(until) = switches::paused_until() {
+        info!("Paused, importing the calendars after {until}");
+        let wait = (until - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+    let mut db_conn = match acquire(&pool).await {
+        Ok(db_conn) => db_conn,
+        Err(why) => {
+            error!("{why:#?}");
+            return;
+        },
+    };
+    for source in config.ics.iter() {
+        match import(db_conn.as_mut(), &http, config, source).await {
+            Ok(synced) => {
+                info!("Imported {synced} weekends from {}", source.location)
+            },
+            Err(why) => {
+                error!("Couldn't import {}: {why}", source.location)
+            },
+        }
+    }
+}
@@ -0,0 +1,3 @@
+//! Schedule imports from files, for series without an API to sync from.
+
+pub mod ics;
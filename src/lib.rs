@@ -0,0 +1,29 @@
+//! Scheduling, storage and rendering for the F1 notification bot.
+//!
+//! The binary wires these into a Discord client, other projects can use
+//! [`util::FullWeekend`] and the helpers around it directly.
+
+pub mod bot;
+pub mod commands;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod health;
+#[cfg(feature = "http-api")]
+pub mod http;
+#[cfg(feature = "ics")]
+pub mod ingest;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod next;
+#[cfg(feature = "predictions")]
+pub mod polls;
+#[cfg(feature = "results")]
+pub mod results;
+pub mod sync;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod util;
+pub mod watchalong;
+#[cfg(feature = "weather")]
+pub mod weather;
@@ -1,31 +1,30 @@
-pub mod bot;
-pub mod config;
-pub mod error;
-pub mod util;
-
 use anyhow::anyhow;
-use sqlx::{mysql::MySqlConnectOptions, MySqlPool};
-use std::{fs::File, io::Read, sync::atomic::AtomicBool};
+use std::{path::Path, process::exit, sync::atomic::AtomicBool, time::Instant};
 
-use config::Config;
+use f1_notif_bot::{
+    bot::Bot,
+    config::{Config, CAT_PATH, CONFIG_PATH},
+    health,
+    util::{self, handle_config_error},
+};
 use serenity::{client::ClientBuilder, prelude::GatewayIntents};
-
-use crate::{bot::Bot, util::handle_config_error};
+use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<(), String> {
+    #[cfg(feature = "otel")]
+    let _telemetry = f1_notif_bot::telemetry::init();
+    #[cfg(not(feature = "otel"))]
     tracing_subscriber::fmt().init();
 
-    let mut config = match File::open("./config/config.toml") {
-        Ok(config) => config,
-        Err(why) => handle_config_error(why),
+    let phase = Instant::now();
+    let string = match tokio::fs::read_to_string(CONFIG_PATH).await {
+        Ok(string) => string,
+        Err(why) => {
+            handle_config_error(why).await;
+            exit(0x0100)
+        },
     };
-    let mut string = "".to_owned();
-    if let Err(why) = config.read_to_string(&mut string) {
-        return Err(
-            anyhow!("Error reading config file: \n\t`{why}`").to_string()
-        );
-    }
     let config = match toml::from_str::<Config>(string.as_str()) {
         Ok(config) => config,
         Err(why) => {
@@ -34,14 +33,13 @@ async fn main() -> Result<(), String> {
             )
         },
     };
+    if let Err(why) = config.validate() {
+        return Err(anyhow!("Invalid config file:\n\t`{why}`").to_string());
+    }
+    info!("Loaded config in {:?}", phase.elapsed());
 
-    let db_options = MySqlConnectOptions::new()
-        .username(&config.database.username)
-        .password(&config.database.password)
-        .host(&config.database.url)
-        .port(3306)
-        .database("fia-docs");
-    let database = match MySqlPool::connect_with(db_options).await {
+    let phase = Instant::now();
+    let database = match util::connect(&config.database).await {
         Ok(db) => db,
         Err(why) => {
             return Err(
@@ -49,29 +47,61 @@ async fn main() -> Result<(), String> {
             )
         },
     };
+    info!("Connected to database in {:?}", phase.elapsed());
 
-    let Ok(mut cat_video) = File::open("./config/cats.mp4") else {
-        return Err(anyhow!("Error opening the cat.").to_string());
-    };
+    let phase = Instant::now();
+    if let Err(why) = util::migrate(&database).await {
+        return Err(anyhow!("Error running migrations:\n\t`{why}`").to_string());
+    }
+    info!("Ran migrations in {:?}", phase.elapsed());
+    #[cfg(debug_assertions)]
+    if let Err(why) = util::check_query_plans(&database).await {
+        tracing::warn!("Couldn't check query plans: {why}");
+    }
 
-    let Ok(cat_meta) = cat_video.metadata() else {
-        return Err(anyhow!("No metadata on the cat.").to_string());
-    };
-    let mut cat_data = Vec::with_capacity(cat_meta.len() as usize);
+    // the cat is read from disk for each notification, only make sure it's
+    // there before going online.
+    match tokio::fs::metadata(CAT_PATH).await {
+        Ok(meta) if meta.is_file() => {},
+        Ok(_) => return Err(anyhow!("The cat is not a file.").to_string()),
+        Err(_) => return Err(anyhow!("Error opening the cat.").to_string()),
+    }
 
-    let Ok(_) = cat_video.read_to_end(&mut cat_data) else {
-        return Err(anyhow!("Can't see the cats insides.").to_string());
-    };
+    let config: &'static Config = Box::leak(Box::new(config));
+    util::crash::install(
+        &config.discord.bot_token,
+        config.discord.admin_channel,
+    );
+    util::lookups::configure(&config.cache);
+    util::templates::configure(&config.templates);
+    util::instance::configure(&config.instance);
+    health::configure(&config.http);
+
+    #[cfg(feature = "http-api")]
+    if !config.http.bind.is_empty() {
+        let database = database.clone();
+        tokio::spawn(async move {
+            if let Err(why) =
+                f1_notif_bot::http::serve(&config.http.bind, database).await
+            {
+                tracing::error!("{why:#?}");
+            }
+        });
+    }
 
-    let config = Box::leak(Box::new(config));
+    #[cfg(feature = "mqtt")]
+    if !config.mqtt.host.is_empty() {
+        tokio::spawn(f1_notif_bot::mqtt::run(&config.mqtt));
+    }
 
     let bot = Bot {
         is_mainthread_running: AtomicBool::new(false),
         config,
         database: Box::leak(Box::new(database)),
-        cat: cat_data.leak(),
+        cat: Path::new(CAT_PATH),
     };
 
+    let phase = Instant::now();
     let mut client = match ClientBuilder::new(
         &bot.config.discord.bot_token,
         GatewayIntents::non_privileged(),
@@ -85,6 +115,7 @@ async fn main() -> Result<(), String> {
                 .to_string())
         },
     };
+    info!("Created discord client in {:?}", phase.elapsed());
 
     client.start_autosharded().await.map_err(|f| f.to_string())
 }
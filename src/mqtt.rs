@@ -0,0 +1,79 @@
+//! Publishes session start and end events to an MQTT broker, one topic per
+//! series, for home automation.
+
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, MqttOptions, QoS};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::MqttConfig,
+    events::{BotEvent, EVENTS},
+};
+
+/// `prefix/f1-academy` style topic for `series`.
+fn topic(
+    prefix: &str,
+    series: &str,
+) -> String {
+    format!("{prefix}/{}", series.to_lowercase().replace(' ', "-"))
+}
+
+/// Forwards session events from the event bus until the bus closes.
+pub async fn run(config: &MqttConfig) {
+    let mut options =
+        MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if !config.username.is_empty() {
+        options.set_credentials(&config.username, &config.password);
+    }
+    let (client, mut event_loop) = AsyncClient::new(options, 16);
+
+    // rumqttc only makes progress (and reconnects) while the loop is polled
+    tokio::spawn(async move {
+        loop {
+            if let Err(why) = event_loop.poll().await {
+                warn!("MQTT connection error: {why}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    });
+    info!("Publishing session events to {}:{}", config.host, config.port);
+
+    let mut events = EVENTS.subscribe();
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                warn!("MQTT publisher missed {missed} events");
+                continue;
+            },
+            Err(RecvError::Closed) => return,
+        };
+        let series = match &event {
+            BotEvent::SessionStarting {
+                series,
+                ..
+            }
+            | BotEvent::SessionEnded {
+                series,
+                ..
+            } => series,
+            _ => continue,
+        };
+        let topic = topic(&config.topic_prefix, series);
+        let payload = match serde_json::to_vec(&event) {
+            Ok(payload) => payload,
+            Err(why) => {
+                error!("{why:#?}");
+                continue;
+            },
+        };
+        if let Err(why) =
+            client.publish(topic, QoS::AtLeastOnce, false, payload).await
+        {
+            error!("{why:#?}");
+        }
+    }
+}
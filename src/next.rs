@@ -0,0 +1,64 @@
+//! Structured view of the upcoming sessions across all series, the same data
+//! the weekend messages are rendered from.
+
+use chrono::{DateTime, Utc};
+use f1_bot_types::SessionStatus;
+use serde::Serialize;
+use sqlx::MySqlConnection;
+
+use crate::util::{lookups, names::ALL_SERIES, session_end, SessionDuration};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NextEvent {
+    pub series: String,
+    pub weekend: String,
+    pub icon: String,
+    pub session: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Seconds until `start`, negative once the session is live.
+    pub starts_in: i64,
+    pub live: bool,
+    /// The weekend is shown but its dates aren't confirmed yet.
+    pub provisional: bool,
+}
+
+/// Returns the sessions that haven't ended yet from every series' next
+/// weekend, soonest first.
+pub async fn next_events(
+    db_conn: &mut MySqlConnection,
+    now: DateTime<Utc>,
+) -> Result<Vec<NextEvent>, sqlx::Error> {
+    let mut events = vec![];
+    for series in ALL_SERIES {
+        let Some(weekend) = lookups::next_full_weekend(db_conn, series).await?
+        else {
+            continue;
+        };
+        for session in weekend.sessions.iter() {
+            if session.status == SessionStatus::Cancelled {
+                continue;
+            }
+            let end =
+                session_end(session.start_date, SessionDuration::of(session));
+            if end <= now {
+                continue;
+            }
+            let starts_in =
+                session.start_date.signed_duration_since(now).num_seconds();
+            events.push(NextEvent {
+                series: series.to_string(),
+                weekend: weekend.weekend.name.clone(),
+                icon: weekend.weekend.icon.clone(),
+                session: session.title.clone(),
+                start: session.start_date,
+                end,
+                starts_in,
+                live: starts_in <= 0,
+                provisional: weekend.meta.provisional,
+            });
+        }
+    }
+    events.sort_by_key(|f| f.start);
+    Ok(events)
+}
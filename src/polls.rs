@@ -0,0 +1,170 @@
+//! Rate-your-race polls posted after each race, with the collected ratings
+//! stored for a season average.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, TimeDelta, Utc};
+use f1_bot_types::Series;
+use serenity::all::{
+    CacheHttp, ChannelId, CreateMessage, CreatePoll, CreatePollAnswer,
+    MessageId,
+};
+use sqlx::MySqlConnection;
+
+use crate::{
+    config::PollConfig,
+    error::Error,
+    util::{
+        fetch_closed_race_ratings, fetch_ended_sessions, fetch_race_rating,
+        fetch_season_race_ratings, fetch_weekend, insert_race_rating, is_race,
+        set_race_rating_results, RaceRating,
+    },
+};
+
+/// Races ending longer ago than this don't get a poll anymore, so a bot that
+/// was down over the weekend doesn't post stale ones.
+const POLL_CUTOFF: TimeDelta = TimeDelta::days(1);
+
+/// Posts polls for races that ended `config.delay` minutes ago and collects
+/// the ones that closed.
+pub async fn tick(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &PollConfig,
+) -> Result<(), Error> {
+    if config.channel == 0 {
+        return Ok(());
+    }
+    let now = Utc::now();
+    post_due(db_conn, &http, config, now).await?;
+    collect_closed(db_conn, &http, config, now).await
+}
+
+async fn post_due(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &PollConfig,
+    now: DateTime<Utc>,
+) -> Result<(), Error> {
+    let until = now - TimeDelta::minutes(config.delay as i64);
+    let ended =
+        fetch_ended_sessions(db_conn, until - POLL_CUTOFF, until).await?;
+    for session in ended.iter().filter(|f| is_race(f)) {
+        if fetch_race_rating(db_conn, session.id).await?.is_some() {
+            continue;
+        }
+        let Some(weekend) =
+            fetch_weekend(db_conn, session.weekend as u64).await?
+        else {
+            continue;
+        };
+        let answers = (1..=10)
+            .map(|f| CreatePollAnswer::new().text(f.to_string()))
+            .collect::<Vec<_>>();
+        let poll = CreatePoll::new()
+            .question(format!(
+                "How would you rate the {} {}?",
+                weekend.name, session.title
+            ))
+            .answers(answers)
+            .duration(Duration::from_secs(config.duration * 60 * 60));
+        let message = ChannelId::new(config.channel)
+            .send_message(http.http(), CreateMessage::new().poll(poll))
+            .await?;
+        insert_race_rating(
+            db_conn,
+            session.id,
+            weekend.series,
+            config.channel,
+            message.id.get(),
+            now + TimeDelta::hours(config.duration as i64),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn collect_closed(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &PollConfig,
+    now: DateTime<Utc>,
+) -> Result<(), Error> {
+    for rating in fetch_closed_race_ratings(db_conn, now).await? {
+        let message = ChannelId::new(rating.channel)
+            .message(http.http(), rating.message)
+            .await?;
+        let Some(poll) = message.poll else {
+            continue;
+        };
+        let Some(results) = poll.results.filter(|f| f.is_finalized) else {
+            // Discord finalizes a little after the poll closes
+            continue;
+        };
+        let (mut votes, mut total) = (0, 0);
+        for count in results.answer_counts.iter() {
+            let score = poll
+                .answers
+                .iter()
+                .find(|f| f.answer_id == count.id)
+                .and_then(|f| f.poll_media.text.as_ref())
+                .and_then(|f| f.parse::<u64>().ok());
+            if let Some(score) = score {
+                votes += count.count;
+                total += score * count.count;
+            }
+        }
+        set_race_rating_results(db_conn, rating.session, votes, total).await?;
+        let rating = RaceRating {
+            votes,
+            total,
+            collected: true,
+            ..rating
+        };
+        post_results(db_conn, &http, config, &rating).await?;
+    }
+    Ok(())
+}
+
+async fn post_results(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &PollConfig,
+    rating: &RaceRating,
+) -> Result<(), Error> {
+    let Some(average) = rating.average() else {
+        return Ok(());
+    };
+    let series = Series::from(rating.series);
+    let season =
+        fetch_season_race_ratings(db_conn, series, rating.posted.year())
+            .await?;
+    let (votes, total) =
+        season.iter().fold((0, 0), |(v, t), f| (v + f.votes, t + f.total));
+    let mut content = format!(
+        "Rated **{average:.1}/10** by {} {}.",
+        rating.votes,
+        if rating.votes == 1 {
+            "fan"
+        } else {
+            "fans"
+        }
+    );
+    if votes > 0 && season.len() > 1 {
+        content += &format!(
+            "\n-# {series} season average: {:.1}/10 over {} races",
+            total as f64 / votes as f64,
+            season.len()
+        );
+    }
+    ChannelId::new(config.channel)
+        .send_message(
+            http.http(),
+            CreateMessage::new().content(content).reference_message((
+                ChannelId::new(rating.channel),
+                MessageId::new(rating.message),
+            )),
+        )
+        .await?;
+    Ok(())
+}
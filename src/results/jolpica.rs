@@ -0,0 +1,166 @@
+//! Classifications from the Jolpica API, F1 only.
+
+use serde::Deserialize;
+
+use super::{Classification, ClassifiedDriver, ResultKind};
+use crate::error::Error;
+
+const BASE_URL: &str = "https://api.jolpi.ca/ergast/f1";
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(rename = "MRData")]
+    data: Data,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    #[serde(rename = "RaceTable")]
+    race_table: RaceTable,
+}
+
+#[derive(Deserialize)]
+struct RaceTable {
+    #[serde(rename = "Races")]
+    races: Vec<Race>,
+}
+
+#[derive(Deserialize)]
+struct Race {
+    #[serde(rename = "Results", alias = "SprintResults", default)]
+    results: Vec<RaceResult>,
+    #[serde(rename = "QualifyingResults", default)]
+    qualifying: Vec<QualifyingResult>,
+}
+
+#[derive(Deserialize)]
+struct Driver {
+    #[serde(rename = "givenName")]
+    given_name: String,
+    #[serde(rename = "familyName")]
+    family_name: String,
+}
+
+impl Driver {
+    fn name(&self) -> String {
+        format!("{} {}", self.given_name, self.family_name)
+    }
+}
+
+#[derive(Deserialize)]
+struct Constructor {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Time {
+    time: String,
+}
+
+#[derive(Deserialize)]
+struct FastestLap {
+    rank: Option<String>,
+    #[serde(rename = "Time")]
+    time: Option<Time>,
+}
+
+#[derive(Deserialize)]
+struct RaceResult {
+    position: String,
+    #[serde(rename = "Driver")]
+    driver: Driver,
+    #[serde(rename = "Constructor")]
+    constructor: Constructor,
+    status: String,
+    #[serde(rename = "Time")]
+    time: Option<Time>,
+    #[serde(rename = "FastestLap")]
+    fastest_lap: Option<FastestLap>,
+}
+
+#[derive(Deserialize)]
+struct QualifyingResult {
+    position: String,
+    #[serde(rename = "Driver")]
+    driver: Driver,
+    #[serde(rename = "Constructor")]
+    constructor: Constructor,
+    #[serde(rename = "Q1")]
+    q1: Option<String>,
+    #[serde(rename = "Q2")]
+    q2: Option<String>,
+    #[serde(rename = "Q3")]
+    q3: Option<String>,
+}
+
+fn path(kind: ResultKind) -> &'static str {
+    match kind {
+        ResultKind::Race => "results",
+        ResultKind::Sprint => "sprint",
+        ResultKind::Qualifying => "qualifying",
+    }
+}
+
+/// The classification of a session in round `round` of `season`, [None]
+/// until Jolpica has it.
+pub async fn fetch_classification(
+    client: &reqwest::Client,
+    season: i32,
+    round: u64,
+    kind: ResultKind,
+) -> Result<Option<Classification>, Error> {
+    let response = client
+        .get(format!("{BASE_URL}/{season}/{round}/{}.json", path(kind)))
+        .send()
+        .await
+        .and_then(|f| f.error_for_status())
+        .map_err(|f| Error::NNF(f.into()))?;
+    let body =
+        response.json::<Response>().await.map_err(|f| Error::NNF(f.into()))?;
+    let Some(race) = body.data.race_table.races.into_iter().next() else {
+        return Ok(None);
+    };
+    let mut fastest_lap = None;
+    let drivers = match kind {
+        ResultKind::Qualifying => race
+            .qualifying
+            .into_iter()
+            .map(|f| ClassifiedDriver {
+                position: f.position,
+                driver: f.driver.name(),
+                team: f.constructor.name,
+                // the time of the last part the driver made it into
+                time: f.q3.or(f.q2).or(f.q1).unwrap_or_default(),
+            })
+            .collect::<Vec<_>>(),
+        _ => race
+            .results
+            .into_iter()
+            .map(|f| {
+                let lap = f.fastest_lap.as_ref();
+                if lap.and_then(|f| f.rank.as_deref()) == Some("1") {
+                    let time = lap.and_then(|f| f.time.as_ref());
+                    fastest_lap = Some(match time {
+                        Some(time) => {
+                            format!("{} ({})", f.driver.name(), time.time)
+                        },
+                        None => f.driver.name(),
+                    });
+                }
+                ClassifiedDriver {
+                    position: f.position,
+                    driver: f.driver.name(),
+                    team: f.constructor.name,
+                    time: f.time.map(|f| f.time).unwrap_or(f.status),
+                }
+            })
+            .collect::<Vec<_>>(),
+    };
+    if drivers.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Classification {
+        drivers,
+        fastest_lap,
+    }))
+}
@@ -0,0 +1,340 @@
+//! Results posted into the series channel after races and qualifying, and
+//! edited when the classification is revised.
+//!
+//! Only F1 has a source so far, the other series are skipped.
+
+mod jolpica;
+pub mod standings;
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{Datelike, TimeDelta, Utc};
+use f1_bot_types::{Series, Session};
+use serenity::all::{
+    CacheHttp, ChannelId, CreateEmbed, CreateMessage, EditMessage, Http,
+};
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    error::Error,
+    util::{
+        acquire, fetch_ended_sessions, fetch_full_weekend, render, stable_hash,
+        switches, Cadence, FullWeekend,
+    },
+};
+
+/// Sessions ending longer ago than this aren't checked for revised results
+/// anymore.
+const RESULTS_WINDOW: TimeDelta = TimeDelta::days(3);
+
+/// Drivers shown in a results message.
+const SHOWN: usize = 10;
+
+/// Sessions that get a results message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultKind {
+    Race,
+    Sprint,
+    Qualifying,
+}
+
+impl ResultKind {
+    /// Goes by the title like [is_race](crate::util::is_race), sprint
+    /// qualifying has no classification upstream.
+    pub fn of(session: &Session) -> Option<Self> {
+        let title = session.title.to_ascii_lowercase();
+        if title.contains("sprint") && title.contains("qualifying")
+            || title.contains("shootout")
+        {
+            None
+        } else if title.contains("qualifying") {
+            Some(Self::Qualifying)
+        } else if title.contains("sprint") {
+            Some(Self::Sprint)
+        } else if title.contains("race") {
+            Some(Self::Race)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Hash)]
+pub struct ClassifiedDriver {
+    /// Position as reported, `R` and the like for unclassified drivers.
+    pub position: String,
+    pub driver: String,
+    pub team: String,
+    /// Race time, gap or status, best lap time for qualifying.
+    pub time: String,
+}
+
+#[derive(Debug, Hash)]
+pub struct Classification {
+    pub drivers: Vec<ClassifiedDriver>,
+    /// Driver and time of the fastest lap.
+    pub fastest_lap: Option<String>,
+}
+
+impl Classification {
+    fn fingerprint(&self) -> u64 {
+        stable_hash(self)
+    }
+}
+
+/// The results message of `session`.
+pub fn results_embed(
+    weekend: &FullWeekend,
+    session: &Session,
+    classification: &Classification,
+) -> CreateEmbed {
+    let lines = classification
+        .drivers
+        .iter()
+        .take(SHOWN)
+        .map(|f| {
+            format!(
+                "`{:>2}` **{}** {} · {}",
+                f.position, f.driver, f.team, f.time
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut embed = CreateEmbed::new()
+        .title(format!(
+            "{} {} {} results",
+            weekend.weekend.icon, weekend.weekend.name, session.title
+        ))
+        .colour(render::colour(weekend.weekend.series))
+        .description(lines);
+    if let Some(fastest_lap) = &classification.fastest_lap {
+        embed = embed.field("Fastest lap", fastest_lap, false);
+    }
+    embed
+}
+
+/// A posted results message.
+#[derive(Debug)]
+pub struct SessionResult {
+    pub session: i64,
+    pub series: i8,
+    pub channel: u64,
+    pub message: u64,
+    pub hash: String,
+}
+
+async fn fetch_session_result(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+) -> Result<Option<SessionResult>, sqlx::Error> {
+    sqlx::query_as!(
+        SessionResult,
+        "SELECT session, series, channel, message, hash FROM session_results
+WHERE session = ?",
+        session
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+async fn insert_session_result(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    series: Series,
+    channel: u64,
+    message: u64,
+    hash: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO session_results (session, series, channel, message, hash)
+VALUES (?, ?, ?, ?, ?)",
+        session,
+        series.i8(),
+        channel,
+        message,
+        hash.to_string()
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+async fn set_session_result_hash(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    hash: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE session_results SET hash = ? WHERE session = ?",
+        hash.to_string(),
+        session
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Jolpica round of `weekend`, from the calendar sync if it matched the
+/// weekend and else counted by start date.
+async fn jolpica_round(
+    db_conn: &mut MySqlConnection,
+    weekend: &FullWeekend,
+) -> Result<u64, sqlx::Error> {
+    let synced = sqlx::query_scalar!(
+        "SELECT round FROM jolpica_rounds WHERE season = ? AND weekend = ?",
+        weekend.weekend.start_date.year(),
+        weekend.weekend.id
+    )
+    .fetch_optional(db_conn)
+    .await?;
+    Ok(synced.map_or(weekend.round, |f| f as u64))
+}
+
+/// Posts or edits the results of `session`, if there are any yet.
+async fn post_results(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    client: &reqwest::Client,
+    config: &Config<'_>,
+    session: &Session,
+    kind: ResultKind,
+) -> Result<(), Error> {
+    let Some(weekend) =
+        fetch_full_weekend(db_conn, session.weekend as u64).await?
+    else {
+        return Ok(());
+    };
+    if weekend.weekend.series != Series::F1 {
+        return Ok(());
+    }
+    let round = jolpica_round(db_conn, &weekend).await?;
+    let season = weekend.weekend.start_date.year();
+    let Some(classification) =
+        jolpica::fetch_classification(client, season, round, kind).await?
+    else {
+        return Ok(());
+    };
+    let hash = classification.fingerprint();
+    let embed = results_embed(&weekend, session, &classification);
+    match fetch_session_result(db_conn, session.id).await? {
+        Some(posted) if posted.hash == hash.to_string() => {},
+        Some(posted) => {
+            ChannelId::new(posted.channel)
+                .edit_message(
+                    http.http(),
+                    posted.message,
+                    EditMessage::new().embed(embed),
+                )
+                .await?;
+            set_session_result_hash(db_conn, session.id, hash).await?;
+            info!("Revised the results of session {}", session.id);
+        },
+        None => {
+            let channel = config.channel(weekend.weekend.series);
+            if channel == 0 {
+                return Ok(());
+            }
+            let message = ChannelId::new(channel)
+                .send_message(http.http(), CreateMessage::new().embed(embed))
+                .await?;
+            insert_session_result(
+                db_conn,
+                session.id,
+                weekend.weekend.series,
+                channel,
+                message.id.get(),
+                hash,
+            )
+            .await?;
+        },
+    }
+    Ok(())
+}
+
+/// Posts or edits the results of every session that ended within
+/// [RESULTS_WINDOW], returns whether a race or sprint was among them.
+async fn check_ended(
+    db_conn: &mut MySqlConnection,
+    http: &Http,
+    client: &reqwest::Client,
+    config: &Config<'_>,
+) -> Result<bool, Error> {
+    let now = Utc::now();
+    let ended =
+        fetch_ended_sessions(db_conn, now - RESULTS_WINDOW, now).await?;
+    let mut raced = false;
+    for session in ended.iter() {
+        let Some(kind) = ResultKind::of(session) else {
+            continue;
+        };
+        raced |= kind != ResultKind::Qualifying;
+        if let Err(why) =
+            post_results(db_conn, http, client, config, session, kind).await
+        {
+            error!(
+                "Couldn't post the results of session {}: {why}",
+                session.id
+            );
+        }
+    }
+    Ok(raced)
+}
+
+/// Checks for new or revised results every `config.results.interval`
+/// seconds on race weekends, adapted around sessions and between weekends
+/// by [Cadence]. The standings are refreshed on the first check and after
+/// races.
+pub async fn run(
+    pool: MySqlPool,
+    http: Arc<Http>,
+    config: &Config<'_>,
+) {
+    if config.results.interval == 0 {
+        return;
+    }
+    let client = reqwest::Client::new();
+    let mut first = true;
+    let cadence = Cadence::new(config.results.interval, &config.cadence);
+    loop {
+        if switches::is_paused() {
+            cadence.sleep(&pool).await;
+            continue;
+        }
+        let mut db_conn = match acquire(&pool).await {
+            Ok(db_conn) => db_conn,
+            Err(why) => {
+                error!("{why:#?}");
+                tokio::time::sleep(Duration::from_secs(
+                    config.results.interval,
+                ))
+                .await;
+                continue;
+            },
+        };
+        let raced =
+            match check_ended(db_conn.as_mut(), &http, &client, config).await {
+                Ok(raced) => raced,
+                Err(why) => {
+                    error!("{why:#?}");
+                    false
+                },
+            };
+        if first || raced {
+            if let Err(why) = standings::update(
+                db_conn.as_mut(),
+                &http,
+                &client,
+                &config.standings,
+            )
+            .await
+            {
+                error!("Couldn't update the standings: {why}");
+            }
+            first = false;
+        }
+        drop(db_conn);
+        cadence.sleep(&pool).await;
+    }
+}
@@ -0,0 +1,294 @@
+//! The championship standings message, posted once and edited whenever the
+//! standings change.
+
+use chrono::{Datelike, Utc};
+use f1_bot_types::Series;
+use serde::Deserialize;
+use serenity::all::{
+    CacheHttp, ChannelId, CreateEmbed, CreateMessage, EditMessage, StatusCode,
+};
+use sqlx::MySqlConnection;
+use tracing::info;
+
+use crate::{
+    config::StandingsConfig,
+    error::Error,
+    util::{render, stable_hash},
+};
+
+const BASE_URL: &str = "https://api.jolpi.ca/ergast/f1";
+
+/// Positions listed per championship.
+const SHOWN: usize = 20;
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(rename = "MRData")]
+    data: Data,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    #[serde(rename = "StandingsTable")]
+    table: Table,
+}
+
+#[derive(Deserialize)]
+struct Table {
+    #[serde(rename = "StandingsLists")]
+    lists: Vec<List>,
+}
+
+#[derive(Deserialize)]
+struct List {
+    round: String,
+    #[serde(rename = "DriverStandings", default)]
+    drivers: Vec<DriverStanding>,
+    #[serde(rename = "ConstructorStandings", default)]
+    constructors: Vec<ConstructorStanding>,
+}
+
+#[derive(Deserialize)]
+struct Driver {
+    #[serde(rename = "givenName")]
+    given_name: String,
+    #[serde(rename = "familyName")]
+    family_name: String,
+}
+
+#[derive(Deserialize)]
+struct Constructor {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DriverStanding {
+    position: Option<String>,
+    points: String,
+    #[serde(rename = "Driver")]
+    driver: Driver,
+}
+
+#[derive(Deserialize)]
+struct ConstructorStanding {
+    position: Option<String>,
+    points: String,
+    #[serde(rename = "Constructor")]
+    constructor: Constructor,
+}
+
+/// One line of a championship table.
+#[derive(Debug, Hash)]
+pub struct Standing {
+    pub position: String,
+    pub name: String,
+    pub points: String,
+}
+
+#[derive(Debug, Hash)]
+pub struct Standings {
+    /// Round the standings are after.
+    pub round: String,
+    pub drivers: Vec<Standing>,
+    pub constructors: Vec<Standing>,
+}
+
+async fn fetch_list(
+    client: &reqwest::Client,
+    season: i32,
+    table: &str,
+) -> Result<Option<List>, Error> {
+    let response = client
+        .get(format!("{BASE_URL}/{season}/{table}.json?limit=100"))
+        .send()
+        .await
+        .and_then(|f| f.error_for_status())
+        .map_err(|f| Error::NNF(f.into()))?;
+    let body =
+        response.json::<Response>().await.map_err(|f| Error::NNF(f.into()))?;
+    Ok(body.data.table.lists.into_iter().next())
+}
+
+/// Drivers and constructors standings of `season`, [None] before the first
+/// race.
+pub async fn fetch_standings(
+    client: &reqwest::Client,
+    season: i32,
+) -> Result<Option<Standings>, Error> {
+    let Some(drivers) = fetch_list(client, season, "driverStandings").await?
+    else {
+        return Ok(None);
+    };
+    let constructors = fetch_list(client, season, "constructorStandings")
+        .await?
+        .map(|f| f.constructors)
+        .unwrap_or_default();
+    Ok(Some(Standings {
+        round: drivers.round,
+        drivers: drivers
+            .drivers
+            .into_iter()
+            .map(|f| Standing {
+                position: f.position.unwrap_or_else(|| "-".to_owned()),
+                name: format!(
+                    "{} {}",
+                    f.driver.given_name, f.driver.family_name
+                ),
+                points: f.points,
+            })
+            .collect(),
+        constructors: constructors
+            .into_iter()
+            .map(|f| Standing {
+                position: f.position.unwrap_or_else(|| "-".to_owned()),
+                name: f.constructor.name,
+                points: f.points,
+            })
+            .collect(),
+    }))
+}
+
+fn table(standings: &[Standing]) -> String {
+    let table = standings
+        .iter()
+        .take(SHOWN)
+        .map(|f| format!("`{:>2}` {} · {} pts", f.position, f.name, f.points))
+        .collect::<Vec<_>>()
+        .join("\n");
+    match table.is_empty() {
+        true => "*None yet*".to_owned(),
+        false => table,
+    }
+}
+
+/// The standings message of `series`.
+pub fn standings_embed(
+    series: Series,
+    season: i32,
+    standings: &Standings,
+) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(format!(
+            "{series} {season} standings after round {}",
+            standings.round
+        ))
+        .colour(render::colour(series))
+        .field("Drivers", table(&standings.drivers), true)
+        .field("Constructors", table(&standings.constructors), true)
+}
+
+/// A posted standings message.
+#[derive(Debug)]
+pub struct StandingsMessage {
+    pub series: i8,
+    pub channel: u64,
+    pub message: u64,
+    pub hash: String,
+}
+
+async fn fetch_standings_message(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Option<StandingsMessage>, sqlx::Error> {
+    sqlx::query_as!(
+        StandingsMessage,
+        "SELECT * FROM standings_messages WHERE series = ?",
+        series.i8()
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+async fn set_standings_message(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    channel: u64,
+    message: u64,
+    hash: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO standings_messages (series, channel, message, hash)
+VALUES (?, ?, ?, ?)
+ON DUPLICATE KEY UPDATE channel = VALUES(channel),
+message = VALUES(message), hash = VALUES(hash)",
+        series.i8(),
+        channel,
+        message,
+        hash.to_string()
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Posts the F1 standings into `config.channel`, or edits the message
+/// posted before if they changed since. A message deleted in Discord or
+/// left in another channel is replaced.
+pub async fn update(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    client: &reqwest::Client,
+    config: &StandingsConfig,
+) -> Result<(), Error> {
+    if config.channel == 0 {
+        return Ok(());
+    }
+    let series = Series::F1;
+    let season = Utc::now().year();
+    let Some(standings) = fetch_standings(client, season).await? else {
+        return Ok(());
+    };
+    let hash = stable_hash(&(&standings, season));
+
+    let posted = fetch_standings_message(db_conn, series)
+        .await?
+        .filter(|f| f.channel == config.channel);
+    if let Some(posted) = &posted {
+        if posted.hash == hash.to_string() {
+            return Ok(());
+        }
+        let edited = ChannelId::new(posted.channel)
+            .edit_message(
+                http.http(),
+                posted.message,
+                EditMessage::new()
+                    .embed(standings_embed(series, season, &standings)),
+            )
+            .await;
+        match edited {
+            Ok(_) => {
+                set_standings_message(
+                    db_conn,
+                    series,
+                    posted.channel,
+                    posted.message,
+                    hash,
+                )
+                .await?;
+                info!("Updated the {series} standings");
+                return Ok(());
+            },
+            Err(serenity::Error::Http(why))
+                if why
+                    .status_code()
+                    .is_some_and(|f| f == StatusCode::NOT_FOUND) => {},
+            Err(why) => return Err(why.into()),
+        }
+    }
+    let message = ChannelId::new(config.channel)
+        .send_message(
+            http.http(),
+            CreateMessage::new()
+                .embed(standings_embed(series, season, &standings)),
+        )
+        .await?;
+    set_standings_message(
+        db_conn,
+        series,
+        config.channel,
+        message.id.get(),
+        hash,
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//! F1 calendar from the Jolpica API, the successor of Ergast.
+//!
+//! Every round goes through [sync_weekend] like other upstream changes,
+//! rounds without a stored weekend are staged as a new weekend.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Utc};
+use f1_bot_types::Series;
+use serde::Deserialize;
+use serenity::all::{CacheHttp, Http};
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info, warn};
+
+use super::{sync_weekend, IncomingSession, SessionSource};
+use crate::{
+    config::Config,
+    error::Error,
+    util::{
+        acquire, fetch_kind_for_title, fetch_weekend_by_name, switches,
+        Cadence, SessionDuration,
+    },
+};
+
+const BASE_URL: &str = "https://api.jolpi.ca/ergast/f1";
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(rename = "MRData")]
+    data: Data,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    #[serde(rename = "RaceTable")]
+    race_table: RaceTable,
+}
+
+#[derive(Deserialize)]
+struct RaceTable {
+    #[serde(rename = "Races")]
+    races: Vec<Race>,
+}
+
+/// Date and, for recent seasons, UTC time of a session.
+#[derive(Deserialize)]
+struct Slot {
+    date: String,
+    time: Option<String>,
+}
+
+impl Slot {
+    fn start(&self) -> Option<DateTime<Utc>> {
+        let time = self.time.as_ref()?;
+        DateTime::parse_from_rfc3339(&format!("{}T{time}", self.date))
+            .ok()
+            .map(|f| f.with_timezone(&Utc))
+    }
+}
+
+#[derive(Deserialize)]
+struct Race {
+    round: String,
+    #[serde(rename = "raceName")]
+    race_name: String,
+    #[serde(flatten)]
+    race: Slot,
+    #[serde(rename = "FirstPractice")]
+    first_practice: Option<Slot>,
+    #[serde(rename = "SecondPractice")]
+    second_practice: Option<Slot>,
+    #[serde(rename = "ThirdPractice")]
+    third_practice: Option<Slot>,
+    #[serde(rename = "SprintQualifying", alias = "SprintShootout")]
+    sprint_qualifying: Option<Slot>,
+    #[serde(rename = "Sprint")]
+    sprint: Option<Slot>,
+    #[serde(rename = "Qualifying")]
+    qualifying: Option<Slot>,
+}
+
+impl Race {
+    /// Sessions with their title and scheduled length in seconds, the API
+    /// only has start times.
+    fn slots(&self) -> Vec<(&'static str, &Slot, i64)> {
+        [
+            ("Free Practice 1", self.first_practice.as_ref(), 3600),
+            ("Free Practice 2", self.second_practice.as_ref(), 3600),
+            ("Free Practice 3", self.third_practice.as_ref(), 3600),
+            ("Sprint Qualifying", self.sprint_qualifying.as_ref(), 2700),
+            ("Sprint", self.sprint.as_ref(), 3600),
+            ("Qualifying", self.qualifying.as_ref(), 3600),
+            ("Race", Some(&self.race), 7200),
+        ]
+        .into_iter()
+        .filter_map(|(title, slot, duration)| Some((title, slot?, duration)))
+        .collect()
+    }
+}
+
+async fn fetch_races(
+    client: &reqwest::Client,
+    season: i32,
+) -> Result<Vec<Race>, Error> {
+    let response = client
+        .get(format!("{BASE_URL}/{season}.json?limit=100"))
+        .send()
+        .await
+        .and_then(|f| f.error_for_status())
+        .map_err(|f| Error::NNF(f.into()))?;
+    let body =
+        response.json::<Response>().await.map_err(|f| Error::NNF(f.into()))?;
+    Ok(body.data.race_table.races)
+}
+
+/// The weekend of `round`, by an earlier sync or else by name.
+async fn find_weekend(
+    db_conn: &mut MySqlConnection,
+    season: i32,
+    round: i32,
+    name: &str,
+) -> Result<Option<u64>, sqlx::Error> {
+    let known = sqlx::query_scalar!(
+        "SELECT weekend FROM jolpica_rounds WHERE season = ? AND round = ?",
+        season,
+        round
+    )
+    .fetch_optional(&mut *db_conn)
+    .await?;
+    if known.is_some() {
+        return Ok(known);
+    }
+    fetch_weekend_by_name(db_conn, Series::F1, name, season).await
+}
+
+async fn remember_round(
+    db_conn: &mut MySqlConnection,
+    season: i32,
+    round: i32,
+    weekend: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO jolpica_rounds (season, round, weekend) VALUES (?, ?, ?)
+ON DUPLICATE KEY UPDATE weekend = VALUES(weekend)",
+        season,
+        round,
+        weekend
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+async fn sync_race(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+    season: i32,
+    race: &Race,
+) -> Result<(), Error> {
+    let round = race.round.parse::<i32>()?;
+    let mut incoming = vec![];
+    for (title, slot, duration) in race.slots() {
+        let Some(start_date) = slot.start() else {
+            continue;
+        };
+        let Some(kind) =
+            fetch_kind_for_title(db_conn, Series::F1, title).await?
+        else {
+            warn!("No stored session `{title}` to take a kind from, skipped");
+            continue;
+        };
+        incoming.push(IncomingSession {
+            kind,
+            title: title.to_owned(),
+            start_date,
+            duration: SessionDuration::stored(duration),
+            cancelled: false,
+        });
+    }
+    let existing =
+        find_weekend(db_conn, season, round, &race.race_name).await?;
+    let Some(id) = sync_weekend(
+        db_conn,
+        http,
+        config,
+        existing,
+        Series::F1,
+        &race.race_name,
+        SessionSource::Api,
+        incoming,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+    if existing.is_none() {
+        info!("Added {season} round {round} `{}`", race.race_name);
+    }
+    remember_round(db_conn, season, round, id).await.map_err(Error::from)
+}
+
+/// Syncs the current and the next season, starting right away. Runs every
+/// `config.jolpica.interval` seconds on race weekends, more often around
+/// sessions and less often between weekends, see [Cadence].
+pub async fn run(
+    pool: MySqlPool,
+    http: Arc<Http>,
+    config: &Config<'_>,
+) {
+    if config.jolpica.interval == 0 {
+        return;
+    }
+    let client = reqwest::Client::new();
+    let cadence = Cadence::new(config.jolpica.interval, &config.cadence);
+    loop {
+        // the data may be getting fixed by hand
+        if switches::is_paused() {
+            cadence.sleep(&pool).await;
+            continue;
+        }
+        let now = Utc::now().year();
+        for season in [now, now + 1] {
+            let races = match fetch_races(&client, season).await {
+                Ok(races) => races,
+                Err(why) => {
+                    error!("Couldn't fetch the {season} calendar: {why}");
+                    continue;
+                },
+            };
+            let mut db_conn = match acquire(&pool).await {
+                Ok(db_conn) => db_conn,
+                Err(why) => {
+                    error!("{why:#?}");
+                    continue;
+                },
+            };
+            for race in races.iter() {
+                if let Err(why) =
+                    sync_race(db_conn.as_mut(), &http, config, season, race)
+                        .await
+                {
+                    error!("Couldn't sync `{}`: {why}", race.race_name);
+                }
+            }
+        }
+        cadence.sleep(&pool).await;
+    }
+}
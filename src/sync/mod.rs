@@ -0,0 +1,710 @@
+#[cfg(feature = "jolpica")]
+pub mod jolpica;
+#[cfg(feature = "openf1")]
+pub mod openf1;
+
+use std::fmt;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::{Series, SessionStatus, Weekend, WeekendStatus};
+use serenity::all::{
+    ButtonStyle, CacheHttp, ChannelId, ComponentInteraction, Context,
+    CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage,
+};
+use sqlx::{Connection, MySqlConnection, MySqlPool};
+use tracing::{info, warn};
+
+use crate::{
+    config::Config,
+    error::Error,
+    events::{BotEvent, EVENTS},
+    util::{
+        delete_pending_sync, fetch_full_weekend, fetch_pending_changes,
+        fetch_pending_sync, fetch_pending_syncs_for_new_weekend,
+        fetch_pending_syncs_for_weekend, fetch_protected_sessions,
+        insert_pending_sync, insert_pending_weekend, insert_weekend, lookups,
+        set_pending_sync_message, set_session_source, FullWeekend, KindCode,
+        PendingChange, PendingSync, SessionDuration,
+    },
+};
+
+/// Where a session was last written from.
+///
+/// Syncs never overwrite a [Manual](SessionSource::Manual) session that was
+/// delayed or cancelled until the override is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionSource {
+    Manual,
+    Api,
+    Ics,
+}
+
+impl SessionSource {
+    pub fn i8(&self) -> i8 {
+        match self {
+            Self::Manual => 0,
+            Self::Api => 1,
+            Self::Ics => 2,
+        }
+    }
+}
+
+impl TryFrom<i8> for SessionSource {
+    type Error = Error;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Manual),
+            1 => Ok(Self::Api),
+            2 => Ok(Self::Ics),
+            value => Err(Error::UnknownValue {
+                kind: "session source",
+                value: value.into(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SessionSource {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            Self::Manual => "manual",
+            Self::Api => "api",
+            Self::Ics => "ics",
+        })
+    }
+}
+
+const CONFIRM_PREFIX: &str = "sync_confirm:";
+const REJECT_PREFIX: &str = "sync_reject:";
+
+/// A session as reported by an external schedule source.
+#[derive(Debug)]
+pub struct IncomingSession {
+    /// Known or registered custom kind.
+    pub kind: KindCode,
+    pub title: String,
+    pub start_date: DateTime<Utc>,
+    pub duration: SessionDuration,
+    pub cancelled: bool,
+}
+
+/// A single difference between the stored schedule and an external source.
+#[derive(Debug)]
+pub enum ScheduleChange {
+    Moved {
+        session: i64,
+        title: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    Cancelled {
+        session: i64,
+        title: String,
+    },
+    Added(IncomingSession),
+}
+
+impl ScheduleChange {
+    fn kind_i8(&self) -> i8 {
+        match self {
+            Self::Moved {
+                ..
+            } => 0,
+            Self::Cancelled {
+                ..
+            } => 1,
+            Self::Added(_) => 2,
+        }
+    }
+
+    /// Rebuilds a change from its staged row, [None] for unknown kinds.
+    pub fn from_row(row: PendingChange) -> Option<Self> {
+        match row.kind {
+            0 => Some(Self::Moved {
+                session: row.session?,
+                title: row.title,
+                from: row.previous_date?,
+                to: row.start_date?,
+            }),
+            1 => Some(Self::Cancelled {
+                session: row.session?,
+                title: row.title,
+            }),
+            2 => Some(Self::Added(IncomingSession {
+                kind: KindCode(row.session_kind),
+                title: row.title,
+                start_date: row.start_date?,
+                duration: SessionDuration::stored(row.duration),
+                cancelled: false,
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// Formats an offset like `+2h`, `-30m` or `+1h 15m`.
+fn fmt_offset(delta: TimeDelta) -> String {
+    let minutes = delta.num_minutes();
+    let sign = if minutes < 0 {
+        "-"
+    } else {
+        "+"
+    };
+    let (hours, minutes) = (minutes.abs() / 60, minutes.abs() % 60);
+    match (hours, minutes) {
+        (0, m) => format!("{sign}{m}m"),
+        (h, 0) => format!("{sign}{h}h"),
+        (h, m) => format!("{sign}{h}h {m}m"),
+    }
+}
+
+impl fmt::Display for ScheduleChange {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Moved {
+                title,
+                from,
+                to,
+                ..
+            } => write!(
+                f,
+                "{title} moved {} (<t:{}:f>)",
+                fmt_offset(to.signed_duration_since(from)),
+                to.timestamp()
+            ),
+            Self::Cancelled {
+                title,
+                ..
+            } => write!(f, "{title} cancelled"),
+            Self::Added(session) => write!(
+                f,
+                "{} added (<t:{}:f>)",
+                session.title,
+                session.start_date.timestamp()
+            ),
+        }
+    }
+}
+
+/// Compares the stored weekend against the sessions reported upstream.
+/// Sessions are matched by title, sessions missing upstream and `protected`
+/// sessions are left alone.
+pub fn diff_weekend(
+    current: &FullWeekend,
+    incoming: Vec<IncomingSession>,
+    protected: &[i64],
+) -> Vec<ScheduleChange> {
+    let mut changes = Vec::new();
+    for session in incoming.into_iter() {
+        let existing = current
+            .sessions
+            .iter()
+            .find(|f| f.title.eq_ignore_ascii_case(&session.title));
+        let Some(existing) = existing else {
+            if !session.cancelled {
+                changes.push(ScheduleChange::Added(session));
+            }
+            continue;
+        };
+        if protected.contains(&existing.id) {
+            continue;
+        }
+        if session.cancelled {
+            if !matches!(existing.status, SessionStatus::Cancelled) {
+                changes.push(ScheduleChange::Cancelled {
+                    session: existing.id,
+                    title: existing.title.clone(),
+                });
+            }
+        } else if existing.start_date != session.start_date {
+            changes.push(ScheduleChange::Moved {
+                session: existing.id,
+                title: existing.title.clone(),
+                from: existing.start_date,
+                to: session.start_date,
+            });
+        }
+    }
+    changes
+}
+
+/// Icon of weekends created by a sync.
+const NEW_WEEKEND_ICON: &str = ":checkered_flag:";
+
+/// The weekend a sync writes to.
+#[derive(Debug, Clone, Copy)]
+enum SyncTarget<'a> {
+    Existing(&'a Weekend),
+    /// Not stored yet, it's created when the sync is applied.
+    New {
+        series: Series,
+        name: &'a str,
+    },
+}
+
+fn summary(
+    target: SyncTarget<'_>,
+    changes: &[ScheduleChange],
+) -> String {
+    let mut summary = match target {
+        SyncTarget::Existing(weekend) => {
+            format!("**Schedule sync for {} {}**", weekend.icon, weekend.name)
+        },
+        SyncTarget::New {
+            series,
+            name,
+        } => format!("**New {series} weekend {NEW_WEEKEND_ICON} {name}**"),
+    };
+    for change in changes.iter() {
+        summary += &format!("\n> {change}");
+    }
+    summary
+}
+
+/// Writes the changes to the database in a single transaction and records
+/// `source` for every touched session.
+pub async fn apply_changes(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    source: SessionSource,
+    changes: &[ScheduleChange],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db_conn.begin().await?;
+    write_changes(&mut *tx, weekend, source, changes).await?;
+    tx.commit().await?;
+    applied(weekend, changes);
+    Ok(())
+}
+
+/// Applies a staged sync like [apply_changes] and removes it in the same
+/// transaction. Returns false without writing anything when another click
+/// applied or discarded it first.
+async fn apply_pending_sync(
+    db_conn: &mut MySqlConnection,
+    sync: &PendingSync,
+    changes: &[ScheduleChange],
+) -> Result<bool, Error> {
+    let source = sync.source.try_into()?;
+    let mut tx = db_conn.begin().await?;
+    if !delete_pending_sync(&mut *tx, sync.id).await? {
+        return Ok(false);
+    }
+    let weekend = match (sync.weekend, sync.series, &sync.name) {
+        (Some(weekend), _, _) => weekend,
+        (None, Some(series), Some(name)) => {
+            let Some(first) = first_start(changes) else {
+                return Err(Error::NotFound);
+            };
+            insert_weekend(
+                &mut *tx,
+                name,
+                NEW_WEEKEND_ICON,
+                Series::from(series),
+                first,
+            )
+            .await?
+        },
+        _ => return Err(Error::NotFound),
+    };
+    write_changes(&mut *tx, weekend, source, changes).await?;
+    tx.commit().await?;
+    applied(weekend, changes);
+    Ok(true)
+}
+
+/// Start of the earliest added session.
+fn first_start(changes: &[ScheduleChange]) -> Option<DateTime<Utc>> {
+    changes
+        .iter()
+        .filter_map(|f| match f {
+            ScheduleChange::Added(session) => Some(session.start_date),
+            _ => None,
+        })
+        .min()
+}
+
+fn applied(
+    weekend: u64,
+    changes: &[ScheduleChange],
+) {
+    lookups::invalidate();
+    EVENTS.publish(BotEvent::ScheduleChanged {
+        weekend,
+        changes: changes.iter().map(|f| f.to_string()).collect(),
+    });
+}
+
+async fn write_changes(
+    tx: &mut MySqlConnection,
+    weekend: u64,
+    source: SessionSource,
+    changes: &[ScheduleChange],
+) -> Result<(), sqlx::Error> {
+    for change in changes.iter() {
+        match change {
+            ScheduleChange::Moved {
+                session,
+                to,
+                ..
+            } => {
+                sqlx::query!(
+                    "UPDATE sessions SET start_date = ? WHERE id = ?",
+                    to,
+                    session
+                )
+                .execute(&mut *tx)
+                .await?;
+                set_session_source(&mut *tx, *session, source).await?;
+            },
+            ScheduleChange::Cancelled {
+                session,
+                ..
+            } => {
+                sqlx::query!(
+                    "UPDATE sessions SET status = ? WHERE id = ?",
+                    SessionStatus::Cancelled.i8(),
+                    session
+                )
+                .execute(&mut *tx)
+                .await?;
+                set_session_source(&mut *tx, *session, source).await?;
+            },
+            ScheduleChange::Added(session) => {
+                let result = sqlx::query!(
+                    "INSERT INTO sessions
+(weekend, kind, title, start_date, duration, status)
+VALUES (?, ?, ?, ?, ?, ?)",
+                    weekend,
+                    session.kind.0,
+                    session.title,
+                    session.start_date,
+                    session.duration.secs(),
+                    SessionStatus::Open.i8()
+                )
+                .execute(&mut *tx)
+                .await?;
+                set_session_source(
+                    &mut *tx,
+                    result.last_insert_id() as i64,
+                    source,
+                )
+                .await?;
+            },
+        }
+    }
+    Ok(())
+}
+
+async fn stage_changes(
+    db_conn: &mut MySqlConnection,
+    target: SyncTarget<'_>,
+    source: SessionSource,
+    changes: &[ScheduleChange],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = db_conn.begin().await?;
+    let sync = match target {
+        SyncTarget::Existing(weekend) => {
+            insert_pending_sync(&mut *tx, weekend.id, source).await?
+        },
+        SyncTarget::New {
+            series,
+            name,
+        } => insert_pending_weekend(&mut *tx, series, name, source).await?,
+    };
+    for change in changes.iter() {
+        let (session, session_kind, title, start_date, previous_date, duration) =
+            match change {
+                ScheduleChange::Moved {
+                    session,
+                    title,
+                    from,
+                    to,
+                } => (Some(*session), 0, title, Some(*to), Some(*from), 0),
+                ScheduleChange::Cancelled {
+                    session,
+                    title,
+                } => (Some(*session), 0, title, None, None, 0),
+                ScheduleChange::Added(session) => (
+                    None,
+                    session.kind.0,
+                    &session.title,
+                    Some(session.start_date),
+                    None,
+                    session.duration.secs(),
+                ),
+            };
+        sqlx::query!(
+            "INSERT INTO pending_changes
+(sync, session, kind, session_kind, title, start_date, previous_date, duration)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            sync,
+            session,
+            change.kind_i8(),
+            session_kind,
+            title,
+            start_date,
+            previous_date,
+            duration
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(sync)
+}
+
+/// Diffs the upstream sessions against the stored weekend and either applies
+/// them right away (`sync.auto_apply`) or posts a preview with confirm and
+/// reject buttons to the admin channel.
+pub async fn stage_weekend_sync(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+    current: &FullWeekend,
+    source: SessionSource,
+    incoming: Vec<IncomingSession>,
+) -> Result<(), Error> {
+    let protected =
+        fetch_protected_sessions(db_conn, current.weekend.id).await?;
+    let changes = diff_weekend(current, incoming, &protected);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let target = SyncTarget::Existing(&current.weekend);
+    stage_sync(db_conn, http, config, target, source, &changes).await?;
+    Ok(())
+}
+
+/// Applies the changes right away (`sync.auto_apply`) or posts a preview
+/// with confirm and reject buttons to the admin channel.
+///
+/// Returns the weekend if the changes were applied.
+async fn stage_sync(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+    target: SyncTarget<'_>,
+    source: SessionSource,
+    changes: &[ScheduleChange],
+) -> Result<Option<u64>, Error> {
+    let summary = summary(target, changes);
+    let admin_channel = config.discord.admin_channel;
+
+    if config.sync.auto_apply {
+        let (weekend, name) = match target {
+            SyncTarget::Existing(weekend) => (weekend.id, &weekend.name[..]),
+            SyncTarget::New {
+                series,
+                name,
+            } => {
+                let Some(first) = first_start(changes) else {
+                    return Ok(None);
+                };
+                let id = insert_weekend(
+                    db_conn,
+                    name,
+                    NEW_WEEKEND_ICON,
+                    series,
+                    first,
+                )
+                .await?;
+                (id, name)
+            },
+        };
+        apply_changes(db_conn, weekend, source, changes).await?;
+        info!("Auto-applied {} changes to {name}", changes.len());
+        if admin_channel != 0 {
+            ChannelId::new(admin_channel)
+                .send_message(
+                    http.http(),
+                    CreateMessage::new().content(format!(
+                        "{summary}\n*Applied automatically.*"
+                    )),
+                )
+                .await?;
+        }
+        return Ok(Some(weekend));
+    }
+
+    // A newer upstream state supersedes whatever is still waiting.
+    let superseded = match target {
+        SyncTarget::Existing(weekend) => {
+            fetch_pending_syncs_for_weekend(db_conn, weekend.id).await?
+        },
+        SyncTarget::New {
+            series,
+            name,
+        } => fetch_pending_syncs_for_new_weekend(db_conn, series, name).await?,
+    };
+    for old in superseded.into_iter() {
+        if let (Some(channel), Some(message)) = (old.channel, old.message) {
+            if let Err(why) = ChannelId::new(channel.parse()?)
+                .delete_message(http.http(), message.parse::<u64>()?)
+                .await
+            {
+                warn!("Could not remove superseded sync preview: {why}");
+            }
+        }
+        delete_pending_sync(db_conn, old.id).await?;
+    }
+
+    let sync = stage_changes(db_conn, target, source, changes).await?;
+    if admin_channel == 0 {
+        warn!("No admin channel configured, sync {sync} stays pending.");
+        return Ok(None);
+    }
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{CONFIRM_PREFIX}{sync}"))
+            .label("Apply")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{REJECT_PREFIX}{sync}"))
+            .label("Discard")
+            .style(ButtonStyle::Danger),
+    ]);
+    let message = ChannelId::new(admin_channel)
+        .send_message(
+            http.http(),
+            CreateMessage::new().content(summary).components(vec![buttons]),
+        )
+        .await?;
+    set_pending_sync_message(db_conn, sync, admin_channel, message.id.get())
+        .await?;
+    Ok(None)
+}
+
+/// Syncs the upstream state of one weekend. Known weekends (`existing`) go
+/// through [stage_weekend_sync], unknown ones are staged as a new weekend
+/// that's created once the sync is applied.
+///
+/// Returns the weekend, [None] if there was nothing to sync or the new
+/// weekend waits for confirmation.
+#[allow(clippy::too_many_arguments)]
+pub async fn sync_weekend(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+    existing: Option<u64>,
+    series: Series,
+    name: &str,
+    source: SessionSource,
+    incoming: Vec<IncomingSession>,
+) -> Result<Option<u64>, Error> {
+    if incoming.is_empty() {
+        return Ok(None);
+    }
+    if let Some(id) = existing {
+        let Some(current) = fetch_full_weekend(db_conn, id).await? else {
+            return Ok(None);
+        };
+        if current.weekend.status != WeekendStatus::Done {
+            stage_weekend_sync(
+                db_conn, http, config, &current, source, incoming,
+            )
+            .await?;
+        }
+        return Ok(Some(id));
+    }
+    let changes =
+        incoming.into_iter().map(ScheduleChange::Added).collect::<Vec<_>>();
+    let target = SyncTarget::New {
+        series,
+        name,
+    };
+    stage_sync(db_conn, http, config, target, source, &changes).await
+}
+
+/// Returns true for button ids created by [stage_weekend_sync].
+pub fn is_sync_component(custom_id: &str) -> bool {
+    custom_id.starts_with(CONFIRM_PREFIX)
+        || custom_id.starts_with(REJECT_PREFIX)
+}
+
+/// Handles a click on one of the preview buttons.
+pub async fn handle_component(
+    ctx: &Context,
+    database: &MySqlPool,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = component.data.custom_id.as_str();
+    let (confirm, id) = match custom_id.strip_prefix(CONFIRM_PREFIX) {
+        Some(id) => (true, id),
+        None => (false, custom_id.trim_start_matches(REJECT_PREFIX)),
+    };
+    let id: u64 = id.parse()?;
+    // the buttons are visible to everyone in the admin channel
+    let allowed = component
+        .member
+        .as_ref()
+        .and_then(|f| f.permissions)
+        .is_some_and(|f| f.manage_guild());
+    if !allowed {
+        component
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("Reviewing syncs needs Manage Server.")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+    let mut db_conn = database.acquire().await?;
+
+    let pending = match fetch_pending_sync(db_conn.as_mut(), id).await? {
+        Some(sync) if confirm => {
+            let changes = fetch_pending_changes(db_conn.as_mut(), sync.id)
+                .await?
+                .into_iter()
+                .filter_map(ScheduleChange::from_row)
+                .collect::<Vec<_>>();
+            apply_pending_sync(db_conn.as_mut(), &sync, &changes).await?
+        },
+        Some(sync) => delete_pending_sync(db_conn.as_mut(), sync.id).await?,
+        None => false,
+    };
+    if !pending {
+        component
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content("*This sync is no longer pending.*")
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+    let outcome = if confirm {
+        "Applied"
+    } else {
+        "Discarded"
+    };
+    info!("{outcome} sync {id} by {}", component.user.name);
+
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(format!(
+                        "{}\n*{outcome} by <@{}>.*",
+                        component.message.content, component.user.id
+                    ))
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,211 @@
+//! Live F1 session state from the OpenF1 race control feed.
+//!
+//! Only polled while an F1 session is running: reported delays flip the
+//! session to [Delayed](f1_bot_types::SessionStatus::Delayed), red flags and
+//! suspensions are posted into the F1 channel until the session ends.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::{MessageKind, Series, Session};
+use serde::Deserialize;
+use serenity::all::{CacheHttp, ChannelId, CreateMessage, Http};
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info};
+
+use super::SessionSource;
+use crate::{
+    config::Config,
+    error::Error,
+    util::{
+        acquire, fetch_live_sessions, fetch_weekend, insert_expiring_message,
+        mark_session_delayed, session_end, switches, Cadence, SessionDuration,
+    },
+};
+
+const BASE_URL: &str = "https://api.openf1.org/v1";
+
+/// How far before the scheduled start race control messages are considered,
+/// delays are usually announced shortly before.
+const LOOKBACK: TimeDelta = TimeDelta::minutes(30);
+
+#[derive(Deserialize)]
+struct RaceControl {
+    date: DateTime<Utc>,
+    flag: Option<String>,
+    message: String,
+}
+
+/// Race control messages worth acting on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Notice {
+    RedFlag,
+    Suspended,
+    Delayed,
+}
+
+impl Notice {
+    fn of(message: &RaceControl) -> Option<Self> {
+        let text = message.message.to_ascii_uppercase();
+        if message.flag.as_deref() == Some("RED") {
+            Some(Self::RedFlag)
+        } else if text.contains("SUSPENDED") {
+            Some(Self::Suspended)
+        } else if text.contains("DELAYED") {
+            Some(Self::Delayed)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::RedFlag => "🟥 Red flag",
+            Self::Suspended => "⏸️ Session suspended",
+            Self::Delayed => "⏳ Start delayed",
+        }
+    }
+}
+
+async fn fetch_race_control(
+    client: &reqwest::Client,
+    since: DateTime<Utc>,
+) -> Result<Vec<RaceControl>, Error> {
+    // OpenF1 filters are part of the key, `reqwest` would escape the `>`.
+    let response = client
+        .get(format!(
+            "{BASE_URL}/race_control?session_key=latest&date>={}",
+            since.format("%Y-%m-%dT%H:%M:%S")
+        ))
+        .send()
+        .await
+        .and_then(|f| f.error_for_status())
+        .map_err(|f| Error::NNF(f.into()))?;
+    response.json().await.map_err(|f| Error::NNF(f.into()))
+}
+
+/// Acts on the race control messages of `session` not in `seen` yet.
+async fn check_session(
+    db_conn: &mut MySqlConnection,
+    http: &impl CacheHttp,
+    client: &reqwest::Client,
+    config: &Config<'_>,
+    session: &Session,
+    seen: &mut HashSet<(i64, DateTime<Utc>, String)>,
+) -> Result<(), Error> {
+    let Some(weekend) = fetch_weekend(db_conn, session.weekend as u64).await?
+    else {
+        return Ok(());
+    };
+    if weekend.series != Series::F1 {
+        return Ok(());
+    }
+    let messages =
+        fetch_race_control(client, session.start_date - LOOKBACK).await?;
+    let channel = config.channel(Series::F1);
+    for message in messages {
+        if !seen.insert((session.id, message.date, message.message.clone())) {
+            continue;
+        }
+        let Some(notice) = Notice::of(&message) else {
+            continue;
+        };
+        if notice == Notice::Delayed
+            && !mark_session_delayed(db_conn, session.id, SessionSource::Api)
+                .await?
+        {
+            // Already delayed, by an earlier message or by hand.
+            continue;
+        }
+        info!("{} `{}`: {}", weekend.name, session.title, message.message);
+        let posted = ChannelId::new(channel)
+            .send_message(
+                http,
+                CreateMessage::new().content(format!(
+                    "**{}** {} {} {}\n> {}",
+                    notice.label(),
+                    weekend.icon,
+                    weekend.name,
+                    session.title,
+                    message.message
+                )),
+            )
+            .await?;
+        insert_expiring_message(
+            db_conn,
+            channel,
+            posted.id.get(),
+            MessageKind::Custom,
+            Series::F1,
+            session_end(session.start_date, SessionDuration::of(session)),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Checks race control while an F1 session is live, polling every
+/// `config.openf1.interval` seconds on race weekends and backing off between
+/// them, see [Cadence].
+pub async fn run(
+    pool: MySqlPool,
+    http: Arc<Http>,
+    config: &Config<'_>,
+) {
+    if config.openf1.interval == 0 {
+        return;
+    }
+    let client = reqwest::Client::new();
+    let mut seen = HashSet::new();
+    let cadence = Cadence::new(config.openf1.interval, &config.cadence);
+    let mut wait = Duration::from_secs(config.openf1.interval);
+    loop {
+        tokio::time::sleep(wait).await;
+        if switches::is_paused() {
+            continue;
+        }
+        let mut db_conn = match acquire(&pool).await {
+            Ok(db_conn) => db_conn,
+            Err(why) => {
+                error!("{why:#?}");
+                continue;
+            },
+        };
+        let now = Utc::now();
+        wait = match cadence.next(db_conn.as_mut(), now).await {
+            Ok(wait) => wait,
+            Err(why) => {
+                error!("{why:#?}");
+                Duration::from_secs(config.openf1.interval)
+            },
+        };
+        let sessions = match fetch_live_sessions(db_conn.as_mut(), now).await {
+            Ok(sessions) => sessions,
+            Err(why) => {
+                error!("{why:#?}");
+                continue;
+            },
+        };
+        if sessions.is_empty() {
+            seen.clear();
+            continue;
+        }
+        for session in sessions.iter() {
+            if let Err(why) = check_session(
+                db_conn.as_mut(),
+                &http,
+                &client,
+                config,
+                session,
+                &mut seen,
+            )
+            .await
+            {
+                error!(
+                    "Couldn't check race control for `{}`: {why}",
+                    session.title
+                );
+            }
+        }
+    }
+}
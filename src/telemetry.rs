@@ -0,0 +1,57 @@
+//! Log output plus, with the `otel` feature, OTLP export of tracing spans
+//! for Jaeger, Tempo and the like.
+//!
+//! Export is configured with the standard `OTEL_EXPORTER_OTLP_*`
+//! environment variables and only starts when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set, since it has to be running before
+//! the config file is read.
+
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+const SERVICE_NAME: &str = "f1-notif-bot";
+
+/// Flushes the remaining spans when dropped.
+pub struct Telemetry(Option<TracerProvider>);
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(provider) = self.0.take() {
+            if let Err(why) = provider.shutdown() {
+                eprintln!("Couldn't flush spans: {why}");
+            }
+        }
+    }
+}
+
+/// Installs the global subscriber, keep the result alive until exiting.
+pub fn init() -> Telemetry {
+    let fmt = tracing_subscriber::fmt::layer();
+    if std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT").is_none() {
+        tracing_subscriber::registry().with(fmt).init();
+        return Telemetry(None);
+    }
+    let exporter = match SpanExporter::builder().with_tonic().build() {
+        Ok(exporter) => exporter,
+        Err(why) => {
+            tracing_subscriber::registry().with(fmt).init();
+            tracing::error!("Couldn't create the OTLP exporter: {why}");
+            return Telemetry(None);
+        },
+    };
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new(
+            "service.name",
+            SERVICE_NAME,
+        )]))
+        .build();
+    let tracer = provider.tracer(SERVICE_NAME);
+    tracing_subscriber::registry()
+        .with(fmt)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+    Telemetry(Some(provider))
+}
@@ -0,0 +1,195 @@
+//! Serious runtime errors for the admin channel. They are collected where
+//! they happen and posted in batches, at most `config.max_posts` an hour, so
+//! an outage shows up as a few messages rather than one per failed query.
+//!
+//! Panics don't go through here, [crash](super::crash) reports them right
+//! away as the process may not survive them.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use serenity::all::{ChannelId, Http, StatusCode};
+use tracing::error;
+
+use crate::{config::AlertConfig, error::Error, util::switches};
+
+/// Window `config.max_posts` applies to.
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Distinct alerts listed per batch, later ones are only counted.
+const MAX_BATCH: usize = 10;
+
+/// Characters kept of an error, longer ones are cut off.
+const MAX_LINE: usize = 150;
+
+static PENDING: LazyLock<Mutex<Batch>> = LazyLock::new(Mutex::default);
+
+/// Whether an error is worth bothering the admins with, as opposed to ones
+/// that are expected every now and then and only logged.
+pub trait Severity {
+    fn is_serious(&self) -> bool;
+}
+
+impl Severity for sqlx::Error {
+    fn is_serious(&self) -> bool {
+        !matches!(self, sqlx::Error::RowNotFound)
+    }
+}
+
+impl Severity for serenity::Error {
+    /// Rejected requests other than for deleted messages and channels,
+    /// those usually mean missing permissions or a bad payload.
+    fn is_serious(&self) -> bool {
+        let serenity::Error::Http(why) = self else {
+            return false;
+        };
+        why.status_code()
+            .is_some_and(|f| f.is_client_error() && f != StatusCode::NOT_FOUND)
+    }
+}
+
+impl Severity for Error {
+    fn is_serious(&self) -> bool {
+        match self {
+            Self::Sqlx(inner) => inner.is_serious(),
+            Self::Serenity(inner) => inner.is_serious(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Batch {
+    /// Alerts with the number of times each one came up.
+    alerts: Vec<(String, usize)>,
+    /// Alerts that didn't fit [MAX_BATCH].
+    dropped: usize,
+}
+
+impl Batch {
+    fn push(
+        &mut self,
+        alert: String,
+    ) {
+        if let Some((_, count)) =
+            self.alerts.iter_mut().find(|(f, _)| *f == alert)
+        {
+            *count += 1;
+        } else if self.alerts.len() < MAX_BATCH {
+            self.alerts.push((alert, 1));
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.alerts.iter().map(|(_, count)| count).sum::<usize>() + self.dropped
+    }
+
+    /// The message posting this batch, mentioning the `suppressed` alerts
+    /// since the last one that went out.
+    fn content(
+        &self,
+        suppressed: usize,
+    ) -> String {
+        let mut content = String::from(":warning: **Runtime errors**");
+        for (alert, count) in self.alerts.iter() {
+            content.push_str("\n- ");
+            content.push_str(alert);
+            if *count > 1 {
+                content.push_str(&format!(" (×{count})"));
+            }
+        }
+        if self.dropped > 0 {
+            content.push_str(&format!("\n…and {} more", self.dropped));
+        }
+        if suppressed > 0 {
+            content.push_str(&format!(
+                "\n{suppressed} earlier alerts were held back, see the logs."
+            ));
+        }
+        content
+    }
+}
+
+/// Queues `why` for the admin channel if it's serious, `what` says what
+/// failed. Logging it is still up to the caller.
+pub fn report(
+    what: &str,
+    why: &(impl Severity + fmt::Display),
+) {
+    if !why.is_serious() {
+        return;
+    }
+    let mut why = why.to_string();
+    if let Some((end, _)) = why.char_indices().nth(MAX_LINE) {
+        why.truncate(end);
+        why.push('…');
+    }
+    let alert = format!("{what}: `{}`", why.replace('`', "'"));
+    match PENDING.lock() {
+        Ok(mut pending) => pending.push(alert),
+        Err(poisoned) => poisoned.into_inner().push(alert),
+    }
+}
+
+fn take() -> Batch {
+    match PENDING.lock() {
+        Ok(mut pending) => std::mem::take(&mut *pending),
+        Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+    }
+}
+
+/// Posts the queued alerts to `admin_channel` every `config.interval`
+/// seconds. Alerts past the hourly limit are only counted and mentioned
+/// with the next batch that goes out.
+pub async fn run(
+    http: Arc<Http>,
+    config: &AlertConfig,
+    admin_channel: u64,
+) {
+    if config.interval == 0 || admin_channel == 0 {
+        return;
+    }
+    let mut posted = VecDeque::new();
+    let mut suppressed = 0;
+    loop {
+        tokio::time::sleep(Duration::from_secs(config.interval)).await;
+        // held back until the pause runs out
+        if switches::is_paused() {
+            continue;
+        }
+        let batch = take();
+        if batch.alerts.is_empty() {
+            continue;
+        }
+        let now = Instant::now();
+        while posted
+            .front()
+            .is_some_and(|f: &Instant| now.duration_since(*f) >= HOUR)
+        {
+            posted.pop_front();
+        }
+        if posted.len() >= config.max_posts as usize {
+            suppressed += batch.total();
+            continue;
+        }
+        match ChannelId::new(admin_channel)
+            .say(&http, batch.content(suppressed))
+            .await
+        {
+            Ok(_) => {
+                posted.push_back(now);
+                suppressed = 0;
+            },
+            Err(why) => {
+                error!("Couldn't post alerts: {why}");
+                suppressed += batch.total();
+            },
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! How many members a role ping reaches.
+//!
+//! Counting pages through the whole member list, which needs the Server
+//! Members intent enabled for the bot, so the counts of every role are
+//! kept for [MAX_AGE] and refreshed together.
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use serenity::all::{CacheHttp, GuildId, UserId};
+
+use crate::error::Error;
+
+const MAX_AGE: Duration = Duration::from_secs(10 * 60);
+/// Most members Discord returns per request.
+const PAGE: u64 = 1000;
+
+struct Counts {
+    taken: Instant,
+    roles: HashMap<u64, u64>,
+}
+
+static COUNTS: LazyLock<Mutex<Option<Counts>>> =
+    LazyLock::new(Default::default);
+
+fn cached(role: u64) -> Option<u64> {
+    let counts = COUNTS.lock().ok()?;
+    let counts = counts.as_ref().filter(|f| f.taken.elapsed() < MAX_AGE)?;
+    Some(counts.roles.get(&role).copied().unwrap_or(0))
+}
+
+async fn count_roles(
+    http: impl CacheHttp,
+    guild: GuildId,
+) -> Result<HashMap<u64, u64>, Error> {
+    let mut roles = HashMap::new();
+    let mut after: Option<UserId> = None;
+    loop {
+        let members = guild.members(http.http(), Some(PAGE), after).await?;
+        for member in members.iter() {
+            for role in member.roles.iter() {
+                *roles.entry(role.get()).or_default() += 1;
+            }
+        }
+        if (members.len() as u64) < PAGE {
+            return Ok(roles);
+        }
+        after = members.last().map(|f| f.user.id);
+    }
+}
+
+/// Members of `guild` holding `role`, at most [MAX_AGE] old.
+pub async fn role_members(
+    http: impl CacheHttp,
+    guild: u64,
+    role: u64,
+) -> Result<u64, Error> {
+    if let Some(count) = cached(role) {
+        return Ok(count);
+    }
+    let roles = count_roles(http, GuildId::new(guild)).await?;
+    let count = roles.get(&role).copied().unwrap_or(0);
+    if let Ok(mut counts) = COUNTS.lock() {
+        *counts = Some(Counts {
+            taken: Instant::now(),
+            roles,
+        });
+    }
+    Ok(count)
+}
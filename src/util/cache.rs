@@ -0,0 +1,93 @@
+use std::{fmt, future::Future};
+
+use chrono::{TimeDelta, Utc};
+use sqlx::MySqlConnection;
+use tracing::warn;
+
+use crate::{config::CacheConfig, error::Error};
+
+use super::{fetch_cache_entry, upsert_cache_entry};
+
+/// What kind of third-party data a cache entry holds, each kind has its own
+/// TTL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    Schedule,
+    Results,
+    Standings,
+}
+
+impl CacheKind {
+    pub fn i8(&self) -> i8 {
+        match self {
+            Self::Schedule => 0,
+            Self::Results => 1,
+            Self::Standings => 2,
+        }
+    }
+
+    pub fn ttl(
+        &self,
+        config: &CacheConfig,
+    ) -> TimeDelta {
+        let seconds = match self {
+            Self::Schedule => config.schedule_ttl,
+            Self::Results => config.results_ttl,
+            Self::Standings => config.standings_ttl,
+        };
+        TimeDelta::seconds(seconds as i64)
+    }
+}
+
+impl fmt::Display for CacheKind {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            Self::Schedule => "schedule",
+            Self::Results => "results",
+            Self::Standings => "standings",
+        })
+    }
+}
+
+/// Returns the cached body for `key` while it's younger than the kinds TTL,
+/// otherwise runs `fetch` and stores its result.
+///
+/// If `fetch` fails the stale body is returned instead, so an API outage
+/// keeps showing the last known data rather than nothing.
+pub async fn cached_fetch<F, Fut>(
+    db_conn: &mut MySqlConnection,
+    config: &CacheConfig,
+    kind: CacheKind,
+    key: &str,
+    fetch: F,
+) -> Result<String, Error>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<String, Error>>,
+{
+    let cached = fetch_cache_entry(db_conn, kind, key).await?;
+    if let Some(entry) = &cached {
+        if Utc::now() - entry.fetched_at < kind.ttl(config) {
+            return Ok(entry.body.clone());
+        }
+    }
+    match fetch().await {
+        Ok(body) => {
+            upsert_cache_entry(db_conn, kind, key, &body).await?;
+            Ok(body)
+        },
+        Err(why) => match cached {
+            Some(entry) => {
+                warn!(
+                    "Using {kind} for `{key}` from {} after fetch failed: {why}",
+                    entry.fetched_at
+                );
+                Ok(entry.body)
+            },
+            None => Err(why),
+        },
+    }
+}
@@ -0,0 +1,142 @@
+//! How often the external syncs and live-status checks poll.
+//!
+//! Around a session they run at least every `cadence.near` seconds, during
+//! a weekend at their own interval and between weekends at most every
+//! `cadence.idle` seconds.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::SessionStatus;
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::error;
+
+use crate::{
+    config::CadenceConfig,
+    util::{
+        acquire, fetch_sessions_starting_between, session_end, SessionDuration,
+    },
+};
+
+/// How close the bot is to a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// A session is live or within `cadence.window` of its start or end.
+    Near,
+    /// A session starts or ended within `cadence.weekend`.
+    Weekend,
+    Idle,
+}
+
+/// Polling intervals for one poller, its own `interval` as the base.
+#[derive(Debug, Clone, Copy)]
+pub struct Cadence {
+    base: TimeDelta,
+    near: TimeDelta,
+    idle: TimeDelta,
+    window: TimeDelta,
+    weekend: TimeDelta,
+}
+
+impl Cadence {
+    pub fn new(
+        base: u64,
+        config: &CadenceConfig,
+    ) -> Self {
+        Self {
+            base: TimeDelta::seconds(base as i64),
+            near: TimeDelta::seconds(config.near as i64),
+            idle: TimeDelta::seconds(config.idle as i64),
+            window: TimeDelta::seconds(config.window as i64),
+            weekend: TimeDelta::seconds(config.weekend as i64),
+        }
+    }
+
+    /// Where `now` is relative to `sessions`, given as start and end.
+    pub fn phase(
+        &self,
+        sessions: &[(DateTime<Utc>, DateTime<Utc>)],
+        now: DateTime<Utc>,
+    ) -> Phase {
+        let within = |margin: TimeDelta| {
+            sessions.iter().any(|(start, end)| {
+                *start - margin <= now && now < *end + margin
+            })
+        };
+        if within(self.window) {
+            Phase::Near
+        } else if within(self.weekend) {
+            Phase::Weekend
+        } else {
+            Phase::Idle
+        }
+    }
+
+    /// Time until the next poll. Never sleeps past the start of a window
+    /// around a session, so the faster polling starts on time.
+    pub fn interval(
+        &self,
+        sessions: &[(DateTime<Utc>, DateTime<Utc>)],
+        now: DateTime<Utc>,
+    ) -> Duration {
+        let interval = match self.phase(sessions, now) {
+            // 0 leaves the near and idle intervals to the poller
+            Phase::Near if self.near > TimeDelta::zero() => {
+                self.base.min(self.near)
+            },
+            Phase::Idle => self.base.max(self.idle),
+            _ => self.base,
+        };
+        let next_window = sessions
+            .iter()
+            .flat_map(|(start, _)| {
+                [*start - self.weekend, *start - self.window]
+            })
+            .filter(|f| *f > now)
+            .min();
+        let interval = next_window.map_or(interval, |f| interval.min(f - now));
+        interval.to_std().unwrap_or(Duration::ZERO)
+    }
+
+    /// Like [interval](Self::interval), with the sessions around `now`
+    /// read from the database.
+    pub async fn next(
+        &self,
+        db_conn: &mut MySqlConnection,
+        now: DateTime<Utc>,
+    ) -> Result<Duration, sqlx::Error> {
+        // sessions run for a day at most, one more covers the ones still
+        // live or just ended
+        let margin = self.weekend.max(self.window) + self.idle.max(self.base);
+        let sessions = fetch_sessions_starting_between(
+            db_conn,
+            now - margin - SessionDuration::MAX,
+            now + margin,
+        )
+        .await?
+        .into_iter()
+        .filter(|f| f.status != SessionStatus::Cancelled)
+        .map(|f| {
+            (f.start_date, session_end(f.start_date, SessionDuration::of(&f)))
+        })
+        .collect::<Vec<_>>();
+        Ok(self.interval(&sessions, now))
+    }
+
+    /// Sleeps until the next poll, for the base interval if the sessions
+    /// can't be read.
+    pub async fn sleep(
+        &self,
+        pool: &MySqlPool,
+    ) {
+        let wait = match acquire(pool).await {
+            Ok(mut db_conn) => self.next(db_conn.as_mut(), Utc::now()).await,
+            Err(why) => Err(why),
+        };
+        let wait = wait.unwrap_or_else(|why| {
+            error!("{why:#?}");
+            self.base.to_std().unwrap_or(Duration::ZERO)
+        });
+        tokio::time::sleep(wait).await;
+    }
+}
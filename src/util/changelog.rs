@@ -0,0 +1,154 @@
+//! Tells the admin channel what changed when the bot starts on a newer
+//! version than it last ran as.
+
+use chrono::Utc;
+use serenity::all::{CacheHttp, ChannelId, CreateEmbed, CreateMessage};
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info};
+
+use crate::{
+    error::Error,
+    util::{acquire, switches},
+};
+
+/// The bundled changelog, one `## <version>` section per release.
+pub const CHANGELOG: &str = include_str!("../../CHANGELOG.md");
+
+/// Version of this build.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// `bot_state` row holding the version of the previous run.
+const LAST_VERSION: &str = "last_version";
+
+/// Discord's limit for embed descriptions.
+const DESCRIPTION_LIMIT: usize = 4096;
+
+/// `version` split into its numeric parts, [None] if any part isn't a
+/// number.
+pub fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|f| f.parse().ok())
+        .collect()
+}
+
+/// Sections of `changelog` newer than `last` and not newer than `current`,
+/// newest first, as version and body pairs.
+pub fn changes_since<'a>(
+    changelog: &'a str,
+    last: &str,
+    current: &str,
+) -> Vec<(&'a str, &'a str)> {
+    let (Some(last), Some(current)) =
+        (parse_version(last), parse_version(current))
+    else {
+        return vec![];
+    };
+    let mut sections = vec![];
+    let mut rest = changelog;
+    while let Some(start) = rest.find("\n## ") {
+        rest = &rest[start + 4..];
+        let (heading, body) = rest.split_once('\n').unwrap_or((rest, ""));
+        let end = body.find("\n## ").unwrap_or(body.len());
+        let version = heading.trim();
+        if parse_version(version).is_some_and(|f| f > last && f <= current) {
+            sections.push((version, body[..end].trim()));
+        }
+    }
+    sections
+}
+
+async fn last_version(
+    db_conn: &mut MySqlConnection
+) -> Result<Option<String>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT value FROM bot_state WHERE name = ?",
+        LAST_VERSION
+    )
+    .fetch_optional(db_conn)
+    .await?;
+    Ok(row.map(|f| f.value))
+}
+
+async fn set_last_version(
+    db_conn: &mut MySqlConnection,
+    version: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO bot_state (name, value) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE value = VALUES(value)",
+        LAST_VERSION,
+        version
+    )
+    .execute(db_conn)
+    .await?;
+    Ok(())
+}
+
+/// The update message for the `sections` since the last run.
+fn changelog_embed(sections: &[(&str, &str)]) -> CreateEmbed {
+    let mut description = String::new();
+    for (version, body) in sections {
+        let section = format!("**v{version}**\n{body}\n\n");
+        if description.len() + section.len() > DESCRIPTION_LIMIT {
+            description += "…";
+            break;
+        }
+        description += &section;
+    }
+    CreateEmbed::new()
+        .title(format!("Bot updated to v{VERSION}"))
+        .description(description.trim_end())
+}
+
+/// Posts the changes since the previous run to `admin_channel` and records
+/// this version. The first run only records it.
+pub async fn announce(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    admin_channel: u64,
+) -> Result<(), Error> {
+    let last = last_version(db_conn).await?;
+    if last.as_deref() == Some(VERSION) {
+        return Ok(());
+    }
+    if let Some(last) = last {
+        let sections = changes_since(CHANGELOG, &last, VERSION);
+        info!("Updated from v{last} to v{VERSION}");
+        if admin_channel != 0 && !sections.is_empty() {
+            ChannelId::new(admin_channel)
+                .send_message(
+                    http.http(),
+                    CreateMessage::new().embed(changelog_embed(&sections)),
+                )
+                .await?;
+        }
+    }
+    set_last_version(db_conn, VERSION).await?;
+    Ok(())
+}
+
+/// [announce] on a connection of its own, for spawning at startup. Waits
+/// for a `/pause` to run out first.
+pub async fn run(
+    pool: MySqlPool,
+    http: impl CacheHttp,
+    admin_channel: u64,
+) {
+    while let Some(until) = switches::paused_until() {
+        info!("Paused, announcing the update after {until}");
+        let wait = (until - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+    let result = match acquire(&pool).await {
+        Ok(mut db_conn) => {
+            announce(db_conn.as_mut(), http, admin_channel).await
+        },
+        Err(why) => Err(why.into()),
+    };
+    if let Err(why) = result {
+        error!("{why:#?}");
+    }
+}
@@ -0,0 +1,72 @@
+//! Host clock check against the `Date` header of Discord API responses,
+//! notifications are only as punctual as the clock they're scheduled by.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serenity::{
+    all::{ChannelId, Http},
+    http::{LightMethod, Request, Route},
+};
+use tracing::{error, info, warn};
+
+use crate::{config::ClockConfig, error::Error, util::switches};
+
+/// How far the local clock is ahead of Discords, negative when behind.
+///
+/// The header only has second precision, so differences below a second
+/// don't mean anything.
+pub async fn discord_drift(http: &Http) -> Result<TimeDelta, Error> {
+    let sent = Utc::now();
+    let response =
+        http.request(Request::new(Route::Gateway, LightMethod::Get)).await?;
+    let received = Utc::now();
+    let date = response
+        .headers()
+        .get("date")
+        .and_then(|f| f.to_str().ok())
+        .and_then(|f| DateTime::parse_from_rfc2822(f).ok())
+        .ok_or(Error::NotFound)?;
+    let local = sent + received.signed_duration_since(sent) / 2;
+    Ok(local.signed_duration_since(date))
+}
+
+/// Checks the drift every `config.interval` seconds, starting right away,
+/// and warns in `admin_channel` while it's above `config.max_drift`.
+pub async fn watch(
+    http: Arc<Http>,
+    config: &ClockConfig,
+    admin_channel: u64,
+) {
+    if config.interval == 0 {
+        return;
+    }
+    let max_drift = TimeDelta::seconds(config.max_drift as i64);
+    loop {
+        match discord_drift(&http).await {
+            Ok(drift) if drift.abs() > max_drift => {
+                warn!("Clock is off by {}s", drift.num_seconds());
+                // the next check after the pause warns again
+                if admin_channel != 0 && !switches::is_paused() {
+                    let warning = format!(
+                        "The host clock is {}s {} Discord, notifications will be off by as much.",
+                        drift.num_seconds().abs(),
+                        if drift > TimeDelta::zero() {
+                            "ahead of"
+                        } else {
+                            "behind"
+                        }
+                    );
+                    if let Err(why) =
+                        ChannelId::new(admin_channel).say(&http, warning).await
+                    {
+                        error!("{why:#?}");
+                    }
+                }
+            },
+            Ok(drift) => info!("Clock drift {}ms", drift.num_milliseconds()),
+            Err(why) => error!("Couldn't check the clock: {why}"),
+        }
+        tokio::time::sleep(Duration::from_secs(config.interval)).await;
+    }
+}
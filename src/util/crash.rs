@@ -0,0 +1,88 @@
+//! Reports panics to the admin channel, so a crash at night is noticed in
+//! the morning rather than at the next missed session.
+
+use std::{
+    backtrace::Backtrace,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Write,
+    panic::{self, PanicHookInfo},
+    sync::mpsc,
+    time::Duration,
+};
+
+use serenity::all::{ChannelId, Http};
+use tracing::error;
+
+/// How long a panicking thread waits for the report to go out.
+const REPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn panic_message(info: &PanicHookInfo<'_>) -> &str {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(|f| f.as_str()))
+        .unwrap_or("(no message)")
+}
+
+/// Posts `content` from a fresh thread and runtime, the panicking one may
+/// be a runtime worker or about to take the runtime down with it.
+pub fn report(
+    token: &str,
+    channel: u64,
+    content: String,
+) {
+    if channel == 0 {
+        return;
+    }
+    let token = token.to_owned();
+    let (done, wait) = mpsc::channel();
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(_) => return,
+        };
+        let http = Http::new(&token);
+        let result =
+            runtime.block_on(ChannelId::new(channel).say(&http, content));
+        let _ = done.send(result.is_ok());
+    });
+    match wait.recv_timeout(REPORT_TIMEOUT) {
+        Ok(true) => {},
+        _ => eprintln!("Couldn't report the panic to the admin channel"),
+    }
+}
+
+/// Keeps the default hook's output, then logs the backtrace and reports the
+/// panic with a hash of the backtrace to tell repeated crashes apart.
+pub fn install(
+    token: &'static str,
+    admin_channel: u64,
+) {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default(info);
+        let backtrace = Backtrace::force_capture().to_string();
+        let mut hasher = DefaultHasher::new();
+        backtrace.hash(&mut hasher);
+        let hash = hasher.finish();
+        let location = info
+            .location()
+            .map(|f| format!("{}:{}", f.file(), f.line()))
+            .unwrap_or_default();
+        let message = panic_message(info);
+        error!("Panicked at {location}: {message}\n{backtrace}");
+        let _ = std::io::stdout().flush();
+        report(
+            token,
+            admin_channel,
+            format!(
+                ":rotating_light: Panicked at `{location}`: {message}\
+                 \nBacktrace `{hash:016x}`, see the logs."
+            ),
+        );
+    }));
+}
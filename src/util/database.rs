@@ -1,18 +1,240 @@
-use std::hash::Hash;
+use std::{
+    collections::HashSet, future::Future, hash::Hash, path::Path,
+    sync::OnceLock, time::Duration,
+};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
 use f1_bot_types::{
     Message, MessageKind, Series, Session, SessionStatus, Weekend,
     WeekendStatus,
 };
-use sqlx::MySqlConnection;
+use futures::stream::BoxStream;
+use sqlx::{
+    migrate::MigrateError,
+    mysql::{MySqlConnectOptions, MySqlDatabaseError},
+    pool::PoolConnection,
+    Connection, MySql, MySqlConnection, MySqlPool, Row,
+};
+use tracing::warn;
+
+use crate::{
+    config::{DatabaseConfig, DisplayConfig, SeasonConfig},
+    sync::SessionSource,
+    util::{
+        facts::circuit_facts, instance, is_in_notify_window, is_lights_out_due,
+        is_milestone_due, lookups, quarantine, session_end, CacheKind,
+        CustomKind, DateFormat, KindCode, SessionDuration, WeekStart,
+        CUSTOM_KIND_START, NOTIFY_WINDOW,
+    },
+};
+
+/// Longest delay between two attempts, however many failed before.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// MySQL error numbers that go away on their own: lock wait timeout,
+/// deadlock, too many connections, shutdown in progress, server gone away
+/// and lost connection.
+const TRANSIENT_ERRORS: [u16; 6] = [1205, 1213, 1040, 1053, 2006, 2013];
+
+/// Whether `why` is worth retrying, like a dropped connection or a deadlock.
+/// Bad queries and missing rows fail the same way every time.
+pub fn is_transient(why: &sqlx::Error) -> bool {
+    match why {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::Protocol(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::WorkerCrashed => true,
+        sqlx::Error::Database(why) => why
+            .try_downcast_ref::<MySqlDatabaseError>()
+            .is_some_and(|f| TRANSIENT_ERRORS.contains(&f.number())),
+        _ => false,
+    }
+}
+
+/// Retry schedule for transient database errors, set from the config by
+/// [connect].
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub retries: u32,
+    pub delay: Duration,
+}
+
+impl Backoff {
+    pub fn of(config: &DatabaseConfig<'_>) -> Self {
+        Self {
+            retries: config.retries,
+            delay: Duration::from_millis(config.retry_delay),
+        }
+    }
+
+    /// Delay before retry number `attempt`, counting from 0: doubled with
+    /// every attempt up to [MAX_RETRY_DELAY], and up to a quarter longer so
+    /// the background tasks don't all retry at once.
+    pub fn delay(
+        &self,
+        attempt: u32,
+    ) -> Duration {
+        let delay = self
+            .delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_DELAY);
+        let spread = delay.as_millis() as u64 / 4;
+        let jitter = match spread {
+            0 => 0,
+            spread => Utc::now().timestamp_subsec_nanos() as u64 % spread,
+        };
+        delay + Duration::from_millis(jitter)
+    }
+}
+
+static BACKOFF: OnceLock<Backoff> = OnceLock::new();
+
+/// The configured schedule, the defaults before [connect] ran.
+pub fn backoff() -> Backoff {
+    *BACKOFF.get_or_init(|| Backoff::of(&DatabaseConfig::default()))
+}
+
+/// Runs `attempt` until it succeeds, fails for good or [backoff] runs out
+/// of retries. Every transient failure is logged with `what`.
+pub async fn retry<T, F, Fut>(
+    what: &str,
+    mut attempt: F,
+) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let backoff = backoff();
+    let mut tries = 0;
+    loop {
+        match attempt().await {
+            Err(why) if tries < backoff.retries && is_transient(&why) => {
+                let delay = backoff.delay(tries);
+                warn!("{what} failed, retrying in {delay:?}: {why}");
+                tokio::time::sleep(delay).await;
+                tries += 1;
+            },
+            result => return result,
+        }
+    }
+}
+
+pub async fn connect(
+    config: &DatabaseConfig<'_>
+) -> Result<MySqlPool, sqlx::Error> {
+    // the first config wins, there is only ever one
+    let _ = BACKOFF.set(Backoff::of(config));
+    let db_options = MySqlConnectOptions::new()
+        .username(&config.username)
+        .password(&config.password)
+        .host(&config.url)
+        .port(3306)
+        .database("fia-docs");
+    retry("Connecting to the database", || {
+        MySqlPool::connect_with(db_options.clone())
+    })
+    .await
+}
+
+/// A connection from `pool`, retried like [retry].
+pub async fn acquire(
+    pool: &MySqlPool
+) -> Result<PoolConnection<MySql>, sqlx::Error> {
+    retry("Acquiring a database connection", || pool.acquire()).await
+}
+
+/// Replaces `db_conn` with a fresh connection from `pool` if it stopped
+/// answering, so a long-lived connection survives a database restart.
+pub async fn ensure_connected(
+    pool: &MySqlPool,
+    db_conn: &mut PoolConnection<MySql>,
+) -> Result<(), sqlx::Error> {
+    let Err(why) = db_conn.ping().await else {
+        return Ok(());
+    };
+    warn!("Lost the database connection: {why}");
+    // don't hand the broken connection back to the pool
+    db_conn.close_on_drop();
+    *db_conn = acquire(pool).await?;
+    Ok(())
+}
+
+/// Brings the bots own tables up to date.
+pub async fn migrate(pool: &MySqlPool) -> Result<(), MigrateError> {
+    sqlx::migrate!().run(pool).await
+}
+
+/// Queries run on every loop iteration, with representative values.
+const HOT_QUERIES: [&str; 3] = [
+    "SELECT * FROM messages WHERE kind = 1 AND series = 0",
+    "SELECT * FROM sessions WHERE weekend = 1 ORDER BY start_date ASC",
+    "SELECT * FROM weekends WHERE series = 0 AND status != 1 ORDER BY start_date ASC LIMIT 1",
+];
+
+/// Warns about every hot query MySQL plans as a full table scan, which
+/// usually means an index migration is missing.
+pub async fn check_query_plans(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    for query in HOT_QUERIES.iter() {
+        let plan =
+            sqlx::query(&format!("EXPLAIN {query}")).fetch_all(pool).await?;
+        for row in plan.iter() {
+            let access: Option<String> = row.try_get("type")?;
+            if access.as_deref() == Some("ALL") {
+                let table: Option<String> = row.try_get("table")?;
+                warn!("`{query}` scans all of `{}`", table.unwrap_or_default());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Range of weekend start dates the list queries load, [None] leaves that
+/// end open.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeasonWindow {
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl SeasonWindow {
+    /// Every weekend ever stored.
+    pub const ALL: Self = Self {
+        from: None,
+        until: None,
+    };
+
+    /// The seasons `config` keeps active around `now`.
+    pub fn around(
+        config: &SeasonConfig,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let first = now.year() - config.years_behind as i32;
+        let last = now.year() + config.years_ahead as i32;
+        Self {
+            from: Utc.with_ymd_and_hms(first, 1, 1, 0, 0, 0).single(),
+            until: Utc.with_ymd_and_hms(last + 1, 1, 1, 0, 0, 0).single(),
+        }
+    }
+}
 
 pub async fn fetch_weekends(
-    db_conn: &mut MySqlConnection
+    db_conn: &mut MySqlConnection,
+    window: SeasonWindow,
 ) -> Result<Vec<Weekend>, sqlx::Error> {
-    sqlx::query_as!(Weekend, "SELECT * FROM weekends ORDER BY start_date ASC")
-        .fetch_all(db_conn)
-        .await
+    sqlx::query_as!(
+        Weekend,
+        "SELECT * FROM weekends
+WHERE (? IS NULL OR start_date >= ?) AND (? IS NULL OR start_date < ?)
+ORDER BY start_date ASC",
+        window.from,
+        window.from,
+        window.until,
+        window.until
+    )
+    .fetch_all(db_conn)
+    .await
 }
 
 pub async fn fetch_weekend(
@@ -24,14 +246,37 @@ pub async fn fetch_weekend(
         .await
 }
 
+/// Weekends whose name is `LIKE` `pattern`, newest first.
+pub async fn search_weekends(
+    db_conn: &mut MySqlConnection,
+    pattern: &str,
+    limit: u32,
+) -> Result<Vec<Weekend>, sqlx::Error> {
+    sqlx::query_as!(
+        Weekend,
+        "SELECT * FROM weekends WHERE name LIKE ? ORDER BY start_date DESC LIMIT ?",
+        pattern,
+        limit
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
 pub async fn fetch_weekend_for_series(
     db_conn: &mut MySqlConnection,
     series: Series,
+    window: SeasonWindow,
 ) -> Result<Vec<Weekend>, sqlx::Error> {
     sqlx::query_as!(
         Weekend,
-        "SELECT * FROM weekends WHERE series = ? ORDER BY start_date ASC",
-        series.i8()
+        "SELECT * FROM weekends WHERE series = ?
+AND (? IS NULL OR start_date >= ?) AND (? IS NULL OR start_date < ?)
+ORDER BY start_date ASC",
+        series.i8(),
+        window.from,
+        window.from,
+        window.until,
+        window.until
     )
     .fetch_all(db_conn)
     .await
@@ -50,363 +295,2674 @@ pub async fn fetch_sessions(
     .await
 }
 
+/// A labeled URL shown as a button under a weekend message.
 #[derive(Debug)]
-pub struct FullWeekend {
-    pub weekend: Weekend,
-    pub sessions: Vec<Session>,
+pub struct WeekendLink {
+    pub id: u64,
+    pub weekend: u64,
+    pub label: String,
+    pub url: String,
 }
 
-impl FullWeekend {
-    pub fn check_is_done(&self, modified_session: &Session) -> bool {
-        if self.weekend.status == WeekendStatus::Done {
-            return true;
-        }
-        self.sessions.iter().all(|f| {
-            if f.id == modified_session.id {
-                return true;
-            }
-            matches!(f.status, SessionStatus::Finished | SessionStatus::Cancelled)
-        })
+pub async fn fetch_weekend_links(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+) -> Result<Vec<WeekendLink>, sqlx::Error> {
+    sqlx::query_as!(
+        WeekendLink,
+        "SELECT * FROM weekend_links WHERE weekend = ? ORDER BY id ASC",
+        weekend
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn insert_weekend_link(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    label: &str,
+    url: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO weekend_links (weekend, label, url) VALUES (?, ?, ?)",
+        weekend,
+        label,
+        url
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+pub async fn delete_weekend_link(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    label: &str,
+) -> Result<(), sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM weekend_links WHERE weekend = ? AND label = ?",
+        weekend,
+        label
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
     }
+    Ok(())
+}
 
-    pub fn is_done(&self) -> bool {
-        if self.weekend.status == WeekendStatus::Done {
-            return true;
+/// How much of each weekend the calendar lists.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalendarVerbosity {
+    /// Every session.
+    #[default]
+    Full,
+    /// Only qualifying and races.
+    Compact,
+    /// Only the dates of the weekend.
+    Minimal,
+}
+
+impl CalendarVerbosity {
+    pub const ALL: [Self; 3] = [Self::Full, Self::Compact, Self::Minimal];
+
+    /// The stored value.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Compact => "compact",
+            Self::Minimal => "minimal",
         }
-        self.sessions.iter().all(|f| {
-            matches!(f.status, SessionStatus::Finished | SessionStatus::Cancelled)
-        })
+    }
 
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == value)
     }
+}
 
-    pub fn next_session(&self) -> Option<&Session> {
-        if matches!(self.weekend.status, WeekendStatus::Done) {
-            return None;
-        }
-        self.sessions.iter().find(|f| {
-            matches!(
-                f.status,
-                f1_bot_types::SessionStatus::Open
-                    | f1_bot_types::SessionStatus::Delayed
-            ) && matches!(
-                f.start_date.signed_duration_since(Utc::now()).num_minutes(),
-                0..5
-            )
-        })
+/// Settings a guild can change at runtime, stored as name/value pairs.
+#[derive(Debug, Default, Clone, Hash)]
+pub struct GuildSettings {
+    /// Explain the sprint format under sprint weekends.
+    pub sprint_footer: bool,
+    /// List how long each session of the weekend runs.
+    pub lengths_footer: bool,
+    /// Also list low-priority sessions, like media days.
+    pub verbose: bool,
+    /// How much of each weekend the calendar lists.
+    pub calendar: CalendarVerbosity,
+    /// First day of the week in `/schedule`.
+    pub week_start: WeekStart,
+    /// How `/schedule` writes out days.
+    pub date_format: DateFormat,
+}
+
+impl GuildSettings {
+    /// Every setting name with its current value.
+    pub fn pairs(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("sprint_footer", self.sprint_footer.to_string()),
+            ("lengths_footer", self.lengths_footer.to_string()),
+            ("verbose", self.verbose.to_string()),
+            ("calendar", self.calendar.name().to_owned()),
+            ("week_start", self.week_start.name().to_owned()),
+            ("date_format", self.date_format.name().to_owned()),
+        ]
     }
 
-    pub fn weekend_msg_str(&self, extra: bool) -> String {
-        let mut sessions_str = String::new();
-        for session in self.sessions.iter() {
-            let tz = session.start_date.timestamp();
-            let is_done =
-                match Utc::now().timestamp() > tz + session.duration as i64 {
-                    true => "~~",
-                    false => "",
+    /// Applies one stored pair, returns false for unknown names or values.
+    pub fn set(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> bool {
+        match name {
+            "calendar" => {
+                let Some(value) = CalendarVerbosity::parse(value) else {
+                    return false;
+                };
+                self.calendar = value;
+                return true;
+            },
+            "week_start" => {
+                let Some(value) = WeekStart::parse(value) else {
+                    return false;
+                };
+                self.week_start = value;
+                return true;
+            },
+            "date_format" => {
+                let Some(value) = DateFormat::parse(value) else {
+                    return false;
                 };
-            sessions_str += &format!(
-                "\n> `{:>12}` {2}<t:{}:f> (<t:{1}:R>){2}",
-                session.title, tz, is_done
-            );
-        }
-        let extra_str = match extra {
-            true => &format!("\nUse <id:customize> to get the `{}-notifications` role\n**Times are in your Timezone**", self.weekend.series),
-            false => ""
+                self.date_format = value;
+                return true;
+            },
+            _ => {},
+        }
+        let Ok(value) = value.parse::<bool>() else {
+            return false;
         };
-        format!("{} {}{}{}", self.weekend.icon, self.weekend.name, sessions_str, extra_str)
+        match name {
+            "sprint_footer" => self.sprint_footer = value,
+            "lengths_footer" => self.lengths_footer = value,
+            "verbose" => self.verbose = value,
+            _ => return false,
+        }
+        true
     }
 }
 
-impl Hash for FullWeekend {
-    fn hash<H: std::hash::Hasher>(
-        &self,
-        state: &mut H,
-    ) {
-        state.write_u64(self.weekend.id);
-        state.write(self.weekend.name.as_bytes());
-        state.write_i64(self.weekend.start_date.timestamp_micros());
-        state.write(self.weekend.icon.as_bytes());
-        state.write_i8(self.weekend.status.i8());
-        for session in &self.sessions {
-            state.write_i64(session.id);
-            state.write_i64(session.weekend);
-            state.write_i8(session.kind.i8());
-            state.write(session.title.as_bytes());
-            state.write_i64(session.start_date.timestamp_micros());
-            state.write_i8(session.status.i8());
+pub async fn fetch_guild_settings(
+    db_conn: &mut MySqlConnection,
+    guild: u64,
+) -> Result<GuildSettings, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT name, value FROM guild_settings WHERE guild = ?",
+        guild
+    )
+    .fetch_all(db_conn)
+    .await?;
+    let mut settings = GuildSettings::default();
+    for row in rows.iter() {
+        settings.set(&row.name, &row.value);
+    }
+    Ok(settings)
+}
+
+pub async fn set_guild_setting(
+    db_conn: &mut MySqlConnection,
+    guild: u64,
+    name: &str,
+    value: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild, name, value) VALUES (?, ?, ?)
+ON DUPLICATE KEY UPDATE value = VALUES(value)",
+        guild,
+        name,
+        value
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// A known grid penalty for the race of a weekend.
+#[derive(Debug)]
+pub struct GridPenalty {
+    pub id: u64,
+    pub weekend: u64,
+    pub driver: String,
+    /// Free text like `5 places` or `back of the grid`.
+    pub penalty: String,
+    pub reason: Option<String>,
+}
+
+pub async fn fetch_grid_penalties(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+) -> Result<Vec<GridPenalty>, sqlx::Error> {
+    sqlx::query_as!(
+        GridPenalty,
+        "SELECT * FROM grid_penalties WHERE weekend = ? ORDER BY id ASC",
+        weekend
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn insert_grid_penalty(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    driver: &str,
+    penalty: &str,
+    reason: Option<&str>,
+) -> Result<u64, sqlx::Error> {
+    let id = sqlx::query!(
+        "INSERT INTO grid_penalties (weekend, driver, penalty, reason)
+VALUES (?, ?, ?, ?)",
+        weekend,
+        driver,
+        penalty,
+        reason
+    )
+    .execute(db_conn)
+    .await?
+    .last_insert_id();
+    lookups::invalidate();
+    Ok(id)
+}
+
+pub async fn delete_grid_penalty(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    id: u64,
+) -> Result<(), sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM grid_penalties WHERE weekend = ? AND id = ?",
+        weekend,
+        id
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    Ok(())
+}
+
+/// Extra data about a [Weekend] that isn't part of the shared schema.
+#[derive(Debug, Default)]
+pub struct WeekendMeta {
+    pub weekend: u64,
+    /// IANA name of the circuits timezone, e.g. `Europe/London`.
+    pub timezone: Option<String>,
+    /// Unconfirmed calendar entry, shown but never notified.
+    pub provisional: bool,
+    /// Linked [Circuit], see [fetch_weekend_circuit] for unlinked weekends.
+    pub circuit: Option<u64>,
+}
+
+pub async fn fetch_weekend_meta(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+) -> Result<WeekendMeta, sqlx::Error> {
+    let meta = sqlx::query_as!(
+        WeekendMeta,
+        "SELECT * FROM weekend_meta WHERE weekend = ?",
+        weekend
+    )
+    .fetch_optional(db_conn)
+    .await?;
+    Ok(meta.unwrap_or(WeekendMeta {
+        weekend,
+        ..Default::default()
+    }))
+}
+
+pub async fn set_weekend_provisional(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    provisional: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO weekend_meta (weekend, provisional) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE provisional = VALUES(provisional)",
+        weekend,
+        provisional
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+pub async fn set_weekend_timezone(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    timezone: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO weekend_meta (weekend, timezone) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE timezone = VALUES(timezone)",
+        weekend,
+        timezone
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+pub async fn set_weekend_circuit(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    circuit: Option<u64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO weekend_meta (weekend, circuit) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE circuit = VALUES(circuit)",
+        weekend,
+        circuit
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+/// A circuit with the coordinates its weather is forecast for.
+#[derive(Debug)]
+pub struct Circuit {
+    pub id: u64,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub country: Option<String>,
+    /// Track layout, an http(s) URL or a path on the bot host.
+    pub image: Option<String>,
+    /// Laps of the Formula 1 race.
+    pub laps: Option<u16>,
+}
+
+/// Where the layout image of a [Circuit] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackImage<'a> {
+    Url(&'a str),
+    File(&'a Path),
+}
+
+impl TrackImage<'_> {
+    /// Name the file is attached under, [None] for URLs.
+    pub fn file_name(&self) -> Option<String> {
+        match self {
+            Self::Url(_) => None,
+            Self::File(path) => {
+                Some(path.file_name()?.to_string_lossy().into_owned())
+            },
+        }
+    }
+}
+
+impl Circuit {
+    pub fn track_image(&self) -> Option<TrackImage<'_>> {
+        let image = self.image.as_deref().filter(|f| !f.trim().is_empty())?;
+        match image.starts_with("https://") || image.starts_with("http://") {
+            true => Some(TrackImage::Url(image)),
+            false => Some(TrackImage::File(Path::new(image))),
         }
     }
 }
 
-pub async fn fetch_full_weekends_for_series(
+pub async fn fetch_circuits(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<Circuit>, sqlx::Error> {
+    sqlx::query_as!(Circuit, "SELECT * FROM circuits ORDER BY name ASC")
+        .fetch_all(db_conn)
+        .await
+}
+
+pub async fn fetch_circuit_by_name(
+    db_conn: &mut MySqlConnection,
+    name: &str,
+) -> Result<Option<Circuit>, sqlx::Error> {
+    sqlx::query_as!(Circuit, "SELECT * FROM circuits WHERE name = ?", name)
+        .fetch_optional(db_conn)
+        .await
+}
+
+/// Circuit of `weekend`, the linked one or else the one of the bundled
+/// facts matching its name.
+pub async fn fetch_weekend_circuit(
+    db_conn: &mut MySqlConnection,
+    weekend: &Weekend,
+    meta: &WeekendMeta,
+) -> Result<Option<Circuit>, sqlx::Error> {
+    if let Some(circuit) = meta.circuit {
+        return sqlx::query_as!(
+            Circuit,
+            "SELECT * FROM circuits WHERE id = ?",
+            circuit
+        )
+        .fetch_optional(db_conn)
+        .await;
+    }
+    match circuit_facts(&weekend.name) {
+        Some(facts) => fetch_circuit_by_name(db_conn, facts.circuit).await,
+        None => Ok(None),
+    }
+}
+
+/// Daily weather forecast at a circuit.
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub circuit: u64,
+    pub day: NaiveDate,
+    /// Highest temperature of the day in °C.
+    pub temperature: f64,
+    /// Highest chance of rain over the day in percent.
+    pub rain_probability: u8,
+    pub fetched: DateTime<Utc>,
+}
+
+pub async fn fetch_forecasts(
+    db_conn: &mut MySqlConnection,
+    circuit: u64,
+) -> Result<Vec<Forecast>, sqlx::Error> {
+    sqlx::query_as!(
+        Forecast,
+        "SELECT * FROM weather_forecasts WHERE circuit = ? ORDER BY day ASC",
+        circuit
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn upsert_forecast(
+    db_conn: &mut MySqlConnection,
+    forecast: &Forecast,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO weather_forecasts
+(circuit, day, temperature, rain_probability, fetched)
+VALUES (?, ?, ?, ?, ?)
+ON DUPLICATE KEY UPDATE temperature = VALUES(temperature),
+rain_probability = VALUES(rain_probability), fetched = VALUES(fetched)",
+        forecast.circuit,
+        forecast.day,
+        forecast.temperature,
+        forecast.rain_probability,
+        forecast.fetched
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+/// Drops forecasts for days before `day`, they are never shown again.
+pub async fn delete_forecasts_before(
+    db_conn: &mut MySqlConnection,
+    day: NaiveDate,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query!("DELETE FROM weather_forecasts WHERE day < ?", day)
+        .execute(db_conn)
+        .await
+        .map(|f| f.rows_affected())
+}
+
+/// Whether `session` is a race, going by its title so feature and sprint
+/// races of the support series count too.
+pub fn is_race(session: &Session) -> bool {
+    session.title.to_ascii_lowercase().contains("race")
+}
+
+/// Whether `session` sets a grid, going by its title like [is_race].
+pub fn is_qualifying(session: &Session) -> bool {
+    let title = session.title.to_ascii_lowercase();
+    title.contains("qualifying") || title.contains("shootout")
+}
+
+#[derive(Debug)]
+pub struct FullWeekend {
+    pub weekend: Weekend,
+    pub sessions: Vec<Session>,
+    pub links: Vec<WeekendLink>,
+    pub meta: WeekendMeta,
+    pub penalties: Vec<GridPenalty>,
+    /// Position among the weekends of the series in the same year, by start
+    /// date.
+    pub round: u64,
+    pub circuit: Option<Circuit>,
+    /// Weather at the circuit, empty without a circuit or the `weather`
+    /// feature.
+    pub forecasts: Vec<Forecast>,
+}
+
+/// Loads everything attached to `weekend`.
+#[tracing::instrument(skip_all)]
+async fn fetch_full(
+    db_conn: &mut MySqlConnection,
+    weekend: Weekend,
+) -> Result<FullWeekend, sqlx::Error> {
+    let mut sessions = fetch_sessions(db_conn, &weekend).await?;
+    sessions.retain(|f| !quarantine::is_quarantined(f.id));
+    let links = fetch_weekend_links(db_conn, weekend.id).await?;
+    let meta = fetch_weekend_meta(db_conn, weekend.id).await?;
+    let penalties = fetch_grid_penalties(db_conn, weekend.id).await?;
+    let round = fetch_weekend_round(db_conn, &weekend).await?;
+    let circuit = fetch_weekend_circuit(db_conn, &weekend, &meta).await?;
+    let forecasts = match &circuit {
+        Some(circuit) => fetch_forecasts(db_conn, circuit.id).await?,
+        None => vec![],
+    };
+    Ok(FullWeekend {
+        weekend,
+        sessions,
+        links,
+        meta,
+        penalties,
+        round,
+        circuit,
+        forecasts,
+    })
+}
+
+/// Round of `weekend` in its series and year, weekends starting at the same
+/// time are ordered by id.
+pub async fn fetch_weekend_round(
+    db_conn: &mut MySqlConnection,
+    weekend: &Weekend,
+) -> Result<u64, sqlx::Error> {
+    let round = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM weekends
+WHERE series = ? AND YEAR(start_date) = ?
+AND (start_date < ? OR (start_date = ? AND id <= ?))",
+        weekend.series.i8(),
+        weekend.start_date.year(),
+        weekend.start_date,
+        weekend.start_date,
+        weekend.id
+    )
+    .fetch_one(db_conn)
+    .await?;
+    Ok(round as u64)
+}
+
+impl FullWeekend {
+    pub fn check_is_done(&self, modified_session: &Session) -> bool {
+        if self.weekend.status == WeekendStatus::Done {
+            return true;
+        }
+        self.sessions.iter().all(|f| {
+            if f.id == modified_session.id {
+                return true;
+            }
+            matches!(f.status, SessionStatus::Finished | SessionStatus::Cancelled)
+        })
+    }
+
+    pub fn is_done(&self) -> bool {
+        if self.weekend.status == WeekendStatus::Done {
+            return true;
+        }
+        self.sessions.iter().all(|f| {
+            matches!(f.status, SessionStatus::Finished | SessionStatus::Cancelled)
+        })
+
+    }
+
+    pub fn next_session(&self) -> Option<&Session> {
+        if matches!(self.weekend.status, WeekendStatus::Done) {
+            return None;
+        }
+        self.sessions.iter().find(|f| {
+            matches!(
+                f.status,
+                f1_bot_types::SessionStatus::Open
+                    | f1_bot_types::SessionStatus::Delayed
+            ) && matches!(
+                f.start_date.signed_duration_since(Utc::now()).num_minutes(),
+                0..5
+            )
+        })
+    }
+
+    /// Index of the first open session starting within the notification
+    /// window extended by `lead` that isn't in `notified` yet.
+    pub fn upcoming_session(
+        &self,
+        lead: TimeDelta,
+        notified: &HashSet<i64>,
+    ) -> Option<usize> {
+        if matches!(self.weekend.status, WeekendStatus::Done)
+            || self.meta.provisional
+        {
+            return None;
+        }
+        let now = Utc::now();
+        self.sessions.iter().position(|f| {
+            matches!(f.status, SessionStatus::Open | SessionStatus::Delayed)
+                && is_in_notify_window(f.start_date, now, lead)
+                && !notified.contains(&f.id)
+        })
+    }
+
+    /// Index of the race whose lights out `delay` seconds after its start
+    /// just passed.
+    pub fn due_lights_out(
+        &self,
+        delay: u64,
+    ) -> Option<usize> {
+        if matches!(self.weekend.status, WeekendStatus::Done)
+            || self.meta.provisional
+        {
+            return None;
+        }
+        let now = Utc::now();
+        self.sessions.iter().position(|f| {
+            is_race(f)
+                && matches!(
+                    f.status,
+                    SessionStatus::Open | SessionStatus::Delayed
+                )
+                && is_lights_out_due(f.start_date, delay, now)
+        })
+    }
+
+    /// Races with a milestone at one of `offsets` (in minutes) coming up
+    /// within the next five minutes, as session index and offset.
+    pub fn due_milestones(
+        &self,
+        offsets: &[u64],
+    ) -> Vec<(usize, u64)> {
+        self.due_offsets(offsets, true)
+    }
+
+    /// Like [due_milestones](Self::due_milestones) for every session, not
+    /// only races.
+    pub fn due_reminders(
+        &self,
+        offsets: &[u64],
+    ) -> Vec<(usize, u64)> {
+        self.due_offsets(offsets, false)
+    }
+
+    fn due_offsets(
+        &self,
+        offsets: &[u64],
+        races_only: bool,
+    ) -> Vec<(usize, u64)> {
+        if matches!(self.weekend.status, WeekendStatus::Done)
+            || self.meta.provisional
+        {
+            return vec![];
+        }
+        let now = Utc::now();
+        let mut due = Vec::new();
+        for (index, session) in self.sessions.iter().enumerate() {
+            if (races_only && !is_race(session))
+                || !matches!(
+                    session.status,
+                    SessionStatus::Open | SessionStatus::Delayed
+                )
+            {
+                continue;
+            }
+            for offset in offsets.iter() {
+                if is_milestone_due(session.start_date, now, *offset) {
+                    due.push((index, *offset));
+                }
+            }
+        }
+        due
+    }
+
+    /// Marker appended to the name of provisional weekends.
+    pub fn provisional_str(&self) -> &'static str {
+        match self.meta.provisional {
+            true => " *(provisional)*",
+            false => "",
+        }
+    }
+
+    /// "Round 7 — " prefix for the feeder series, which are referred to by
+    /// round more often than by venue.
+    pub fn round_str(&self) -> String {
+        match self.weekend.series {
+            Series::F1 => String::new(),
+            _ if self.round == 0 => String::new(),
+            _ => format!("Round {} — ", self.round),
+        }
+    }
+
+    /// Timezone of the circuit, if one is set and valid.
+    pub fn tz(&self) -> Option<Tz> {
+        self.meta.timezone.as_ref()?.parse().ok()
+    }
+
+    /// Start of `session` in the circuits local time, [None] without a
+    /// (valid) timezone.
+    pub fn track_time(
+        &self,
+        session: &Session,
+    ) -> Option<String> {
+        let local = session.start_date.with_timezone(&self.tz()?);
+        Some(local.format("%H:%M").to_string())
+    }
+
+    /// Suffix showing the track time of `session` if enabled in `display`.
+    pub fn track_time_str(
+        &self,
+        session: &Session,
+        display: &DisplayConfig,
+    ) -> String {
+        if !display.track_time {
+            return String::new();
+        }
+        match self.track_time(session) {
+            Some(time) => format!(" `{time} track`"),
+            None => String::new(),
+        }
+    }
+
+    /// "Silverstone, United Kingdom · 52 laps" line of the weekend message,
+    /// empty without a circuit.
+    pub fn circuit_str(&self) -> String {
+        let Some(circuit) = &self.circuit else {
+            return String::new();
+        };
+        let mut line = format!("📍 {}", circuit.name);
+        if let Some(country) = &circuit.country {
+            line += &format!(", {country}");
+        }
+        if let Some(laps) = circuit.laps {
+            line += &format!(" · {laps} laps");
+        }
+        line
+    }
+
+    /// Layout image of the circuit, if one is set.
+    pub fn track_image(&self) -> Option<TrackImage<'_>> {
+        self.circuit.as_ref()?.track_image()
+    }
+
+    /// Forecast for the day of `session` at the track, UTC without a
+    /// timezone.
+    pub fn forecast(
+        &self,
+        session: &Session,
+    ) -> Option<&Forecast> {
+        let day = match self.tz() {
+            Some(tz) => session.start_date.with_timezone(&tz).date_naive(),
+            None => session.start_date.date_naive(),
+        };
+        self.forecasts.iter().find(|f| f.day == day)
+    }
+
+    /// Suffix showing the forecast for the day of `session` if enabled in
+    /// `display` and known.
+    pub fn forecast_str(
+        &self,
+        session: &Session,
+        display: &DisplayConfig,
+    ) -> String {
+        if !display.weather {
+            return String::new();
+        }
+        match self.forecast(session) {
+            Some(forecast) => format!(
+                " · 🌡️ {:.0}°C 🌧️ {}%",
+                forecast.temperature, forecast.rain_probability
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// Small section listing the grid penalties, shown under the race.
+    pub fn penalties_str(&self) -> String {
+        let mut penalties = String::new();
+        for penalty in self.penalties.iter() {
+            penalties +=
+                &format!("\n> -# ↳ {}: {}", penalty.driver, penalty.penalty);
+            if let Some(reason) = &penalty.reason {
+                penalties += &format!(" ({reason})");
+            }
+        }
+        penalties
+    }
+
+    /// The educational footers enabled in `settings`.
+    pub fn footers_str(
+        &self,
+        settings: &GuildSettings,
+    ) -> String {
+        let mut footers = String::new();
+        let sprint = self
+            .sessions
+            .iter()
+            .any(|f| f.title.to_ascii_lowercase().contains("sprint"));
+        if settings.sprint_footer && sprint {
+            footers += match self.weekend.series {
+                Series::F1 => "\n-# Sprint weekends add a shorter Saturday race with its own qualifying, points go to the top 8.",
+                _ => "\n-# The Sprint Race starts from a partially reversed grid and awards fewer points than the Feature Race.",
+            };
+        }
+        if settings.lengths_footer && !self.sessions.is_empty() {
+            let lengths = self
+                .sessions
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{} {} min",
+                        f.title,
+                        SessionDuration::of(f).minutes()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            footers += &format!("\n-# Session lengths: {lengths}");
+        }
+        footers
+    }
+
+    /// "Did you know" footer for the circuit, empty when disabled or the
+    /// circuit isn't in the bundled dataset.
+    pub fn facts_str(
+        &self,
+        display: &DisplayConfig,
+    ) -> String {
+        if !display.circuit_facts {
+            return String::new();
+        }
+        match circuit_facts(&self.weekend.name) {
+            Some(facts) => format!(
+                "\n-# Did you know? {}",
+                facts.fact(self.weekend.id, Utc::now())
+            ),
+            None => String::new(),
+        }
+    }
+}
+
+/// Whether `session` is shown struck through at `now`.
+pub fn is_session_over(
+    session: &Session,
+    now: DateTime<Utc>,
+) -> bool {
+    now > session_end(session.start_date, SessionDuration::of(session))
+}
+
+impl Hash for FullWeekend {
+    fn hash<H: std::hash::Hasher>(
+        &self,
+        state: &mut H,
+    ) {
+        state.write_u64(self.weekend.id);
+        state.write(self.weekend.name.as_bytes());
+        state.write_i64(self.weekend.start_date.timestamp_micros());
+        state.write(self.weekend.icon.as_bytes());
+        state.write_i8(self.weekend.status.i8());
+        for session in &self.sessions {
+            state.write_i64(session.id);
+            state.write_i64(session.weekend);
+            state.write_i8(KindCode::of(&session.kind).map_or(-1, |f| f.0));
+            state.write(session.title.as_bytes());
+            state.write_i64(session.start_date.timestamp_micros());
+            state.write_i8(session.status.i8());
+        }
+        if let Some(timezone) = &self.meta.timezone {
+            state.write(timezone.as_bytes());
+        }
+        state.write_u8(self.meta.provisional as u8);
+        state.write_u64(self.round);
+        for penalty in &self.penalties {
+            state.write_u64(penalty.id);
+            state.write(penalty.penalty.as_bytes());
+        }
+        for link in &self.links {
+            state.write(link.label.as_bytes());
+            state.write(link.url.as_bytes());
+        }
+        if let Some(circuit) = &self.circuit {
+            state.write_u64(circuit.id);
+            state.write(circuit.image.as_deref().unwrap_or("").as_bytes());
+        }
+        for forecast in &self.forecasts {
+            state.write_i32(forecast.day.num_days_from_ce());
+            state.write_i64(forecast.temperature.round() as i64);
+            state.write_u8(forecast.rain_probability);
+        }
+    }
+}
+
+pub async fn fetch_full_weekends_for_series(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    window: SeasonWindow,
+) -> Result<Vec<FullWeekend>, sqlx::Error> {
+    let weekends = fetch_weekend_for_series(db_conn, series, window).await?;
+    let mut return_weekends = Vec::with_capacity(weekends.len());
+    for weekend in weekends.into_iter() {
+        return_weekends.push(fetch_full(db_conn, weekend).await?);
+    }
+    Ok(return_weekends)
+}
+
+/// Fetches the weekends of every series in `series`, ordered by start date.
+pub async fn fetch_full_weekends_for_channel(
+    db_conn: &mut MySqlConnection,
+    series: &[Series],
+    window: SeasonWindow,
+) -> Result<Vec<FullWeekend>, sqlx::Error> {
+    let mut weekends = Vec::new();
+    for series in series.iter() {
+        let series_weekends =
+            fetch_full_weekends_for_series(db_conn, *series, window).await?;
+        weekends.extend(series_weekends);
+    }
+    weekends.sort_by_key(|f| f.weekend.start_date);
+    Ok(weekends)
+}
+
+pub async fn fetch_full_weekends(
+    db_conn: &mut MySqlConnection,
+    window: SeasonWindow,
+) -> Result<Vec<FullWeekend>, sqlx::Error> {
+    let weekends = fetch_weekends(db_conn, window).await?;
+    let mut return_weekends = Vec::with_capacity(weekends.len());
+    for weekend in weekends.into_iter() {
+        return_weekends.push(fetch_full(db_conn, weekend).await?);
+    }
+    Ok(return_weekends)
+}
+
+pub async fn fetch_full_weekend(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<Option<FullWeekend>, sqlx::Error> {
+    let weekend =
+        sqlx::query_as!(Weekend, "SELECT * FROM weekends WHERE id = ?", id)
+            .fetch_optional(&mut *db_conn)
+            .await?;
+    Ok(match weekend {
+        None => None,
+        Some(weekend) => Some(fetch_full(db_conn, weekend).await?),
+    })
+}
+
+pub async fn fetch_next_weekend_for_series(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Option<Weekend>, sqlx::Error> {
+    sqlx::query_as!(
+        Weekend,
+        "SELECT * FROM weekends WHERE series = ? AND status != ? ORDER BY start_date ASC LIMIT 1",
+        series.i8(),
+        WeekendStatus::Done.i8(),
+    ).fetch_optional(db_conn).await
+}
+
+pub async fn fetch_next_full_weekend_for_series(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Option<FullWeekend>, sqlx::Error> {
+    let weekend = fetch_next_weekend_for_series(db_conn, series).await?;
+    Ok(match weekend {
+        None => None,
+        Some(weekend) => Some(fetch_full(db_conn, weekend).await?),
+    })
+}
+
+pub async fn fetch_messages(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages
+WHERE COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        instance::id()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// Scopes the message with row `id` to this [instance], production rows
+/// stay untagged.
+pub async fn tag_message(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<(), sqlx::Error> {
+    let instance = instance::id();
+    if instance.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!(
+        "INSERT INTO message_instances (message, instance) VALUES (?, ?)",
+        id,
+        instance
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// A slice of a larger result, for tables that grow without bound.
+#[derive(Debug, Clone, Copy)]
+pub struct Page {
+    pub limit: u32,
+    pub offset: u64,
+}
+
+impl Page {
+    /// Upper bound for `limit`, larger pages are cut down to this.
+    pub const MAX_LIMIT: u32 = 100;
+
+    pub fn new(
+        limit: u32,
+        offset: u64,
+    ) -> Self {
+        Self {
+            limit: limit.clamp(1, Self::MAX_LIMIT),
+            offset,
+        }
+    }
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self::new(25, 0)
+    }
+}
+
+/// Tracked messages, newest first, optionally of one series and kind only.
+pub async fn fetch_messages_page(
+    db_conn: &mut MySqlConnection,
+    series: Option<Series>,
+    kind: Option<i8>,
+    page: Page,
+) -> Result<Vec<Message>, sqlx::Error> {
+    let series = series.map(|f| f.i8());
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages
+WHERE (? IS NULL OR series = ?) AND (? IS NULL OR kind = ?)
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?
+ORDER BY id DESC LIMIT ? OFFSET ?",
+        series,
+        series,
+        kind,
+        kind,
+        instance::id(),
+        page.limit,
+        page.offset
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// Every tracked message, one row at a time.
+pub fn stream_messages(
+    db_conn: &mut MySqlConnection
+) -> BoxStream<'_, Result<Message, sqlx::Error>> {
+    sqlx::query_as!(Message, "SELECT * FROM messages ORDER BY id ASC")
+        .fetch(db_conn)
+}
+
+pub async fn fetch_weekend_messages(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE kind = ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        MessageKind::Weekend.i8(),
+        instance::id()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn mark_weekend_message_for_series_expired(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE messages SET expiry = ? WHERE kind = ? AND series = ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        Utc::now(),
+        MessageKind::Weekend.i8(),
+        series.i8(),
+        instance::id()
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+pub async fn fetch_weekend_message_for_series(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Option<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE kind = ? and series = ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        MessageKind::Weekend.i8(),
+        series.i8(),
+        instance::id()
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+pub async fn expired_messages(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE expiry IS NOT NULL AND expiry < now()
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        instance::id()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// Earliest expiry of a tracked message still to come.
+pub async fn fetch_next_expiry(
+    db_conn: &mut MySqlConnection
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT MIN(expiry) FROM messages WHERE expiry >= now()
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        instance::id()
+    )
+    .fetch_one(db_conn)
+    .await
+}
+
+pub async fn fetch_calendar_messages(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Vec<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE kind = ? AND series = ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?
+ORDER BY posted ASC",
+        MessageKind::Calendar.i8(),
+        series.i8(),
+        instance::id()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn fetch_custom_messages(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE kind = ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        MessageKind::Custom.i8(),
+        instance::id()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn fetch_series_calendar_messages(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Vec<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE series = ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        series.i8(),
+        instance::id()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// Sets a [Messages](Message) expiry date.
+/// If *date* is set to [None] then the message is set to expire immediately
+pub async fn mark_message_expired(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+    date: Option<DateTime<Utc>>,
+) -> Result<(), sqlx::Error> {
+    let date = date.unwrap_or(Utc::now());
+    let result =
+        sqlx::query!("UPDATE messages SET expiry = ? WHERE id = ?", date, id)
+            .execute(db_conn)
+            .await?;
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    Ok(())
+}
+
+/// Deletes a [Message]
+pub async fn delete_message(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM message_contents WHERE message = ?", id)
+        .execute(&mut *db_conn)
+        .await?;
+    sqlx::query!("DELETE FROM message_history WHERE message = ?", id)
+        .execute(&mut *db_conn)
+        .await?;
+    sqlx::query!("DELETE FROM message_instances WHERE message = ?", id)
+        .execute(&mut *db_conn)
+        .await?;
+    let result = sqlx::query!("DELETE FROM messages WHERE id = ?", id)
+        .execute(db_conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    Ok(())
+}
+
+/// Checks [Weekends](Weekend) and if all [Sessions](Session) are [Finished](SessionStatus)
+/// or [Cancelled](SessionStatus), then mark these Weekends as [Done](SessionStatus).
+pub async fn check_weekends(
+    db_conn: &mut MySqlConnection
+) -> Result<(), sqlx::Error> {
+    let weekends = fetch_full_weekends(db_conn, SeasonWindow::ALL).await?;
+    for weekend in weekends.into_iter().filter(|p| {
+        p.sessions.is_empty() && p.weekend.status == WeekendStatus::Open
+    }) {
+        if weekend.sessions.into_iter().all(|f| match f.status {
+            SessionStatus::Open | SessionStatus::Delayed => false,
+            SessionStatus::Finished | SessionStatus::Cancelled => true,
+        }) {
+            mark_weekend_done(db_conn, &weekend.weekend).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Marks a [Weekend] as [Done](WeekendStatus::Done)
+pub async fn mark_weekend_done(
+    db_conn: &mut MySqlConnection,
+    weekend: &Weekend,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE weekends SET status = ? WHERE id = ?",
+        WeekendStatus::Done.i8(),
+        weekend.id
+    )
+    .execute(&mut *db_conn)
+    .await?;
+    // penalties only matter for the upcoming race
+    sqlx::query!("DELETE FROM grid_penalties WHERE weekend = ?", weekend.id)
+        .execute(db_conn)
+        .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+pub async fn mark_session_done(
+    db_conn: &mut MySqlConnection,
+    session: &Session,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE sessions SET STATUS = ? WHERE id = ?",
+        SessionStatus::Finished.i8(),
+        session.id
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    Ok(())
+}
+
+pub async fn update_message_hash(
+    db_conn: &mut MySqlConnection,
+    msg_id: u64,
+    hash: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE messages SET hash = ? WHERE id = ?",
+        hash.to_string(),
+        msg_id
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// A batch of staged [ScheduleChanges](crate::sync::ScheduleChange) waiting
+/// for confirmation in the admin channel.
+#[derive(Debug)]
+pub struct PendingSync {
+    pub id: u64,
+    /// [None] for a new weekend, created from `series` and `name` once the
+    /// sync is applied.
+    pub weekend: Option<u64>,
+    pub series: Option<i8>,
+    pub name: Option<String>,
+    pub channel: Option<String>,
+    pub message: Option<String>,
+    pub created: DateTime<Utc>,
+    pub source: i8,
+}
+
+/// A single staged change belonging to a [PendingSync].
+#[derive(Debug)]
+pub struct PendingChange {
+    pub id: u64,
+    pub sync: u64,
+    pub session: Option<i64>,
+    pub kind: i8,
+    pub session_kind: i8,
+    pub title: String,
+    pub start_date: Option<DateTime<Utc>>,
+    pub previous_date: Option<DateTime<Utc>>,
+    pub duration: i64,
+}
+
+pub async fn fetch_pending_sync(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<Option<PendingSync>, sqlx::Error> {
+    sqlx::query_as!(PendingSync, "SELECT * FROM pending_syncs WHERE id = ?", id)
+        .fetch_optional(db_conn)
+        .await
+}
+
+pub async fn fetch_pending_syncs_for_weekend(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+) -> Result<Vec<PendingSync>, sqlx::Error> {
+    sqlx::query_as!(
+        PendingSync,
+        "SELECT * FROM pending_syncs WHERE weekend = ?",
+        weekend
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// Staged syncs creating the weekend `name` of `series`.
+pub async fn fetch_pending_syncs_for_new_weekend(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    name: &str,
+) -> Result<Vec<PendingSync>, sqlx::Error> {
+    sqlx::query_as!(
+        PendingSync,
+        "SELECT * FROM pending_syncs
+WHERE weekend IS NULL AND series = ? AND name = ?",
+        series.i8(),
+        name
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn fetch_pending_changes(
+    db_conn: &mut MySqlConnection,
+    sync: u64,
+) -> Result<Vec<PendingChange>, sqlx::Error> {
+    sqlx::query_as!(
+        PendingChange,
+        "SELECT * FROM pending_changes WHERE sync = ? ORDER BY id ASC",
+        sync
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn insert_pending_sync(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    source: SessionSource,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO pending_syncs (weekend, source) VALUES (?, ?)",
+        weekend,
+        source.i8()
+    )
+    .execute(db_conn)
+    .await
+    .map(|f| f.last_insert_id())
+}
+
+/// Stages a sync for a weekend that isn't stored yet.
+pub async fn insert_pending_weekend(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    name: &str,
+    source: SessionSource,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO pending_syncs (series, name, source) VALUES (?, ?, ?)",
+        series.i8(),
+        name,
+        source.i8()
+    )
+    .execute(db_conn)
+    .await
+    .map(|f| f.last_insert_id())
+}
+
+pub async fn set_pending_sync_message(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+    channel: u64,
+    message: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE pending_syncs SET channel = ?, message = ? WHERE id = ?",
+        channel.to_string(),
+        message.to_string(),
+        id
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Deletes a [PendingSync], its [PendingChanges](PendingChange) are removed
+/// by the foreign key cascade.
+/// Returns false if the sync was removed already.
+pub async fn delete_pending_sync(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!("DELETE FROM pending_syncs WHERE id = ?", id)
+        .execute(db_conn)
+        .await
+        .map(|f| f.rows_affected() > 0)
+}
+
+pub async fn fetch_session(
+    db_conn: &mut MySqlConnection,
+    id: i64,
+) -> Result<Option<Session>, sqlx::Error> {
+    sqlx::query_as!(Session, "SELECT * FROM sessions WHERE id = ?", id)
+        .fetch_optional(db_conn)
+        .await
+}
+
+/// The `series` weekend called `name` in the `year` season.
+pub async fn fetch_weekend_by_name(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    name: &str,
+    year: i32,
+) -> Result<Option<u64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT id FROM weekends
+WHERE series = ? AND name = ? AND YEAR(start_date) = ?",
+        series.i8(),
+        name,
+        year
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+/// Kind of the latest stored `series` session titled `title`, for imports
+/// from sources without kinds.
+pub async fn fetch_kind_for_title(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    title: &str,
+) -> Result<Option<KindCode>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT sessions.kind FROM sessions
+JOIN weekends ON weekends.id = sessions.weekend
+WHERE weekends.series = ? AND sessions.title = ?
+ORDER BY sessions.start_date DESC LIMIT 1",
+        series.i8(),
+        title
+    )
+    .fetch_optional(db_conn)
+    .await
+    .map(|f| f.map(KindCode))
+}
+
+/// Adds an [Open](WeekendStatus::Open) [Weekend], returns its id.
+pub async fn insert_weekend(
+    db_conn: &mut MySqlConnection,
+    name: &str,
+    icon: &str,
+    series: Series,
+    start_date: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let id = sqlx::query!(
+        "INSERT INTO weekends (name, icon, series, start_date, status)
+VALUES (?, ?, ?, ?, ?)",
+        name,
+        icon,
+        series.i8(),
+        start_date,
+        WeekendStatus::Open.i8()
+    )
+    .execute(db_conn)
+    .await?
+    .last_insert_id();
+    lookups::invalidate();
+    Ok(id)
+}
+
+/// Adds an [Open](SessionStatus::Open) [Session] entered by hand, so syncs
+/// leave it alone. Returns its id.
+pub async fn insert_manual_session(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+    kind: i8,
+    title: &str,
+    start_date: DateTime<Utc>,
+    duration: SessionDuration,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = db_conn.begin().await?;
+    let id = sqlx::query!(
+        "INSERT INTO sessions
+(weekend, kind, title, start_date, duration, status)
+VALUES (?, ?, ?, ?, ?, ?)",
+        weekend,
+        kind,
+        title,
+        start_date,
+        duration.secs(),
+        SessionStatus::Open.i8()
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id() as i64;
+    set_session_source(&mut *tx, id, SessionSource::Manual).await?;
+    tx.commit().await?;
+    lookups::invalidate();
+    Ok(id)
+}
+
+/// Copies `source` with its sessions, links and metadata to a new weekend
+/// called `name` starting at `start_date`, every session moved by the same
+/// offset. Sessions without a stored kind are left out.
+///
+/// Returns the new weekend id and the number of copied sessions.
+pub async fn clone_weekend(
+    db_conn: &mut MySqlConnection,
+    source: &FullWeekend,
+    name: &str,
+    start_date: DateTime<Utc>,
+) -> Result<(u64, usize), sqlx::Error> {
+    let offset = start_date.signed_duration_since(source.weekend.start_date);
+    let mut tx = db_conn.begin().await?;
+    let id = insert_weekend(
+        &mut *tx,
+        name,
+        &source.weekend.icon,
+        source.weekend.series,
+        start_date,
+    )
+    .await?;
+    let mut copied = 0;
+    for session in source.sessions.iter() {
+        let Some(kind) = KindCode::of(&session.kind) else {
+            continue;
+        };
+        insert_manual_session(
+            &mut *tx,
+            id,
+            kind.0,
+            &session.title,
+            session.start_date + offset,
+            SessionDuration::of(session),
+        )
+        .await?;
+        copied += 1;
+    }
+    for link in source.links.iter() {
+        insert_weekend_link(&mut *tx, id, &link.label, &link.url).await?;
+    }
+    if source.meta.timezone.is_some() {
+        set_weekend_timezone(&mut *tx, id, source.meta.timezone.as_deref())
+            .await?;
+    }
+    if source.meta.circuit.is_some() {
+        set_weekend_circuit(&mut *tx, id, source.meta.circuit).await?;
+    }
+    if source.meta.provisional {
+        set_weekend_provisional(&mut *tx, id, true).await?;
+    }
+    tx.commit().await?;
+    Ok((id, copied))
+}
+
+/// Records where a [Session] was last written from.
+pub async fn set_session_source(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    source: SessionSource,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO session_sources (session, source) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE source = VALUES(source)",
+        session,
+        source.i8()
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Fetches the ids of a weekends [Sessions](Session) that were manually
+/// [Delayed](SessionStatus::Delayed) or [Cancelled](SessionStatus::Cancelled).
+/// Sessions without a recorded source count as manual.
+pub async fn fetch_protected_sessions(
+    db_conn: &mut MySqlConnection,
+    weekend: u64,
+) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT sessions.id FROM sessions
+LEFT JOIN session_sources ON session_sources.session = sessions.id
+WHERE sessions.weekend = ? AND sessions.status IN (?, ?)
+AND COALESCE(session_sources.source, ?) = ?",
+        weekend,
+        SessionStatus::Delayed.i8(),
+        SessionStatus::Cancelled.i8(),
+        SessionSource::Manual.i8(),
+        SessionSource::Manual.i8()
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// A delivered notification, kept for delivery statistics.
+#[derive(Debug)]
+pub struct NotificationLog {
+    pub id: u64,
+    pub session: i64,
+    pub series: Series,
+    pub channel: String,
+    pub message: String,
+    pub intended: DateTime<Utc>,
+    pub sent: DateTime<Utc>,
+    pub latency: i64,
+    /// Members holding the pinged role, when they could be counted.
+    pub audience: Option<u64>,
+}
+
+pub async fn log_notification(
+    db_conn: &mut MySqlConnection,
+    session: &Session,
+    series: Series,
+    channel: u64,
+    message: u64,
+    sent: DateTime<Utc>,
+    audience: Option<u64>,
+) -> Result<(), sqlx::Error> {
+    // due once the notification window opens, not at the start
+    let intended = session.start_date - NOTIFY_WINDOW;
+    sqlx::query!(
+        "INSERT INTO notification_log
+(session, series, channel, message, intended, sent, latency, audience)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        session.id,
+        series.i8(),
+        channel.to_string(),
+        message.to_string(),
+        intended,
+        sent,
+        sent.signed_duration_since(intended).num_seconds(),
+        audience
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+pub async fn fetch_notification_log_for_year(
+    db_conn: &mut MySqlConnection,
+    year: i32,
+) -> Result<Vec<NotificationLog>, sqlx::Error> {
+    sqlx::query_as!(
+        NotificationLog,
+        "SELECT * FROM notification_log WHERE YEAR(intended) = ? ORDER BY sent ASC",
+        year
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// Like [fetch_notification_log_for_year], one row at a time.
+pub fn stream_notification_log_for_year(
+    db_conn: &mut MySqlConnection,
+    year: i32,
+) -> BoxStream<'_, Result<NotificationLog, sqlx::Error>> {
+    sqlx::query_as!(
+        NotificationLog,
+        "SELECT * FROM notification_log WHERE YEAR(intended) = ? ORDER BY sent ASC",
+        year
+    )
+    .fetch(db_conn)
+}
+
+pub async fn fetch_notification_log_page(
+    db_conn: &mut MySqlConnection,
+    year: i32,
+    page: Page,
+) -> Result<Vec<NotificationLog>, sqlx::Error> {
+    sqlx::query_as!(
+        NotificationLog,
+        "SELECT * FROM notification_log WHERE YEAR(intended) = ?
+ORDER BY sent ASC LIMIT ? OFFSET ?",
+        year,
+        page.limit,
+        page.offset
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn fetch_notification_log_by_message(
+    db_conn: &mut MySqlConnection,
+    message: &str,
+) -> Result<Option<NotificationLog>, sqlx::Error> {
+    sqlx::query_as!(
+        NotificationLog,
+        "SELECT * FROM notification_log WHERE message = ?",
+        message
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+/// The notification sent last for `session`.
+pub async fn fetch_notification_log_by_session(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+) -> Result<Option<NotificationLog>, sqlx::Error> {
+    sqlx::query_as!(
+        NotificationLog,
+        "SELECT * FROM notification_log WHERE session = ?
+ORDER BY sent DESC LIMIT 1",
+        session
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+/// Reactions and thread activity on a sessions notification.
+#[derive(Debug)]
+pub struct SessionEngagement {
+    pub id: u64,
+    pub session: i64,
+    pub series: Series,
+    pub title: String,
+    pub weekend_name: String,
+    pub reactions: u64,
+    pub thread_messages: u64,
+    pub recorded: DateTime<Utc>,
+}
+
+pub async fn insert_session_engagement(
+    db_conn: &mut MySqlConnection,
+    weekend: &Weekend,
+    session: &Session,
+    reactions: u64,
+    thread_messages: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO session_engagement
+(session, series, title, weekend_name, reactions, thread_messages)
+VALUES (?, ?, ?, ?, ?, ?)",
+        session.id,
+        weekend.series.i8(),
+        session.title,
+        weekend.name,
+        reactions,
+        thread_messages
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+pub async fn fetch_session_engagement_for_year(
+    db_conn: &mut MySqlConnection,
+    year: i32,
+) -> Result<Vec<SessionEngagement>, sqlx::Error> {
+    sqlx::query_as!(
+        SessionEngagement,
+        "SELECT * FROM session_engagement WHERE YEAR(recorded) = ?
+ORDER BY reactions DESC, thread_messages DESC",
+        year
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+pub async fn is_milestone_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    offset: u64,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!(
+        "SELECT session FROM milestones_sent
+WHERE session = ? AND offset_minutes = ?",
+        session,
+        offset
+    )
+    .fetch_optional(db_conn)
+    .await
+    .map(|f| f.is_some())
+}
+
+pub async fn mark_milestone_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    offset: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT IGNORE INTO milestones_sent (session, offset_minutes)
+VALUES (?, ?)",
+        session,
+        offset
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+pub async fn is_reminder_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    offset: u64,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!(
+        "SELECT session FROM reminders_sent
+WHERE session = ? AND offset_minutes = ?",
+        session,
+        offset
+    )
+    .fetch_optional(db_conn)
+    .await
+    .map(|f| f.is_some())
+}
+
+pub async fn mark_reminder_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    offset: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT IGNORE INTO reminders_sent (session, offset_minutes)
+VALUES (?, ?)",
+        session,
+        offset
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Notifications a session can get, each one is sent at most once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationStage {
+    /// The regular notification as the session starts.
+    Start,
+    /// The notice for a session that started while the bot was down.
+    CatchUp,
+}
+
+impl NotificationStage {
+    pub fn i8(self) -> i8 {
+        match self {
+            Self::Start => 0,
+            Self::CatchUp => 1,
+        }
+    }
+}
+
+/// Ids of the sessions starting after `since` that got any notification.
+pub async fn fetch_notified_sessions(
+    db_conn: &mut MySqlConnection,
+    since: DateTime<Utc>,
+) -> Result<HashSet<i64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT DISTINCT notifications.session FROM notifications
+JOIN sessions ON sessions.id = notifications.session
+WHERE sessions.start_date >= ?",
+        since
+    )
+    .fetch_all(db_conn)
+    .await
+    .map(|f| f.into_iter().collect())
+}
+
+pub async fn is_notification_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!(
+        "SELECT session FROM notifications WHERE session = ? LIMIT 1",
+        session
+    )
+    .fetch_optional(db_conn)
+    .await
+    .map(|f| f.is_some())
+}
+
+pub async fn mark_notification_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    stage: NotificationStage,
+    message: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT IGNORE INTO notifications (session, stage, message)
+VALUES (?, ?, ?)",
+        session,
+        stage.i8(),
+        message
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+pub async fn is_lights_out_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!(
+        "SELECT session FROM lights_out_sent WHERE session = ?",
+        session
+    )
+    .fetch_optional(db_conn)
+    .await
+    .map(|f| f.is_some())
+}
+
+pub async fn mark_lights_out_sent(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT IGNORE INTO lights_out_sent (session) VALUES (?)",
+        session
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Tracks a message that gets deleted once `expiry` has passed.
+pub async fn insert_expiring_message(
+    db_conn: &mut MySqlConnection,
+    channel: u64,
+    message: u64,
+    kind: MessageKind,
+    series: Series,
+    expiry: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let id = sqlx::query!(
+        "INSERT INTO messages
+(channel, message, kind, posted, series, expiry)
+VALUES(?, ?, ?, ?, ?, ?)",
+        channel.to_string(),
+        message.to_string(),
+        kind.i8(),
+        Utc::now(),
+        series.i8(),
+        expiry
+    )
+    .execute(&mut *db_conn)
+    .await?
+    .last_insert_id();
+    tag_message(db_conn, id).await
+}
+
+/// Pushes `session` back by `minutes` and marks it
+/// [Delayed](SessionStatus::Delayed). With `cascade` the later sessions of the
+/// same weekend on the same day (track time if known, UTC otherwise) move
+/// along, all in one transaction. Only open and delayed sessions move.
+/// Returns the moved sessions.
+pub async fn delay_session(
+    db_conn: &mut MySqlConnection,
+    weekend: &FullWeekend,
+    session: &Session,
+    minutes: i64,
+    cascade: bool,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let tz: Tz = weekend
+        .meta
+        .timezone
+        .as_ref()
+        .and_then(|f| f.parse().ok())
+        .unwrap_or(Tz::UTC);
+    let day = session.start_date.with_timezone(&tz).date_naive();
+    let moved = weekend
+        .sessions
+        .iter()
+        // finished and cancelled sessions would be notified again
+        .filter(|f| {
+            matches!(f.status, SessionStatus::Open | SessionStatus::Delayed)
+        })
+        .filter(|f| {
+            f.id == session.id
+                || (cascade
+                    && f.start_date > session.start_date
+                    && f.start_date.with_timezone(&tz).date_naive() == day)
+        })
+        .collect::<Vec<_>>();
+
+    let mut tx = db_conn.begin().await?;
+    for f in moved.iter() {
+        sqlx::query!(
+            "UPDATE sessions SET start_date = ?, status = ? WHERE id = ?",
+            f.start_date + TimeDelta::minutes(minutes),
+            SessionStatus::Delayed.i8(),
+            f.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        set_session_source(&mut *tx, f.id, SessionSource::Manual).await?;
+    }
+    tx.commit().await?;
+    lookups::invalidate();
+    Ok(moved.into_iter().map(|f| f.id).collect())
+}
+
+/// Marks an [Open](SessionStatus::Open) session as
+/// [Delayed](SessionStatus::Delayed) without moving it, for delays reported
+/// upstream before a new start time is known. Returns whether it changed.
+pub async fn mark_session_delayed(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+    source: SessionSource,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = db_conn.begin().await?;
+    let changed = sqlx::query!(
+        "UPDATE sessions SET status = ? WHERE id = ? AND status = ?",
+        SessionStatus::Delayed.i8(),
+        session,
+        SessionStatus::Open.i8()
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+    if changed {
+        set_session_source(&mut *tx, session, source).await?;
+    }
+    tx.commit().await?;
+    lookups::invalidate();
+    Ok(changed)
+}
+
+/// Marks the sessions with `ids` of `weekend` as
+/// [Cancelled](SessionStatus::Cancelled), skipping ones that are already
+/// over. Returns the cancelled sessions.
+pub async fn cancel_sessions(
+    db_conn: &mut MySqlConnection,
+    weekend: &FullWeekend,
+    ids: &[i64],
+) -> Result<Vec<i64>, sqlx::Error> {
+    let cancelled = weekend
+        .sessions
+        .iter()
+        .filter(|f| {
+            ids.contains(&f.id)
+                && matches!(
+                    f.status,
+                    SessionStatus::Open | SessionStatus::Delayed
+                )
+        })
+        .map(|f| f.id)
+        .collect::<Vec<_>>();
+
+    let mut tx = db_conn.begin().await?;
+    for id in cancelled.iter() {
+        sqlx::query!(
+            "UPDATE sessions SET status = ? WHERE id = ?",
+            SessionStatus::Cancelled.i8(),
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        set_session_source(&mut *tx, *id, SessionSource::Manual).await?;
+    }
+    tx.commit().await?;
+    lookups::invalidate();
+    Ok(cancelled)
+}
+
+/// The content last rendered into the [Message] with `id`.
+pub async fn fetch_message_content(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT content FROM message_contents WHERE message = ?",
+        id
+    )
+    .fetch_optional(db_conn)
+    .await
+}
+
+/// Stores the content rendered into the [Message] with `id` and appends it
+/// to the message's history, `hash` being the hash that triggered the edit.
+pub async fn set_message_content(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+    content: &str,
+    hash: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO message_contents (message, content) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE content = VALUES(content)",
+        id,
+        content
+    )
+    .execute(&mut *db_conn)
+    .await?;
+    insert_message_revision(db_conn, id, content, hash).await
+}
+
+/// How many past contents are kept per [Message].
+pub const MESSAGE_HISTORY_LEN: u64 = 10;
+
+/// One past content of a tracked [Message].
+#[derive(Debug)]
+pub struct MessageRevision {
+    pub id: u64,
+    pub message: u64,
+    pub content: String,
+    pub hash: u64,
+    pub created: DateTime<Utc>,
+}
+
+async fn insert_message_revision(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+    content: &str,
+    hash: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO message_history (message, content, hash) VALUES (?, ?, ?)",
+        id,
+        content,
+        hash
+    )
+    .execute(&mut *db_conn)
+    .await?;
+    let oldest_kept = sqlx::query_scalar!(
+        "SELECT id FROM message_history WHERE message = ?
+ORDER BY id DESC LIMIT 1 OFFSET ?",
+        id,
+        MESSAGE_HISTORY_LEN - 1
+    )
+    .fetch_optional(&mut *db_conn)
+    .await?;
+    if let Some(oldest_kept) = oldest_kept {
+        sqlx::query!(
+            "DELETE FROM message_history WHERE message = ? AND id < ?",
+            id,
+            oldest_kept
+        )
+        .execute(db_conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Past contents of the [Message] with `id`, newest first.
+pub async fn fetch_message_history(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<Vec<MessageRevision>, sqlx::Error> {
+    sqlx::query_as!(
+        MessageRevision,
+        "SELECT * FROM message_history WHERE message = ? ORDER BY id DESC",
+        id
+    )
+    .fetch_all(db_conn)
+    .await
+}
+
+/// The tracked [Message] posted as Discord message `message`.
+pub async fn fetch_message_by_discord_id(
     db_conn: &mut MySqlConnection,
-    series: Series,
-) -> Result<Vec<FullWeekend>, sqlx::Error> {
-    let weekends = fetch_weekend_for_series(db_conn, series).await?;
-    let mut return_weekends = Vec::with_capacity(weekends.len());
-    for weekend in weekends.into_iter() {
-        let sessions = fetch_sessions(db_conn, &weekend).await?;
-        return_weekends.push(FullWeekend {
-            weekend,
-            sessions,
-        });
-    }
-    Ok(return_weekends)
+    message: u64,
+) -> Result<Option<Message>, sqlx::Error> {
+    sqlx::query_as!(
+        Message,
+        "SELECT * FROM messages WHERE message = ?",
+        message.to_string()
+    )
+    .fetch_optional(db_conn)
+    .await
 }
 
-pub async fn fetch_full_weekends(
+/// A stage opened for a live race, kept so a restart picks it back up.
+#[derive(Debug)]
+pub struct StageInstanceRow {
+    pub session: i64,
+    pub channel: u64,
+    pub started: DateTime<Utc>,
+}
+
+pub async fn fetch_stage_instances(
     db_conn: &mut MySqlConnection
-) -> Result<Vec<FullWeekend>, sqlx::Error> {
-    let weekends = fetch_weekends(db_conn).await?;
-    let mut return_weekends = Vec::with_capacity(weekends.len());
-    for weekend in weekends.into_iter() {
-        let sessions = fetch_sessions(db_conn, &weekend).await?;
-        return_weekends.push(FullWeekend {
-            weekend,
-            sessions,
-        });
-    }
-    Ok(return_weekends)
+) -> Result<Vec<StageInstanceRow>, sqlx::Error> {
+    sqlx::query_as!(StageInstanceRow, "SELECT * FROM stage_instances")
+        .fetch_all(db_conn)
+        .await
 }
 
-pub async fn fetch_full_weekend(
+pub async fn insert_stage_instance(
     db_conn: &mut MySqlConnection,
-    id: u64,
-) -> Result<Option<FullWeekend>, sqlx::Error> {
-    let weekend =
-        sqlx::query_as!(Weekend, "SELECT * FROM weekends WHERE id = ?", id)
-            .fetch_optional(&mut *db_conn)
-            .await?;
-    Ok(match weekend {
-        None => None,
-        Some(weekend) => {
-            let sessions = fetch_sessions(db_conn, &weekend).await?;
-            Some(FullWeekend {
-                weekend,
-                sessions,
-            })
-        },
-    })
+    session: i64,
+    channel: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT IGNORE INTO stage_instances (session, channel) VALUES (?, ?)",
+        session,
+        channel
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
 }
 
-pub async fn fetch_next_weekend_for_series(
+pub async fn delete_stage_instance(
     db_conn: &mut MySqlConnection,
-    series: Series,
-) -> Result<Option<Weekend>, sqlx::Error> {
+    session: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM stage_instances WHERE session = ?", session)
+        .execute(db_conn)
+        .await
+        .map(|_f| ())
+}
+
+/// Sessions starting at or after `from` and before `until`, soonest first.
+pub async fn fetch_sessions_starting_between(
+    db_conn: &mut MySqlConnection,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<Session>, sqlx::Error> {
     sqlx::query_as!(
-        Weekend,
-        "SELECT * FROM weekends WHERE series = ? AND status != ? ORDER BY start_date ASC LIMIT 1",
-        series.i8(),
-        WeekendStatus::Done.i8(),
-    ).fetch_optional(db_conn).await
+        Session,
+        "SELECT * FROM sessions
+WHERE start_date >= ? AND start_date < ?
+ORDER BY start_date ASC",
+        from,
+        until
+    )
+    .fetch_all(db_conn)
+    .await
+    .map(|mut f| {
+        f.retain(|f| !quarantine::is_quarantined(f.id));
+        f
+    })
 }
 
-pub async fn fetch_next_full_weekend_for_series(
+/// Sessions that started but haven't reached their end yet.
+pub async fn fetch_live_sessions(
     db_conn: &mut MySqlConnection,
-    series: Series,
-) -> Result<Option<FullWeekend>, sqlx::Error> {
-    let weekend = fetch_next_weekend_for_series(db_conn, series).await?;
-    Ok(match weekend {
-        None => None,
-        Some(weekend) => Some({
-            let sessions = fetch_sessions(db_conn, &weekend).await?;
-            FullWeekend {
-                weekend,
-                sessions,
-            }
-        }),
+    now: DateTime<Utc>,
+) -> Result<Vec<Session>, sqlx::Error> {
+    sqlx::query_as!(
+        Session,
+        "SELECT * FROM sessions
+WHERE start_date <= ?
+AND DATE_ADD(start_date, INTERVAL duration SECOND) > ?
+AND status != ?
+ORDER BY start_date ASC",
+        now,
+        now,
+        SessionStatus::Cancelled.i8()
+    )
+    .fetch_all(db_conn)
+    .await
+    .map(|mut f| {
+        f.retain(|f| !quarantine::is_quarantined(f.id));
+        f
     })
 }
 
-pub async fn fetch_messages(
-    db_conn: &mut MySqlConnection
-) -> Result<Vec<Message>, sqlx::Error> {
-    sqlx::query_as!(Message, "SELECT * FROM messages").fetch_all(db_conn).await
+#[derive(Debug)]
+pub struct CacheEntry {
+    pub body: String,
+    pub fetched_at: DateTime<Utc>,
 }
 
-pub async fn fetch_weekend_messages(
-    db_conn: &mut MySqlConnection
-) -> Result<Vec<Message>, sqlx::Error> {
+pub async fn fetch_cache_entry(
+    db_conn: &mut MySqlConnection,
+    kind: CacheKind,
+    key: &str,
+) -> Result<Option<CacheEntry>, sqlx::Error> {
     sqlx::query_as!(
-        Message,
-        "SELECT * FROM messages WHERE kind = ?",
-        MessageKind::Weekend.i8()
+        CacheEntry,
+        "SELECT body, fetched_at FROM external_cache
+WHERE kind = ? AND cache_key = ?",
+        kind.i8(),
+        key
     )
-    .fetch_all(db_conn)
+    .fetch_optional(db_conn)
     .await
 }
 
-pub async fn mark_weekend_message_for_series_expired(
+pub async fn upsert_cache_entry(
     db_conn: &mut MySqlConnection,
-    series: Series,
+    kind: CacheKind,
+    key: &str,
+    body: &str,
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
-        "UPDATE messages SET expiry = ? WHERE kind = ? AND series = ?",
-        Utc::now(),
-        MessageKind::Weekend.i8(),
-        series.i8()
+        "INSERT INTO external_cache (kind, cache_key, body, fetched_at)
+VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+ON DUPLICATE KEY UPDATE body = VALUES(body), fetched_at = CURRENT_TIMESTAMP",
+        kind.i8(),
+        key,
+        body
     )
     .execute(db_conn)
     .await
     .map(|_f| ())
 }
 
-pub async fn fetch_weekend_message_for_series(
-    db_conn: &mut MySqlConnection,
-    series: Series,
-) -> Result<Option<Message>, sqlx::Error> {
+pub async fn fetch_custom_kinds(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<CustomKind>, sqlx::Error> {
     sqlx::query_as!(
-        Message,
-        "SELECT * FROM messages WHERE kind = ? and series = ?",
-        MessageKind::Weekend.i8(),
-        series.i8()
+        CustomKind,
+        "SELECT * FROM custom_session_kinds ORDER BY code ASC"
     )
-    .fetch_optional(db_conn)
+    .fetch_all(db_conn)
     .await
 }
 
-pub async fn expired_messages(
+/// Registers a new custom kind under the next free code, returns the code.
+pub async fn insert_custom_kind(
+    db_conn: &mut MySqlConnection,
+    name: &str,
+    low_priority: bool,
+) -> Result<i8, sqlx::Error> {
+    let mut tx = db_conn.begin().await?;
+    let last = sqlx::query_scalar!(
+        "SELECT MAX(code) FROM custom_session_kinds FOR UPDATE"
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    let code = match last {
+        Some(last) => last.checked_add(1).ok_or(sqlx::Error::RowNotFound)?,
+        None => CUSTOM_KIND_START,
+    };
+    sqlx::query!(
+        "INSERT INTO custom_session_kinds (code, name, low_priority)
+VALUES (?, ?, ?)",
+        code,
+        name,
+        low_priority
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(code)
+}
+
+/// A session with its kind as stored, before it's mapped to [SessionKind].
+#[derive(Debug)]
+pub struct RawSessionKind {
+    pub id: i64,
+    pub weekend: i64,
+    pub kind: i8,
+    pub title: String,
+}
+
+pub async fn fetch_raw_session_kinds(
     db_conn: &mut MySqlConnection
-) -> Result<Vec<Message>, sqlx::Error> {
+) -> Result<Vec<RawSessionKind>, sqlx::Error> {
     sqlx::query_as!(
-        Message,
-        "SELECT * FROM messages WHERE expiry IS NOT NULL AND expiry < now()"
+        RawSessionKind,
+        "SELECT id, weekend, kind, title FROM sessions"
     )
     .fetch_all(db_conn)
     .await
 }
 
-pub async fn fetch_calendar_messages(
+pub async fn set_session_kind(
     db_conn: &mut MySqlConnection,
-    series: Series,
-) -> Result<Vec<Message>, sqlx::Error> {
+    session: i64,
+    kind: i8,
+) -> Result<(), sqlx::Error> {
+    let result = sqlx::query!(
+        "UPDATE sessions SET kind = ? WHERE id = ?",
+        kind,
+        session
+    )
+    .execute(db_conn)
+    .await?;
+    lookups::invalidate();
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    Ok(())
+}
+
+/// Sessions whose end falls between `from` and `until`.
+pub async fn fetch_ended_sessions(
+    db_conn: &mut MySqlConnection,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<Session>, sqlx::Error> {
     sqlx::query_as!(
-        Message,
-        "SELECT * FROM messages WHERE kind = ? AND series = ? ORDER BY posted ASC",
-        MessageKind::Calendar.i8(),
-        series.i8()
+        Session,
+        "SELECT * FROM sessions
+WHERE DATE_ADD(start_date, INTERVAL duration SECOND) BETWEEN ? AND ?
+AND status != ?
+ORDER BY start_date ASC",
+        from,
+        until,
+        SessionStatus::Cancelled.i8()
     )
     .fetch_all(db_conn)
     .await
+    .map(|mut f| {
+        f.retain(|f| !quarantine::is_quarantined(f.id));
+        f
+    })
 }
 
-pub async fn fetch_custom_messages(
-    db_conn: &mut MySqlConnection
-) -> Result<Vec<Message>, sqlx::Error> {
+/// A rate-your-race poll, `votes` and `total` are filled in once it's
+/// collected.
+#[derive(Debug)]
+pub struct RaceRating {
+    pub session: i64,
+    pub series: i8,
+    pub channel: u64,
+    pub message: u64,
+    pub posted: DateTime<Utc>,
+    pub closes: DateTime<Utc>,
+    pub votes: u64,
+    /// Sum of all ratings.
+    pub total: u64,
+    pub collected: bool,
+}
+
+impl RaceRating {
+    pub fn average(&self) -> Option<f64> {
+        (self.votes > 0).then(|| self.total as f64 / self.votes as f64)
+    }
+}
+
+pub async fn fetch_race_rating(
+    db_conn: &mut MySqlConnection,
+    session: i64,
+) -> Result<Option<RaceRating>, sqlx::Error> {
     sqlx::query_as!(
-        Message,
-        "SELECT * FROM messages WHERE kind = ?",
-        MessageKind::Custom.i8()
+        RaceRating,
+        "SELECT * FROM race_ratings WHERE session = ?",
+        session
     )
-    .fetch_all(db_conn)
+    .fetch_optional(db_conn)
     .await
 }
 
-pub async fn fetch_series_calendar_messages(
+pub async fn insert_race_rating(
     db_conn: &mut MySqlConnection,
+    session: i64,
     series: Series,
-) -> Result<Vec<Message>, sqlx::Error> {
+    channel: u64,
+    message: u64,
+    closes: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT IGNORE INTO race_ratings (session, series, channel, message, closes)
+VALUES (?, ?, ?, ?, ?)",
+        session,
+        series.i8(),
+        channel,
+        message,
+        closes
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
+}
+
+/// Polls that closed before `now` without being collected.
+pub async fn fetch_closed_race_ratings(
+    db_conn: &mut MySqlConnection,
+    now: DateTime<Utc>,
+) -> Result<Vec<RaceRating>, sqlx::Error> {
     sqlx::query_as!(
-        Message,
-        "SELECT * FROM messages WHERE series = ?",
-        series.i8()
+        RaceRating,
+        "SELECT * FROM race_ratings WHERE collected = FALSE AND closes <= ?",
+        now
     )
     .fetch_all(db_conn)
     .await
 }
 
-/// Sets a [Messages](Message) expiry date.
-/// If *date* is set to [None] then the message is set to expire immediately
-pub async fn mark_message_expired(
+pub async fn set_race_rating_results(
     db_conn: &mut MySqlConnection,
-    id: u64,
-    date: Option<DateTime<Utc>>,
+    session: i64,
+    votes: u64,
+    total: u64,
 ) -> Result<(), sqlx::Error> {
-    let date = date.unwrap_or(Utc::now());
-    let result =
-        sqlx::query!("UPDATE messages SET expiry = ? WHERE id = ?", date, id)
-            .execute(db_conn)
-            .await?;
-    if result.rows_affected() == 0 {
-        return Err(sqlx::Error::RowNotFound);
-    }
-    Ok(())
+    sqlx::query!(
+        "UPDATE race_ratings SET votes = ?, total = ?, collected = TRUE
+WHERE session = ?",
+        votes,
+        total,
+        session
+    )
+    .execute(db_conn)
+    .await
+    .map(|_f| ())
 }
 
-/// Deletes a [Message]
-pub async fn delete_message(
+/// Collected ratings of `series` from polls posted in `year`.
+pub async fn fetch_season_race_ratings(
     db_conn: &mut MySqlConnection,
-    id: u64,
-) -> Result<(), sqlx::Error> {
-    let result = sqlx::query!("DELETE FROM messages WHERE id = ?", id)
-        .execute(db_conn)
-        .await?;
-    if result.rows_affected() == 0 {
-        return Err(sqlx::Error::RowNotFound);
-    }
-    Ok(())
+    series: Series,
+    year: i32,
+) -> Result<Vec<RaceRating>, sqlx::Error> {
+    sqlx::query_as!(
+        RaceRating,
+        "SELECT * FROM race_ratings
+WHERE series = ? AND YEAR(posted) = ? AND collected = TRUE",
+        series.i8(),
+        year
+    )
+    .fetch_all(db_conn)
+    .await
 }
 
-/// Checks [Weekends](Weekend) and if all [Sessions](Session) are [Finished](SessionStatus)
-/// or [Cancelled](SessionStatus), then mark these Weekends as [Done](SessionStatus).
-pub async fn check_weekends(
-    db_conn: &mut MySqlConnection
-) -> Result<(), sqlx::Error> {
-    let weekends = fetch_full_weekends(db_conn).await?;
-    for weekend in weekends.into_iter().filter(|p| {
-        p.sessions.is_empty() && p.weekend.status == WeekendStatus::Open
-    }) {
-        if weekend.sessions.into_iter().all(|f| match f.status {
-            SessionStatus::Open | SessionStatus::Delayed => false,
-            SessionStatus::Finished | SessionStatus::Cancelled => true,
-        }) {
-            mark_weekend_done(db_conn, &weekend.weekend).await?;
-        }
-    }
-
-    Ok(())
+pub async fn fetch_archive_thread(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+    year: i32,
+) -> Result<Option<u64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT thread FROM archive_threads WHERE series = ? AND year = ?",
+        series.i8(),
+        year
+    )
+    .fetch_optional(db_conn)
+    .await
 }
 
-/// Marks a [Weekend] as [Done](WeekendStatus::Done)
-pub async fn mark_weekend_done(
+pub async fn insert_archive_thread(
     db_conn: &mut MySqlConnection,
-    weekend: &Weekend,
+    series: Series,
+    year: i32,
+    thread: u64,
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
-        "UPDATE weekends SET status = ? WHERE id = ?",
-        WeekendStatus::Done.i8(),
-        weekend.id
+        "INSERT INTO archive_threads (series, year, thread) VALUES (?, ?, ?)",
+        series.i8(),
+        year,
+        thread
     )
     .execute(db_conn)
     .await
     .map(|_f| ())
 }
 
-pub async fn mark_session_done(
+/// A link submitted with `/submit-link`.
+#[derive(Debug)]
+pub struct WatchLink {
+    pub id: u64,
+    pub session: i64,
+    pub label: String,
+    pub url: String,
+    pub submitter: u64,
+    pub approved: bool,
+    pub channel: Option<u64>,
+    pub message: Option<u64>,
+}
+
+pub async fn insert_watch_link(
     db_conn: &mut MySqlConnection,
-    session: &Session,
+    session: i64,
+    label: &str,
+    url: &str,
+    submitter: u64,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO watch_links (session, label, url, submitter)
+VALUES (?, ?, ?, ?)",
+        session,
+        label,
+        url,
+        submitter
+    )
+    .execute(db_conn)
+    .await
+    .map(|f| f.last_insert_id())
+}
+
+pub async fn fetch_watch_link(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+) -> Result<Option<WatchLink>, sqlx::Error> {
+    sqlx::query_as!(WatchLink, "SELECT * FROM watch_links WHERE id = ?", id)
+        .fetch_optional(db_conn)
+        .await
+}
+
+/// Marks a link as approved and posted as `message` in `channel`.
+pub async fn set_watch_link_posted(
+    db_conn: &mut MySqlConnection,
+    id: u64,
+    channel: u64,
+    message: u64,
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
-        "UPDATE sessions SET STATUS = ? WHERE id = ?",
-        SessionStatus::Finished.i8(),
-        session.id
+        "UPDATE watch_links SET approved = TRUE, channel = ?, message = ?
+WHERE id = ?",
+        channel,
+        message,
+        id
     )
     .execute(db_conn)
     .await
     .map(|_f| ())
 }
 
-pub async fn update_message_hash(
+pub async fn delete_watch_link(
     db_conn: &mut MySqlConnection,
-    msg_id: u64,
-    hash: u64,
+    id: u64,
 ) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM watch_links WHERE id = ?", id)
+        .execute(db_conn)
+        .await
+        .map(|_f| ())
+}
+
+/// Removes the links of sessions that ended before `now`, returns how many.
+pub async fn delete_finished_watch_links(
+    db_conn: &mut MySqlConnection,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
     sqlx::query!(
-        "UPDATE messages SET hash = ? WHERE id = ?",
-        hash.to_string(),
-        msg_id
+        "DELETE watch_links FROM watch_links
+JOIN sessions ON sessions.id = watch_links.session
+WHERE DATE_ADD(sessions.start_date, INTERVAL sessions.duration SECOND) <= ?",
+        now
     )
     .execute(db_conn)
     .await
-    .map(|_f| ())
+    .map(|f| f.rows_affected())
 }
@@ -0,0 +1,132 @@
+//! Catches session times imported without adjusting for a DST change, or a
+//! move to another timezone, since the previous weekend.
+
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+
+use chrono::{DateTime, Offset, TimeDelta, Utc};
+use chrono_tz::Tz;
+use serenity::all::{CacheHttp, ChannelId};
+use sqlx::MySqlConnection;
+use tracing::warn;
+
+use crate::error::Error;
+
+use super::{
+    fetch_full_weekends_for_series, names, FullWeekend, KindCode, SeasonWindow,
+};
+
+/// Sessions already reported, so each one is only warned about once.
+static REPORTED: LazyLock<Mutex<HashSet<i64>>> =
+    LazyLock::new(Default::default);
+
+/// A session whose local start time jumped against the same session of the
+/// previous weekend.
+#[derive(Debug)]
+pub struct DstSuspect {
+    pub session: i64,
+    pub title: String,
+    pub weekend: String,
+    /// Local time of day of this session minus the previous one.
+    pub jump: TimeDelta,
+}
+
+/// The local time of day jump between two sessions of the same kind on
+/// consecutive weekends, if it looks like the UTC time was copied over
+/// while the UTC offset changed in between.
+pub fn suspicious_jump(
+    previous: DateTime<Utc>,
+    previous_tz: Tz,
+    current: DateTime<Utc>,
+    current_tz: Tz,
+) -> Option<TimeDelta> {
+    let offset = |start: DateTime<Utc>, tz: Tz| {
+        start.with_timezone(&tz).offset().fix().local_minus_utc()
+    };
+    let change = offset(current, current_tz) - offset(previous, previous_tz);
+    if change == 0 || previous.time() != current.time() {
+        return None;
+    }
+    Some(TimeDelta::seconds(change.into()))
+}
+
+/// Every suspicious session in `weekends`, which are ordered by start date
+/// and all of one series.
+pub fn dst_suspects(weekends: &[FullWeekend]) -> Vec<DstSuspect> {
+    let mut suspects = Vec::new();
+    for pair in weekends.windows(2) {
+        let [previous, current] = pair else {
+            continue;
+        };
+        let (Some(previous_tz), Some(current_tz)) =
+            (previous.tz(), current.tz())
+        else {
+            continue;
+        };
+        for session in current.sessions.iter() {
+            let Some(kind) = KindCode::of(&session.kind) else {
+                continue;
+            };
+            let Some(earlier) = previous
+                .sessions
+                .iter()
+                .find(|f| KindCode::of(&f.kind) == Some(kind))
+            else {
+                continue;
+            };
+            if let Some(jump) = suspicious_jump(
+                earlier.start_date,
+                previous_tz,
+                session.start_date,
+                current_tz,
+            ) {
+                suspects.push(DstSuspect {
+                    session: session.id,
+                    title: session.title.clone(),
+                    weekend: current.weekend.name.clone(),
+                    jump,
+                });
+            }
+        }
+    }
+    suspects
+}
+
+/// Warns about new suspicious sessions of every series, in the log and in
+/// `admin_channel` unless it's 0.
+pub async fn check(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    admin_channel: u64,
+    window: SeasonWindow,
+) -> Result<(), Error> {
+    for series in names::ALL_SERIES {
+        let weekends =
+            fetch_full_weekends_for_series(db_conn, series, window).await?;
+        for suspect in dst_suspects(&weekends) {
+            let new =
+                REPORTED.lock().is_ok_and(|mut f| f.insert(suspect.session));
+            if !new {
+                continue;
+            }
+            let warning = format!(
+                "{series} {} `{}` starts {}h {} local time than the previous weekend with the same UTC time, check for a missed DST change.",
+                suspect.weekend,
+                suspect.title,
+                suspect.jump.num_minutes().abs() as f64 / 60.0,
+                if suspect.jump > TimeDelta::zero() {
+                    "later"
+                } else {
+                    "earlier"
+                }
+            );
+            warn!("{warning}");
+            if admin_channel != 0 {
+                ChannelId::new(admin_channel).say(&http, warning).await?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Length of a session, stored as whole seconds in `sessions.duration`.
+
+use std::fmt;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::Session;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::{MySqlTypeInfo, MySqlValueRef},
+    Database, Decode, Encode, MySql,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    Negative,
+    /// Longer than [SessionDuration::MAX] without being all-day.
+    TooLong,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Negative => write!(f, "durations can't be negative"),
+            Self::TooLong => write!(
+                f,
+                "sessions can't run longer than {} hours",
+                SessionDuration::MAX.num_hours()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(try_from = "i64", into = "i64")]
+pub struct SessionDuration(TimeDelta);
+
+impl SessionDuration {
+    /// Longest regular session, only all-day entries may run longer.
+    pub const MAX: TimeDelta = TimeDelta::hours(24);
+
+    pub fn new(
+        duration: TimeDelta,
+        all_day: bool,
+    ) -> Result<Self, DurationError> {
+        if duration < TimeDelta::zero() {
+            return Err(DurationError::Negative);
+        }
+        if !all_day && duration > Self::MAX {
+            return Err(DurationError::TooLong);
+        }
+        Ok(Self(duration))
+    }
+
+    pub fn from_secs(secs: i64) -> Result<Self, DurationError> {
+        let duration =
+            TimeDelta::try_seconds(secs).ok_or(DurationError::TooLong)?;
+        Self::new(duration, false)
+    }
+
+    pub fn from_minutes(minutes: i64) -> Result<Self, DurationError> {
+        Self::from_secs(minutes.checked_mul(60).ok_or(DurationError::TooLong)?)
+    }
+
+    /// A stored duration, which may predate the validation. Negative ones
+    /// count as no duration at all, overly long ones are kept.
+    pub fn stored(secs: i64) -> Self {
+        Self(TimeDelta::try_seconds(secs.max(0)).unwrap_or(TimeDelta::MAX))
+    }
+
+    /// Duration of `session`, see [stored](Self::stored).
+    pub fn of(session: &Session) -> Self {
+        Self::stored(session.duration)
+    }
+
+    pub fn delta(self) -> TimeDelta {
+        self.0
+    }
+
+    pub fn secs(self) -> i64 {
+        self.0.num_seconds()
+    }
+
+    pub fn minutes(self) -> i64 {
+        self.0.num_minutes()
+    }
+
+    /// End of a session starting at `start`, the end of time if that
+    /// overflows.
+    pub fn end(
+        self,
+        start: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        start.checked_add_signed(self.0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+impl TryFrom<i64> for SessionDuration {
+    type Error = DurationError;
+
+    fn try_from(secs: i64) -> Result<Self, Self::Error> {
+        Self::from_secs(secs)
+    }
+}
+
+impl From<SessionDuration> for i64 {
+    fn from(duration: SessionDuration) -> Self {
+        duration.secs()
+    }
+}
+
+impl sqlx::Type<MySql> for SessionDuration {
+    fn type_info() -> MySqlTypeInfo {
+        <i64 as sqlx::Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <i64 as sqlx::Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, MySql> for SessionDuration {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <MySql as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        <i64 as Encode<MySql>>::encode_by_ref(&self.secs(), buf)
+    }
+}
+
+impl<'r> Decode<'r, MySql> for SessionDuration {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self::stored(<i64 as Decode<MySql>>::decode(value)?))
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+
+/// Circuit stats for the "Did you know" footer. Distances and records are
+/// for the Formula 1 race.
+#[derive(Debug)]
+pub struct CircuitFacts {
+    /// Lowercase fragments of weekend names held at this circuit.
+    pub names: &'static [&'static str],
+    pub circuit: &'static str,
+    pub laps: u16,
+    pub first_gp: u16,
+    pub lap_record: &'static str,
+}
+
+pub static CIRCUITS: &[CircuitFacts] = &[
+    CircuitFacts {
+        names: &["bahrain"],
+        circuit: "Bahrain International Circuit",
+        laps: 57,
+        first_gp: 2004,
+        lap_record: "1:31.447 by Pedro de la Rosa (2005)",
+    },
+    CircuitFacts {
+        names: &["saudi"],
+        circuit: "Jeddah Corniche Circuit",
+        laps: 50,
+        first_gp: 2021,
+        lap_record: "1:30.734 by Lewis Hamilton (2021)",
+    },
+    CircuitFacts {
+        names: &["australia"],
+        circuit: "Albert Park",
+        laps: 58,
+        first_gp: 1996,
+        lap_record: "1:19.813 by Charles Leclerc (2024)",
+    },
+    CircuitFacts {
+        names: &["japan"],
+        circuit: "Suzuka",
+        laps: 53,
+        first_gp: 1987,
+        lap_record: "1:30.983 by Lewis Hamilton (2019)",
+    },
+    CircuitFacts {
+        names: &["china", "chinese"],
+        circuit: "Shanghai International Circuit",
+        laps: 56,
+        first_gp: 2004,
+        lap_record: "1:32.238 by Michael Schumacher (2004)",
+    },
+    CircuitFacts {
+        names: &["miami"],
+        circuit: "Miami International Autodrome",
+        laps: 57,
+        first_gp: 2022,
+        lap_record: "1:29.708 by Max Verstappen (2023)",
+    },
+    CircuitFacts {
+        names: &["emilia", "imola"],
+        circuit: "Imola",
+        laps: 63,
+        first_gp: 1980,
+        lap_record: "1:15.484 by Lewis Hamilton (2020)",
+    },
+    CircuitFacts {
+        names: &["monaco"],
+        circuit: "Circuit de Monaco",
+        laps: 78,
+        first_gp: 1950,
+        lap_record: "1:12.909 by Lewis Hamilton (2021)",
+    },
+    CircuitFacts {
+        names: &["canad"],
+        circuit: "Circuit Gilles Villeneuve",
+        laps: 70,
+        first_gp: 1978,
+        lap_record: "1:13.078 by Valtteri Bottas (2019)",
+    },
+    CircuitFacts {
+        names: &["spain", "spanish", "barcelona"],
+        circuit: "Circuit de Barcelona-Catalunya",
+        laps: 66,
+        first_gp: 1991,
+        lap_record: "1:16.330 by Max Verstappen (2023)",
+    },
+    CircuitFacts {
+        names: &["austria"],
+        circuit: "Red Bull Ring",
+        laps: 71,
+        first_gp: 1970,
+        lap_record: "1:05.619 by Carlos Sainz (2020)",
+    },
+    CircuitFacts {
+        names: &["brit", "silverstone"],
+        circuit: "Silverstone",
+        laps: 52,
+        first_gp: 1950,
+        lap_record: "1:27.097 by Max Verstappen (2020)",
+    },
+    CircuitFacts {
+        names: &["hungar"],
+        circuit: "Hungaroring",
+        laps: 70,
+        first_gp: 1986,
+        lap_record: "1:16.627 by Lewis Hamilton (2020)",
+    },
+    CircuitFacts {
+        names: &["belgi", "spa-francorchamps"],
+        circuit: "Spa-Francorchamps",
+        laps: 44,
+        first_gp: 1950,
+        lap_record: "1:46.286 by Valtteri Bottas (2018)",
+    },
+    CircuitFacts {
+        names: &["dutch", "netherlands", "zandvoort"],
+        circuit: "Zandvoort",
+        laps: 72,
+        first_gp: 1952,
+        lap_record: "1:11.097 by Lewis Hamilton (2021)",
+    },
+    CircuitFacts {
+        names: &["ital", "monza"],
+        circuit: "Monza",
+        laps: 53,
+        first_gp: 1950,
+        lap_record: "1:21.046 by Rubens Barrichello (2004)",
+    },
+    CircuitFacts {
+        names: &["azerbaijan", "baku"],
+        circuit: "Baku City Circuit",
+        laps: 51,
+        first_gp: 2016,
+        lap_record: "1:43.009 by Charles Leclerc (2019)",
+    },
+    CircuitFacts {
+        names: &["singapore"],
+        circuit: "Marina Bay Street Circuit",
+        laps: 62,
+        first_gp: 2008,
+        lap_record: "1:34.486 by Daniel Ricciardo (2024)",
+    },
+    CircuitFacts {
+        names: &["united states", "austin"],
+        circuit: "Circuit of the Americas",
+        laps: 56,
+        first_gp: 2012,
+        lap_record: "1:36.169 by Charles Leclerc (2019)",
+    },
+    CircuitFacts {
+        names: &["mexic"],
+        circuit: "Autódromo Hermanos Rodríguez",
+        laps: 71,
+        first_gp: 1963,
+        lap_record: "1:17.774 by Valtteri Bottas (2021)",
+    },
+    CircuitFacts {
+        names: &["são paulo", "sao paulo", "brazil", "interlagos"],
+        circuit: "Interlagos",
+        laps: 71,
+        first_gp: 1973,
+        lap_record: "1:10.540 by Valtteri Bottas (2018)",
+    },
+    CircuitFacts {
+        names: &["las vegas"],
+        circuit: "Las Vegas Strip Circuit",
+        laps: 50,
+        first_gp: 2023,
+        lap_record: "1:35.490 by Oscar Piastri (2023)",
+    },
+    CircuitFacts {
+        names: &["qatar", "lusail"],
+        circuit: "Lusail International Circuit",
+        laps: 57,
+        first_gp: 2021,
+        lap_record: "1:22.384 by Lando Norris (2024)",
+    },
+    CircuitFacts {
+        names: &["abu dhabi", "yas marina"],
+        circuit: "Yas Marina Circuit",
+        laps: 58,
+        first_gp: 2009,
+        lap_record: "1:26.103 by Max Verstappen (2021)",
+    },
+];
+
+pub fn circuit_facts(weekend_name: &str) -> Option<&'static CircuitFacts> {
+    let name = weekend_name.to_lowercase();
+    CIRCUITS.iter().find(|f| f.names.iter().any(|n| name.contains(n)))
+}
+
+impl CircuitFacts {
+    /// One fact, picked by `seed` and changing once per day so repeated
+    /// renders within a day come out the same.
+    pub fn fact(
+        &self,
+        seed: u64,
+        now: DateTime<Utc>,
+    ) -> String {
+        let day = now.timestamp().div_euclid(60 * 60 * 24) as u64;
+        match seed.wrapping_add(day) % 3 {
+            0 => format!(
+                "The lap record at {} is {}.",
+                self.circuit, self.lap_record
+            ),
+            1 => format!(
+                "The Grand Prix at {} runs over {} laps.",
+                self.circuit, self.laps
+            ),
+            _ => format!(
+                "{} first hosted a Grand Prix in {}.",
+                self.circuit, self.first_gp
+            ),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Hashes that are stored and compared across restarts and releases.
+//!
+//! [DefaultHasher](std::hash::DefaultHasher) is only guaranteed to be the
+//! same within one build, a new compiler may change it and make every stored
+//! hash look outdated at once.
+
+use std::hash::{Hash, Hasher};
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0100_0000_01b3;
+
+/// 64-bit FNV-1a, fixed by its specification. Integers are hashed little
+/// endian and `usize` values as `u64`, so every build agrees.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    pub const fn new() -> Self {
+        Self(OFFSET_BASIS)
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(
+        &mut self,
+        bytes: &[u8],
+    ) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(PRIME);
+        }
+    }
+
+    fn write_u16(
+        &mut self,
+        value: u16,
+    ) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(
+        &mut self,
+        value: u32,
+    ) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(
+        &mut self,
+        value: u64,
+    ) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(
+        &mut self,
+        value: u128,
+    ) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(
+        &mut self,
+        value: usize,
+    ) {
+        self.write_u64(value as u64);
+    }
+}
+
+/// The [StableHasher] hash of `value`.
+pub fn stable_hash(value: &impl Hash) -> u64 {
+    let mut hasher = StableHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
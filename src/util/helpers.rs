@@ -1,49 +1,56 @@
 use std::{
-    fs::File,
-    hash::{DefaultHasher, Hash, Hasher},
-    io::{self, Write},
-    process::exit,
-    time::Duration,
+    hash::{Hash, Hasher},
+    io,
+    path::Path,
 };
 
-use chrono::Utc;
+use chrono::{Datelike, Utc};
 use f1_bot_types::{
     Message, MessageKind, Series, Session, SessionStatus, Weekend,
     WeekendStatus,
 };
 use serenity::all::{
-    CacheHttp, ChannelId, CreateAttachment, CreateMessage, EditMessage,
-    MessageId, StatusCode,
+    CacheHttp, ChannelId, ChannelType, CreateActionRow, CreateAllowedMentions,
+    CreateAttachment, CreateButton, CreateMessage, CreateStageInstance,
+    CreateThread, EditMessage, ExecuteWebhook, Http, MessageId, StatusCode,
+    Webhook,
 };
 use sqlx::MySqlConnection;
 use tracing::{error, info};
 
-use crate::{config::Config, error::Error};
+use crate::{
+    config::{
+        ArchiveConfig, Config, DisplayConfig, LightsOutConfig, MilestoneConfig,
+        ReminderConfig, StageConfig, WebhookConfig, CONFIG_PATH,
+    },
+    error::Error,
+    events::{BotEvent, EVENTS},
+};
 
 use super::*;
 
-pub fn handle_config_error(why: std::io::Error) -> ! {
+/// Reports a config file that couldn't be read, writing the default config
+/// if there was none. The bot can't continue either way.
+pub async fn handle_config_error(why: std::io::Error) {
     if let io::ErrorKind::NotFound = why.kind() {
         info!("Generated default config file, please update settings.");
-        if let Err(config_why) = generate_default_config() {
+        if let Err(config_why) = generate_default_config().await {
             error!("Error generating config: `{config_why}`")
         }
-        exit(0x0100)
     } else {
         info!("Error reading config file: {why}");
-        exit(0x0100)
     }
 }
 
-fn generate_default_config() -> Result<(), Error> {
+async fn generate_default_config() -> Result<(), Error> {
     let config = Config::default();
     let str_to_write = toml::to_string_pretty(&config)?;
-    let mut config_file = File::create("./config/config.toml")?;
-    config_file.write_all(str_to_write.as_bytes())?;
+    tokio::fs::write(CONFIG_PATH, str_to_write).await?;
     Ok(())
 }
 
 /// Fetches and Deletes all expired messages.
+#[tracing::instrument(skip_all)]
 pub async fn check_expired_messages(
     conn: &mut MySqlConnection,
     http: impl CacheHttp,
@@ -51,6 +58,27 @@ pub async fn check_expired_messages(
     let expired_messages = expired_messages(conn).await?;
 
     for message in expired_messages.into_iter() {
+        if matches!(message.kind, MessageKind::Notification) {
+            match notified_session(conn, &message).await {
+                Ok(Some((weekend, session))) => {
+                    if let Err(why) = record_engagement(
+                        conn, &http, &message, &weekend, &session,
+                    )
+                    .await
+                    {
+                        error!("{why}");
+                    }
+                    EVENTS.publish(BotEvent::SessionEnded {
+                        series: weekend.series.to_string(),
+                        weekend: weekend.name,
+                        session: session.title,
+                        end: Utc::now(),
+                    });
+                },
+                Ok(None) => {},
+                Err(why) => error!("{why}"),
+            }
+        }
         let delete_result = ChannelId::new(message.channel.parse()?)
             .delete_message(http.http(), message.message.parse::<u64>()?)
             .await;
@@ -74,6 +102,53 @@ pub async fn check_expired_messages(
     Ok(())
 }
 
+/// The weekend and session a notification message was sent for.
+pub async fn notified_session(
+    conn: &mut MySqlConnection,
+    message: &Message,
+) -> Result<Option<(Weekend, Session)>, sqlx::Error> {
+    let Some(entry) =
+        fetch_notification_log_by_message(conn, &message.message).await?
+    else {
+        return Ok(None);
+    };
+    let Some(session) = fetch_session(conn, entry.session).await? else {
+        return Ok(None);
+    };
+    let weekend = fetch_weekend(conn, session.weekend as u64).await?;
+    Ok(weekend.map(|weekend| (weekend, session)))
+}
+
+/// Stores the reactions and thread activity of a notification right before
+/// it gets deleted.
+pub async fn record_engagement(
+    conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    message: &Message,
+    weekend: &Weekend,
+    session: &Session,
+) -> Result<(), crate::error::Error> {
+    let discord_message = ChannelId::new(message.channel.parse()?)
+        .message(http.http(), message.message.parse::<u64>()?)
+        .await?;
+    let reactions = discord_message.reactions.iter().map(|f| f.count).sum();
+    let thread_messages = discord_message
+        .thread
+        .as_ref()
+        .and_then(|f| f.message_count)
+        .unwrap_or_default() as u64;
+
+    insert_session_engagement(
+        conn,
+        weekend,
+        session,
+        reactions,
+        thread_messages,
+    )
+    .await?;
+    Ok(())
+}
+
 pub async fn create_new_calendar_message(
     conn: &mut MySqlConnection,
     http: impl CacheHttp,
@@ -87,7 +162,7 @@ pub async fn create_new_calendar_message(
         )
         .await?;
 
-    sqlx::query!(
+    let id = sqlx::query!(
         "INSERT INTO messages 
 (channel, message, kind, series) 
 VALUES (?, ?, ?, ?)",
@@ -96,8 +171,10 @@ VALUES (?, ?, ?, ?)",
         MessageKind::Calendar.i8(),
         series.i8()
     )
-    .execute(conn)
-    .await?;
+    .execute(&mut *conn)
+    .await?
+    .last_insert_id();
+    tag_message(conn, id).await?;
 
     Ok(())
 }
@@ -128,43 +205,181 @@ pub async fn delete_latest_calendar_message(
     }
 
     delete_message(db_conn, last.id).await?;
+    rendered::forget(last.id);
 
     Ok(())
 }
 
+/// Removes every calendar message owned by `series`.
+pub async fn clear_calendar(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    series: Series,
+) -> Result<(), crate::error::Error> {
+    let messages = fetch_calendar_messages(db_conn, series).await?;
+    let mut pacer = Pacer::deletions();
+    for _ in 0..messages.len() {
+        delete_latest_calendar_message(db_conn, &http, series).await?;
+        pacer.wait().await;
+    }
+    Ok(())
+}
+
 pub async fn create_calendar(
     conn: &mut MySqlConnection,
     http: impl CacheHttp,
     series: Series,
     channel: u64,
+    admin_channel: u64,
+    window: SeasonWindow,
 ) -> Result<(), Error> {
+    let weekends = fetch_full_weekends_for_series(conn, series, window).await?;
+    create_calendar_for(conn, http, series, channel, admin_channel, weekends)
+        .await
+}
+
+/// Reservations shorter than this don't report progress.
+const CALENDAR_PROGRESS_MIN: usize = 5;
+
+/// Posts placeholder calendar messages until `series` owns `total` of them.
+///
+/// Every placeholder is stored right after posting, so an interrupted run
+/// continues from the stored count the next time. Larger runs report their
+/// progress to `admin_channel`.
+pub async fn reserve_calendar_messages(
+    conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    series: Series,
+    channel: u64,
+    admin_channel: u64,
+    existing: usize,
+    total: usize,
+) -> Result<(), Error> {
+    let progress_str =
+        |done: usize| format!("Reserving {series} calendar: {done}/{total}");
+    let mut progress = None;
+    let mut pacer = Pacer::messages();
+    if admin_channel != 0 && total - existing > CALENDAR_PROGRESS_MIN {
+        match ChannelId::new(admin_channel)
+            .send_message(
+                http.http(),
+                CreateMessage::new().content(progress_str(existing)),
+            )
+            .await
+        {
+            Ok(message) => progress = Some(message),
+            Err(why) => error!("{why:#?}"),
+        }
+    }
+
+    for _ in existing..total {
+        create_new_calendar_message(conn, &http, series, channel).await?;
+        if !pacer.wait().await {
+            continue;
+        }
+        let Some(progress) = progress.as_mut() else {
+            continue;
+        };
+        let content = progress_str(existing + pacer.done());
+        if let Err(why) =
+            progress.edit(&http, EditMessage::new().content(content)).await
+        {
+            error!("{why:#?}");
+        }
+    }
+
+    if let Some(mut progress) = progress {
+        if let Err(why) = progress
+            .edit(
+                &http,
+                EditMessage::new().content(format!(
+                    "Reserved {total} {series} calendar messages."
+                )),
+            )
+            .await
+        {
+            error!("{why:#?}");
+        }
+    }
+    Ok(())
+}
+
+/// One calendar message: a weekend, or the separator in front of the first
+/// weekend of a season.
+#[derive(Debug, Hash)]
+pub enum CalendarEntry {
+    Season(i32),
+    Weekend(FullWeekend),
+}
+
+/// The calendar messages showing `weekends`. Calendars spanning a year
+/// boundary, like with pre-season testing, get a separator in front of every
+/// season, single-season calendars none.
+pub fn calendar_entries(weekends: Vec<FullWeekend>) -> Vec<CalendarEntry> {
+    let year = |f: &FullWeekend| f.weekend.start_date.year();
+    let mixed = weekends
+        .first()
+        .is_some_and(|first| weekends.iter().any(|f| year(f) != year(first)));
+    let mut entries = Vec::with_capacity(weekends.len());
+    let mut last_year = None;
+    for weekend in weekends {
+        if mixed && last_year != Some(year(&weekend)) {
+            last_year = Some(year(&weekend));
+            entries.push(CalendarEntry::Season(year(&weekend)));
+        }
+        entries.push(CalendarEntry::Weekend(weekend));
+    }
+    entries
+}
+
+/// Makes sure `series` owns exactly one calendar message per entry of
+/// `weekends`, which may also contain weekends of other series sharing the
+/// channel.
+#[tracing::instrument(skip_all)]
+pub async fn create_calendar_for(
+    conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    series: Series,
+    channel: u64,
+    admin_channel: u64,
+    weekends: Vec<FullWeekend>,
+) -> Result<(), Error> {
+    let entries = calendar_entries(weekends);
     let messages = fetch_calendar_messages(conn, series).await?;
-    let weekends = fetch_full_weekends_for_series(conn, series).await?;
-    match messages.len().cmp(&weekends.len()) {
+    match messages.len().cmp(&entries.len()) {
         std::cmp::Ordering::Less => {
-            let diff = weekends.len() - messages.len();
-            for _ in 0..diff {
-                create_new_calendar_message(conn, &http, series, channel)
-                    .await?;
-                tokio::time::sleep(Duration::from_millis(300)).await;
-            }
-            return Ok(());
+            return reserve_calendar_messages(
+                conn,
+                &http,
+                series,
+                channel,
+                admin_channel,
+                messages.len(),
+                entries.len(),
+            )
+            .await;
         },
         std::cmp::Ordering::Greater => {
-            let diff = messages.len() - weekends.len();
+            let diff = messages.len() - entries.len();
+            let mut pacer = Pacer::deletions();
             for _ in 0..diff {
                 delete_latest_calendar_message(conn, &http, series).await?;
+                pacer.wait().await;
             }
             return Ok(());
         },
         std::cmp::Ordering::Equal => {},
     }
 
-    for (weekend, message) in weekends.into_iter().zip(messages.into_iter()) {
+    for (entry, message) in entries.into_iter().zip(messages.into_iter()) {
         use std::hash::Hash;
+        let CalendarEntry::Weekend(weekend) = entry else {
+            // separators are only ever edited
+            continue;
+        };
         match message.hash {
             None => {
-                let mut hasher = DefaultHasher::new();
+                let mut hasher = StableHasher::new();
                 weekend.hash(&mut hasher);
                 let new_hash = hasher.finish();
                 update_calendar_message(
@@ -177,7 +392,7 @@ pub async fn create_calendar(
                 update_message_hash(conn, message.id, new_hash).await?;
             },
             Some(hash) => {
-                let mut hasher = std::hash::DefaultHasher::new();
+                let mut hasher = StableHasher::new();
                 weekend.hash(&mut hasher);
                 let new_hash = hasher.finish();
                 if hash != new_hash.to_string() {
@@ -206,25 +421,96 @@ pub async fn update_calendar_message(
     Ok(())
 }
 
+/// Progress of the season over `weekends`, one line per series for
+/// calendars shared by several series.
+pub fn season_progress(
+    weekends: &[FullWeekend],
+    show_series: bool,
+) -> String {
+    let mut lines = vec![];
+    for series in names::ALL_SERIES {
+        let total =
+            weekends.iter().filter(|f| f.weekend.series == series).count();
+        if total == 0 {
+            continue;
+        }
+        let done = weekends
+            .iter()
+            .filter(|f| {
+                f.weekend.series == series
+                    && f.weekend.status == WeekendStatus::Done
+            })
+            .count();
+        let line = match total - done {
+            0 => format!("Season complete, all {total} rounds done"),
+            _ if done == 0 => format!("{total} rounds, none complete yet"),
+            1 => {
+                format!("Round {done} of {total} complete — final race next")
+            },
+            left => format!(
+                "Round {done} of {total} complete — {left} races remaining"
+            ),
+        };
+        lines.push(match show_series {
+            true => format!("**{}**: {line}", registry::name(series)),
+            false => line,
+        });
+    }
+    lines.join("\n")
+}
+
 pub async fn edit_calendar(
     db_conn: &mut MySqlConnection,
     http: impl CacheHttp,
     series: Series,
+    display: &DisplayConfig,
+    verbosity: CalendarVerbosity,
+    window: SeasonWindow,
+) -> Result<(), crate::error::Error> {
+    let weekends =
+        fetch_full_weekends_for_series(db_conn, series, window).await?;
+    edit_calendar_for(
+        db_conn, http, series, weekends, false, display, verbosity,
+    )
+    .await
+}
+
+/// Edits the calendar messages owned by `series` to show `weekends` at
+/// `verbosity`, with `show_series` the series is named next to each weekend.
+#[tracing::instrument(skip_all)]
+pub async fn edit_calendar_for(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    series: Series,
+    weekends: Vec<FullWeekend>,
+    show_series: bool,
+    display: &DisplayConfig,
+    verbosity: CalendarVerbosity,
 ) -> Result<(), crate::error::Error> {
+    // headed by the first message, which changes with every finished weekend
+    let progress = season_progress(&weekends, show_series);
+    let entries = calendar_entries(weekends);
     let msgs = fetch_calendar_messages(db_conn, series).await?;
-    let weekends = fetch_full_weekends_for_series(db_conn, series).await?;
-    if msgs.len() != weekends.len() {
+    if msgs.len() != entries.len() {
         return Err(crate::error::Error::NotSameLen);
     }
 
-    for (msg, weekend) in msgs.into_iter().zip(weekends.into_iter()) {
-        let mut hasher = std::hash::DefaultHasher::new();
-        weekend.hash(&mut hasher);
+    for (index, (msg, entry)) in
+        msgs.into_iter().zip(entries.into_iter()).enumerate()
+    {
+        let mut hasher = StableHasher::new();
+        entry.hash(&mut hasher);
+        display.plain_text.hash(&mut hasher);
+        display.weekend_names.hash(&mut hasher);
+        verbosity.hash(&mut hasher);
+        if index == 0 {
+            progress.hash(&mut hasher);
+        }
         let hash = hasher.finish();
         if msg
             .hash
             .as_ref()
-            .map(|f| f.parse::<u64>().unwrap())
+            .and_then(|f| f.parse::<u64>().ok())
             .is_some_and(|f| f == hash)
         {
             continue;
@@ -232,32 +518,57 @@ pub async fn edit_calendar(
 
         let channel_u64: u64 = msg.channel.parse()?;
         let message_u64: u64 = msg.message.parse()?;
-        let mut sessions_str = String::new();
-        for session in weekend.sessions.iter() {
-            sessions_str += &format!(
-                "\n> `{:>12}` <t:{}:f> (<t:{}:R>)",
-                session.title,
-                session.start_date.timestamp(),
-                session.start_date.timestamp()
-            );
+        let mut content = match &entry {
+            CalendarEntry::Season(year) => render::season_separator(*year),
+            CalendarEntry::Weekend(weekend) => {
+                render::calendar_text(weekend, show_series, display, verbosity)
+            },
+        };
+        if index == 0 && !progress.is_empty() {
+            content = format!("{progress}\n\n{content}");
         }
-        match ChannelId::new(channel_u64)
-            .edit_message(
-                &http,
-                message_u64,
-                EditMessage::new().content(format!(
-                    "{} **{}**{}",
-                    weekend.weekend.icon, weekend.weekend.name, sessions_str
-                )),
-            )
-            .await
+        let edit = match &entry {
+            CalendarEntry::Weekend(weekend) if !display.plain_text => {
+                let header = match index {
+                    0 => progress.as_str(),
+                    _ => "",
+                };
+                content += "\0embed";
+                EditMessage::new().content(header).embed(
+                    render::calendar_embed(
+                        weekend,
+                        show_series,
+                        display,
+                        verbosity,
+                    ),
+                )
+            },
+            _ => EditMessage::new().content(&content).embeds(vec![]),
+        };
+
+        // The hash changes on every reorder or restart, the content only when
+        // there is something new to show.
+        if !rendered::is_current(msg.id, &content)
+            && fetch_message_content(db_conn, msg.id).await?.as_ref()
+                != Some(&content)
         {
-            Ok(_) => {},
-            Err(why) => {
+            match ChannelId::new(channel_u64)
+                .edit_message(&http, message_u64, edit)
+                .await
+            {
+                Ok(_) => {},
+                Err(why) => {
+                    error!("{why:#?}");
+                    continue;
+                },
+            }
+            if let Err(why) =
+                set_message_content(db_conn, msg.id, &content, hash).await
+            {
                 error!("{why:#?}");
-                continue;
-            },
+            }
         }
+        rendered::remember(msg.id, &content);
 
         if let Err(why) = set_message_hash(db_conn, &msg, hash).await {
             error!("{why:#?}");
@@ -313,7 +624,7 @@ pub async fn create_new_notifications_msg_db(
     channel: u64,
     message: u64,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    let id = sqlx::query!(
         "INSERT INTO messages 
 (channel, message, kind, posted, series, expiry) 
 VALUES(?, ?, ?, ?, ?, ?)",
@@ -322,11 +633,12 @@ VALUES(?, ?, ?, ?, ?, ?)",
         MessageKind::Notification.i8(),
         Utc::now(),
         series.i8(),
-        Utc::now() + Duration::from_secs(session.duration as u64)
+        session_end(Utc::now(), SessionDuration::of(session))
     )
-    .execute(db_conn)
-    .await
-    .map(|_f| ())
+    .execute(&mut *db_conn)
+    .await?
+    .last_insert_id();
+    tag_message(db_conn, id).await
 }
 
 pub async fn send_notification(
@@ -334,25 +646,338 @@ pub async fn send_notification(
     weekend: &Weekend,
     session: &Session,
     channel: u64,
-    cat: &[u8],
+    cat: &Path,
     role: u64,
 ) -> Result<MessageId, crate::error::Error> {
-    let new_msg = ChannelId::new(channel)
-        .send_message(
-            http,
-            CreateMessage::new()
-                .content(format!(
-                    "<@&{}>\n{} {} {} is starting: <t:{}:R>",
-                    role,
-                    weekend.icon,
-                    weekend.name,
+    send_merged_notification(
+        http,
+        &[(weekend, session, role)],
+        channel,
+        cat,
+        &[],
+    )
+    .await
+}
+
+/// Announces several sessions starting at about the same time in a single
+/// message, mentioning every role once. Role 0 isn't mentioned.
+///
+/// Goes through the webhook configured for the series of the first entry,
+/// if any, which has to post into `channel`.
+#[tracing::instrument(skip_all)]
+pub async fn send_merged_notification(
+    http: impl CacheHttp,
+    entries: &[(&Weekend, &Session, u64)],
+    channel: u64,
+    cat: &Path,
+    webhooks: &[WebhookConfig],
+) -> Result<MessageId, crate::error::Error> {
+    let template = &templates::get().notification;
+    // mentioned in each line instead when the template has a `{role}`
+    let mut roles: Vec<u64> = Vec::with_capacity(entries.len());
+    if !templates::uses(template, "role") {
+        for (_, _, role) in entries.iter() {
+            if *role != 0 && !roles.contains(role) {
+                roles.push(*role);
+            }
+        }
+    }
+    let mut content =
+        roles.iter().map(|f| format!("<@&{f}>")).collect::<Vec<_>>().join(" ");
+    for (weekend, session, role) in entries.iter() {
+        let timestamp = session.start_date.timestamp();
+        let mention = match role {
+            0 => String::new(),
+            role => format!("<@&{role}>"),
+        };
+        content += "\n";
+        content += &templates::fill(
+            template,
+            &[
+                ("role", mention.as_str()),
+                ("series", weekend.series.to_string().as_str()),
+                ("weekend.icon", weekend.icon.as_str()),
+                ("weekend.name", weekend.name.as_str()),
+                ("session.name", session.title.as_str()),
+                ("session.start", format!("<t:{timestamp}:f>").as_str()),
+                ("session.relative", format!("<t:{timestamp}:R>").as_str()),
+            ],
+        );
+    }
+    // read from disk per message so the video isn't held in memory, the
+    // notification goes out without it rather than not at all
+    let cat = CreateAttachment::path(cat)
+        .await
+        .inspect_err(|why| error!("Couldn't attach {}: {why}", cat.display()))
+        .ok();
+    let webhook = entries.first().and_then(|(weekend, _, _)| {
+        let key = names::series_key(weekend.series);
+        webhooks.iter().find(|f| f.series == key)
+    });
+    if let Some(config) = webhook {
+        return send_webhook_notification(
+            http.http(),
+            config,
+            content.trim_start(),
+            channel,
+            cat,
+        )
+        .await;
+    }
+    // without a mention the first line is empty
+    let mut message = CreateMessage::new().content(content.trim_start());
+    if let Some(cat) = cat {
+        message = message.add_file(cat);
+    }
+    let new_msg = ChannelId::new(channel).send_message(http, message).await?;
+    Ok(new_msg.id)
+}
+
+async fn send_webhook_notification(
+    http: &Http,
+    config: &WebhookConfig,
+    content: &str,
+    channel: u64,
+    cat: Option<CreateAttachment>,
+) -> Result<MessageId, crate::error::Error> {
+    let webhook = Webhook::from_url(http, &config.url).await?;
+    // the message is tracked and cleaned up in `channel`
+    if webhook.channel_id.is_none_or(|f| f.get() != channel) {
+        return Err(Error::NNF(
+            format!("The {} webhook posts to another channel", config.series)
+                .into(),
+        ));
+    }
+    let mut builder = ExecuteWebhook::new().content(content);
+    if let Some(cat) = cat {
+        builder = builder.add_file(cat);
+    }
+    if !config.username.is_empty() {
+        builder = builder.username(&config.username);
+    }
+    if !config.avatar_url.is_empty() {
+        builder = builder.avatar_url(&config.avatar_url);
+    }
+    let Some(message) = webhook.execute(http, true, builder).await? else {
+        return Err(Error::NNF("The webhook returned no message".into()));
+    };
+    Ok(message.id)
+}
+
+fn fmt_minutes(minutes: u64) -> String {
+    let plural = |n: u64, unit: &str| match n {
+        1 => format!("1 {unit}"),
+        n => format!("{n} {unit}s"),
+    };
+    match (minutes / 60, minutes % 60) {
+        (0, m) => plural(m, "minute"),
+        (h, 0) => plural(h, "hour"),
+        (h, m) => format!("{} {}", plural(h, "hour"), plural(m, "minute")),
+    }
+}
+
+/// Posts the mentionless race countdown messages that are due, each one is
+/// removed again once the race is over.
+pub async fn send_milestones(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &MilestoneConfig,
+    weekend: &FullWeekend,
+) -> Result<(), crate::error::Error> {
+    if config.channel == 0 {
+        return Ok(());
+    }
+    for (index, offset) in weekend.due_milestones(&config.offsets) {
+        let session = &weekend.sessions[index];
+        if is_milestone_sent(db_conn, session.id, offset).await? {
+            continue;
+        }
+        let message = ChannelId::new(config.channel)
+            .send_message(
+                http.http(),
+                CreateMessage::new().content(format!(
+                    "{} {} {} starts in {} (<t:{}:R>)",
+                    weekend.weekend.icon,
+                    weekend.weekend.name,
                     session.title,
+                    fmt_minutes(offset),
                     session.start_date.timestamp()
-                ))
-                .add_file(CreateAttachment::bytes(cat, "cats.mp4")),
+                )),
+            )
+            .await?;
+        mark_milestone_sent(db_conn, session.id, offset).await?;
+        insert_expiring_message(
+            db_conn,
+            config.channel,
+            message.id.get(),
+            MessageKind::Custom,
+            weekend.weekend.series,
+            session_end(session.start_date, SessionDuration::of(session)),
         )
         .await?;
-    Ok(new_msg.id)
+    }
+    Ok(())
+}
+
+/// Pings `role` at every configured [ReminderConfig] offset before the
+/// sessions of `weekend`, each reminder is removed once its session is over.
+/// Reminders `may_ping` turns down go out without the mention.
+pub async fn send_reminders(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &[ReminderConfig],
+    weekend: &FullWeekend,
+    channel: u64,
+    role: u64,
+    mut may_ping: impl FnMut() -> bool,
+) -> Result<(), crate::error::Error> {
+    let key = names::series_key(weekend.weekend.series);
+    let Some(reminders) = config.iter().find(|f| f.series == key) else {
+        return Ok(());
+    };
+    for (index, offset) in weekend.due_reminders(&reminders.offsets) {
+        let session = &weekend.sessions[index];
+        if is_reminder_sent(db_conn, session.id, offset).await? {
+            continue;
+        }
+        let mention = match role {
+            0 => String::new(),
+            _ if !may_ping() => String::new(),
+            role => format!("<@&{role}> "),
+        };
+        let message = ChannelId::new(channel)
+            .send_message(
+                http.http(),
+                CreateMessage::new().content(format!(
+                    "{mention}{} {} {} starts in {} (<t:{}:R>)",
+                    weekend.weekend.icon,
+                    weekend.weekend.name,
+                    session.title,
+                    fmt_minutes(offset),
+                    session.start_date.timestamp()
+                )),
+            )
+            .await?;
+        mark_reminder_sent(db_conn, session.id, offset).await?;
+        insert_expiring_message(
+            db_conn,
+            channel,
+            message.id.get(),
+            MessageKind::Custom,
+            weekend.weekend.series,
+            session_end(session.start_date, SessionDuration::of(session)),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Pings `role` again once the lights go out in a race of `weekend`, for
+/// series with a [LightsOutConfig]. Without a role, or when `may_ping`
+/// turns it down, the message goes out without the mention.
+pub async fn send_lights_out(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &[LightsOutConfig],
+    weekend: &FullWeekend,
+    channel: u64,
+    role: u64,
+    may_ping: impl FnOnce() -> bool,
+) -> Result<(), crate::error::Error> {
+    let key = names::series_key(weekend.weekend.series);
+    let Some(lights_out) = config.iter().find(|f| f.series == key) else {
+        return Ok(());
+    };
+    let Some(index) = weekend.due_lights_out(lights_out.delay) else {
+        return Ok(());
+    };
+    let session = &weekend.sessions[index];
+    if is_lights_out_sent(db_conn, session.id).await? {
+        return Ok(());
+    }
+    let mention = match role {
+        0 => String::new(),
+        _ if !may_ping() => String::new(),
+        role => format!("<@&{role}> "),
+    };
+    let message = ChannelId::new(channel)
+        .send_message(
+            http.http(),
+            CreateMessage::new().content(format!(
+                "{mention}Lights out! {} {} {}",
+                weekend.weekend.icon, weekend.weekend.name, session.title,
+            )),
+        )
+        .await?;
+    mark_lights_out_sent(db_conn, session.id).await?;
+    insert_expiring_message(
+        db_conn,
+        channel,
+        message.id.get(),
+        MessageKind::Custom,
+        weekend.weekend.series,
+        session_end(session.start_date, SessionDuration::of(session)),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Opens the configured stage channel while a race is live and closes it
+/// again once the race is over.
+///
+/// Open stages are tracked in the database, a restart mid-race neither opens
+/// a second one nor leaves the old one running.
+pub async fn update_stage(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &StageConfig,
+) -> Result<(), crate::error::Error> {
+    if config.channel == 0 {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let live = fetch_live_sessions(db_conn, now).await?;
+    let open = fetch_stage_instances(db_conn).await?;
+
+    for stage in open.iter() {
+        if live.iter().any(|f| f.id == stage.session) {
+            continue;
+        }
+        let closed = ChannelId::new(stage.channel)
+            .delete_stage_instance(http.http(), Some("Session is over"))
+            .await;
+        if let Err(serenity::Error::Http(why)) = &closed {
+            if why.status_code().is_none_or(|f| f != StatusCode::NOT_FOUND) {
+                error!("{why}");
+                continue;
+            }
+        } else {
+            closed?;
+        }
+        delete_stage_instance(db_conn, stage.session).await?;
+        info!("Closed stage for session {}", stage.session);
+    }
+
+    if open.iter().any(|f| live.iter().any(|l| l.id == f.session)) {
+        return Ok(());
+    }
+    let Some(race) = live.iter().find(|f| is_race(f)) else {
+        return Ok(());
+    };
+    let Some(weekend) = fetch_weekend(db_conn, race.weekend as u64).await?
+    else {
+        return Ok(());
+    };
+    let topic = config
+        .topic
+        .replace("{weekend}", &weekend.name)
+        .replace("{session}", &race.title);
+    ChannelId::new(config.channel)
+        .create_stage_instance(&http, CreateStageInstance::new(topic))
+        .await?;
+    insert_stage_instance(db_conn, race.id, config.channel).await?;
+    info!("Opened stage for {} {}", weekend.name, race.title);
+    Ok(())
 }
 
 pub async fn check_expired_weekend(
@@ -385,16 +1010,141 @@ pub async fn check_expired_weekend(
     }
 }
 
+/// Copies the final weekend message of `weekend` into the season thread of
+/// its series in the archive channel, creating the thread if needed.
+pub async fn archive_weekend(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &ArchiveConfig,
+    weekend: &FullWeekend,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
+) -> Result<(), crate::error::Error> {
+    if config.channel == 0 {
+        return Ok(());
+    }
+    let series = weekend.weekend.series;
+    let year = weekend.weekend.start_date.year();
+    let thread = match fetch_archive_thread(db_conn, series, year).await? {
+        Some(thread) => thread,
+        None => {
+            let thread = ChannelId::new(config.channel)
+                .create_thread(
+                    http.http(),
+                    CreateThread::new(format!("{series} {year}"))
+                        .kind(ChannelType::PublicThread),
+                )
+                .await?
+                .id
+                .get();
+            insert_archive_thread(db_conn, series, year, thread).await?;
+            thread
+        },
+    };
+    let mut summary = weekend_message_content(weekend, display, settings);
+    let cancelled = weekend
+        .sessions
+        .iter()
+        .filter(|f| f.status == SessionStatus::Cancelled)
+        .count();
+    if cancelled > 0 {
+        summary += &format!("\n-# {cancelled} cancelled");
+    }
+    ChannelId::new(thread)
+        .send_message(
+            http.http(),
+            CreateMessage::new()
+                .content(summary)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Link buttons for a weekends [WeekendLinks](WeekendLink), five per row.
+pub fn link_rows(weekend: &FullWeekend) -> Vec<CreateActionRow> {
+    weekend
+        .links
+        .chunks(5)
+        .take(5)
+        .map(|f| {
+            CreateActionRow::Buttons(
+                f.iter()
+                    .map(|link| {
+                        CreateButton::new_link(&link.url).label(&link.label)
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// The full text of a weekend message, footers included.
+pub fn weekend_message_content(
+    weekend: &FullWeekend,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
+) -> String {
+    render::weekend_text(weekend, true, display, settings.verbose)
+        + &weekend.footers_str(settings)
+}
+
+/// Hash of everything [weekend_message_content] renders, taken from the
+/// structured fields so unchanged weekends are skipped without building the
+/// message.
+pub fn weekend_message_hash(
+    weekend: &FullWeekend,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
+) -> u64 {
+    let now = Utc::now();
+    let mut hasher = StableHasher::new();
+    weekend.hash(&mut hasher);
+    display.hash(&mut hasher);
+    settings.hash(&mut hasher);
+    for session in weekend.sessions.iter() {
+        is_session_over(session, now).hash(&mut hasher);
+        is_low_priority(&session.kind).hash(&mut hasher);
+    }
+    if display.circuit_facts {
+        // facts rotate daily
+        now.timestamp().div_euclid(60 * 60 * 24).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[tracing::instrument(skip_all)]
 pub async fn post_weekend_message(
     http: impl CacheHttp,
     weekend: &FullWeekend,
     channel: u64,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
 ) -> Result<MessageId, serenity::Error> {
+    let mut message = match display.plain_text {
+        true => CreateMessage::new()
+            .content(weekend_message_content(weekend, display, settings)),
+        false => CreateMessage::new()
+            .embed(render::weekend_embed(weekend, display, settings)),
+    };
+    // embeds show URLs themselves, files and plain text need an attachment;
+    // a broken image shouldn't keep the weekend from being posted
+    let attachment = match weekend.track_image() {
+        Some(TrackImage::File(path)) => {
+            Some(CreateAttachment::path(path).await)
+        },
+        Some(TrackImage::Url(url)) if display.plain_text => {
+            Some(CreateAttachment::url(http.http(), url).await)
+        },
+        _ => None,
+    };
+    match attachment {
+        Some(Ok(attachment)) => message = message.add_file(attachment),
+        Some(Err(why)) => error!("Couldn't attach the track image: {why}"),
+        None => {},
+    }
     ChannelId::new(channel)
-        .send_message(
-            http,
-            CreateMessage::new().content(weekend.weekend_msg_str(true)),
-        )
+        .send_message(http, message.components(link_rows(weekend)))
         .await
         .map(|f| f.id)
 }
@@ -404,11 +1154,87 @@ pub async fn insert_weekend_message(
     channel: u64,
     message: u64,
     weekend: &FullWeekend,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
 ) -> Result<(), sqlx::Error> {
-    let mut hasher = DefaultHasher::new();
-    weekend.hash(&mut hasher);
-    let hash = hasher.finish();
-    sqlx::query!("INSERT INTO messages (channel, message, hash, kind, series) VALUES (?, ?, ?, ?, ?)", channel, message, hash, MessageKind::Weekend.i8(), weekend.weekend.series.i8()).execute(db_conn).await.map(|_f| ())
+    let hash = weekend_message_hash(weekend, display, settings);
+    let id = sqlx::query!(
+        "INSERT INTO messages (channel, message, hash, kind, series) VALUES (?, ?, ?, ?, ?)",
+        channel,
+        message,
+        hash,
+        MessageKind::Weekend.i8(),
+        weekend.weekend.series.i8()
+    )
+    .execute(&mut *db_conn)
+    .await?
+    .last_insert_id();
+    tag_message(&mut *db_conn, id).await?;
+    let key = weekend_render_key(weekend, display, settings);
+    set_message_content(db_conn, id, &key, hash).await?;
+    rendered::remember(id, &key);
+    Ok(())
+}
+
+/// Everything that ends up in a weekend message, to tell whether an edit
+/// would change anything.
+fn weekend_render_key(
+    weekend: &FullWeekend,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
+) -> String {
+    let mut key = weekend_message_content(weekend, display, settings);
+    if !display.plain_text {
+        key += "\0embed";
+    }
+    for link in weekend.links.iter() {
+        key += &format!("\0{}\0{}", link.label, link.url);
+    }
+    key
+}
+
+/// Edits a weekend message unless the rendered content is byte-identical to
+/// the last edit. Returns whether Discord was called.
+#[tracing::instrument(skip_all)]
+pub async fn update_weekend_message_if_changed(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    weekend: &FullWeekend,
+    message: &Message,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
+) -> Result<bool, crate::error::Error> {
+    let key = weekend_render_key(weekend, display, settings);
+    if rendered::is_current(message.id, &key) {
+        return Ok(false);
+    }
+    if fetch_message_content(db_conn, message.id).await?.as_ref() == Some(&key)
+    {
+        rendered::remember(message.id, &key);
+        return Ok(false);
+    }
+    let edited = update_weekend_message(
+        http,
+        weekend,
+        message.channel.parse()?,
+        message.message.parse()?,
+        display,
+        settings,
+    )
+    .await;
+    if let Err(Error::Serenity(serenity::Error::Http(why))) = &edited {
+        // deleted in Discord, expire it so the next tick posts a new one
+        if why.status_code().is_some_and(|f| f == StatusCode::NOT_FOUND) {
+            rendered::forget(message.id);
+            mark_message_expired(db_conn, message.id, None).await?;
+            return Ok(true);
+        }
+    }
+    edited?;
+    let hash = weekend_message_hash(weekend, display, settings);
+    set_message_content(db_conn, message.id, &key, hash).await?;
+    rendered::remember(message.id, &key);
+    Ok(true)
 }
 
 pub async fn update_weekend_message(
@@ -416,13 +1242,20 @@ pub async fn update_weekend_message(
     weekend: &FullWeekend,
     channel: u64,
     message: u64,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
 ) -> Result<(), crate::error::Error> {
+    // clear whichever of the two the message was posted with before
+    let edit = match display.plain_text {
+        true => EditMessage::new()
+            .content(weekend_message_content(weekend, display, settings))
+            .embeds(vec![]),
+        false => EditMessage::new()
+            .content("")
+            .embed(render::weekend_embed(weekend, display, settings)),
+    };
     ChannelId::new(channel)
-        .edit_message(
-            http,
-            message,
-            EditMessage::new().content(weekend.weekend_msg_str(true)),
-        )
+        .edit_message(http, message, edit.components(link_rows(weekend)))
         .await
         .map(|_f| ())?;
     Ok(())
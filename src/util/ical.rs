@@ -0,0 +1,183 @@
+//! iCalendar feeds of the stored schedule, for members to subscribe to in
+//! their own calendar apps.
+//!
+//! Session summaries are `<weekend> - <title>`, the format the ICS importer
+//! reads by default.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use f1_bot_types::{Series, Session, SessionStatus, Weekend};
+use sqlx::MySqlConnection;
+
+use crate::error::Error;
+
+use super::{
+    fetch_full_weekends_for_channel, names, session_end, FullWeekend,
+    SeasonWindow, SessionDuration,
+};
+
+/// Name of the combined feed, next to one `<series>.ics` per series.
+pub const COMBINED: &str = "all";
+
+/// Escapes `TEXT` values.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds `line` into 75 octet lines, ending with CRLF.
+fn push_line(
+    out: &mut String,
+    line: &str,
+) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        width += c.len_utf8();
+        out.push(c);
+    }
+    out.push_str("\r\n");
+}
+
+fn fmt_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Percent-encodes a query value.
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            true => out.push(byte as char),
+            false => out += &format!("%{byte:02X}"),
+        }
+    }
+    out
+}
+
+/// Link creating a Google Calendar event for `session`, for members who
+/// only want single sessions instead of a feed.
+pub fn google_calendar_url(
+    weekend: &Weekend,
+    session: &Session,
+) -> String {
+    let end = session_end(session.start_date, SessionDuration::of(session));
+    format!(
+        "https://calendar.google.com/calendar/render?action=TEMPLATE\
+         &text={}&dates={}/{}&details={}",
+        encode(&format!("{} - {}", weekend.name, session.title)),
+        fmt_time(session.start_date),
+        fmt_time(end),
+        encode(&weekend.series.to_string()),
+    )
+}
+
+/// Public URL of the `series` feed under `base`.
+pub fn feed_url(
+    base: &str,
+    series: Series,
+) -> String {
+    format!("{}/{}.ics", base.trim_end_matches('/'), names::series_key(series))
+}
+
+/// Renders `weekends` as a calendar named `name`.
+pub fn render(
+    name: &str,
+    weekends: &[FullWeekend],
+) -> String {
+    let mut out = String::new();
+    let stamp = fmt_time(Utc::now());
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//F1 Notif Bot//Schedule//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+    for weekend in weekends.iter() {
+        for session in weekend.sessions.iter() {
+            let end =
+                session_end(session.start_date, SessionDuration::of(session));
+            let status = match session.status {
+                SessionStatus::Cancelled => "CANCELLED",
+                _ => "CONFIRMED",
+            };
+            push_line(&mut out, "BEGIN:VEVENT");
+            push_line(
+                &mut out,
+                &format!("UID:session-{}@f1-notif-bot", session.id),
+            );
+            push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+            push_line(
+                &mut out,
+                &format!("DTSTART:{}", fmt_time(session.start_date)),
+            );
+            push_line(&mut out, &format!("DTEND:{}", fmt_time(end)));
+            push_line(
+                &mut out,
+                &format!(
+                    "SUMMARY:{}",
+                    escape(&format!(
+                        "{} - {}",
+                        weekend.weekend.name, session.title
+                    ))
+                ),
+            );
+            push_line(
+                &mut out,
+                &format!("CATEGORIES:{}", weekend.weekend.series),
+            );
+            push_line(&mut out, &format!("STATUS:{status}"));
+            push_line(&mut out, "END:VEVENT");
+        }
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// The feed of `series`, [None] combines every series.
+pub async fn feed(
+    db_conn: &mut MySqlConnection,
+    series: Option<Series>,
+) -> Result<String, sqlx::Error> {
+    let (name, series) = match series {
+        Some(series) => (series.to_string(), vec![series]),
+        None => ("All series".to_owned(), names::ALL_SERIES.to_vec()),
+    };
+    let weekends =
+        fetch_full_weekends_for_channel(db_conn, &series, SeasonWindow::ALL)
+            .await?;
+    Ok(render(&name, &weekends))
+}
+
+/// The series of a feed file name without `.ics`, `Some(None)` for the
+/// combined feed.
+pub fn feed_series(name: &str) -> Option<Option<Series>> {
+    if name == COMBINED {
+        return Some(None);
+    }
+    names::series_from_key(name).map(Some)
+}
+
+/// Writes every feed to `directory`.
+pub async fn write_feeds(
+    db_conn: &mut MySqlConnection,
+    directory: &str,
+) -> Result<(), Error> {
+    let directory = Path::new(directory);
+    tokio::fs::create_dir_all(directory).await?;
+    for series in names::ALL_SERIES {
+        let content = feed(db_conn, Some(series)).await?;
+        let file = format!("{}.ics", names::series_key(series));
+        tokio::fs::write(directory.join(file), content).await?;
+    }
+    let content = feed(db_conn, None).await?;
+    tokio::fs::write(directory.join(format!("{COMBINED}.ics")), content)
+        .await?;
+    Ok(())
+}
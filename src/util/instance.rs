@@ -0,0 +1,16 @@
+//! Id of this deployment, scoping the tracked messages so a mirror can share
+//! the production database without touching its messages.
+
+use std::sync::OnceLock;
+
+static ID: OnceLock<String> = OnceLock::new();
+
+/// Applies `instance`, before the bot loop starts.
+pub fn configure(instance: &str) {
+    let _ = ID.set(instance.to_owned());
+}
+
+/// Empty for production, whose messages aren't tagged.
+pub fn id() -> &'static str {
+    ID.get().map_or("", String::as_str)
+}
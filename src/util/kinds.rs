@@ -0,0 +1,75 @@
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+
+use f1_bot_types::SessionKind;
+
+/// Custom kinds are registered from this value up, the shared
+/// [SessionKind] enum keeps everything below for itself.
+pub const CUSTOM_KIND_START: i8 = 64;
+
+/// A session kind by its stored value.
+///
+/// Unlike [SessionKind] this also covers the kinds registered at runtime,
+/// like shakedowns or media days, so they can be imported without touching
+/// the shared types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KindCode(pub i8);
+
+impl KindCode {
+    /// The stored value of a known kind, [None] for
+    /// [Unsupported](SessionKind::Unsupported) which has none.
+    pub fn of(kind: &SessionKind) -> Option<Self> {
+        match kind {
+            SessionKind::Unsupported => None,
+            kind => Some(Self(kind.i8())),
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.0 >= CUSTOM_KIND_START
+    }
+}
+
+/// A session kind registered in the `custom_session_kinds` table.
+#[derive(Debug)]
+pub struct CustomKind {
+    pub code: i8,
+    pub name: String,
+    /// Stored and notified, but left out of weekend messages unless the
+    /// guild is [verbose](super::GuildSettings::verbose).
+    pub low_priority: bool,
+}
+
+/// Codes of the low-priority custom kinds, refreshed with every quarantine
+/// scan.
+static LOW_PRIORITY: LazyLock<Mutex<HashSet<i8>>> =
+    LazyLock::new(Default::default);
+
+pub fn is_low_priority(kind: &SessionKind) -> bool {
+    let Some(code) = KindCode::of(kind) else {
+        return false;
+    };
+    code.is_custom() && LOW_PRIORITY.lock().is_ok_and(|f| f.contains(&code.0))
+}
+
+/// Replaces the low-priority codes with the ones in `custom`.
+pub fn set_low_priority(custom: &[CustomKind]) {
+    if let Ok(mut low_priority) = LOW_PRIORITY.lock() {
+        *low_priority =
+            custom.iter().filter(|f| f.low_priority).map(|f| f.code).collect();
+    }
+}
+
+/// Whether sessions of `code` can be stored: it's either known to
+/// [SessionKind] or registered in `custom`.
+pub fn is_valid_kind(
+    code: KindCode,
+    custom: &[CustomKind],
+) -> bool {
+    if code.is_custom() {
+        return custom.iter().any(|f| f.code == code.0);
+    }
+    !matches!(SessionKind::from(code.0), SessionKind::Unsupported)
+}
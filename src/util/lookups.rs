@@ -0,0 +1,81 @@
+//! Optional in-process cache of the next weekend of every series, which the
+//! HTTP widgets and `/nextsession` look up on every request.
+//!
+//! Writes through this crate call [invalidate]. Only changes made from
+//! outside the bot can go unnoticed, for at most `cache.lookup_ttl` seconds.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use f1_bot_types::Series;
+use sqlx::MySqlConnection;
+use tokio::sync::Notify;
+
+use crate::config::CacheConfig;
+
+use super::{fetch_next_full_weekend_for_series, FullWeekend};
+
+/// Seconds entries are kept, 0 disables the cache.
+static TTL: AtomicU64 = AtomicU64::new(0);
+
+type Entry = (Instant, Option<Arc<FullWeekend>>);
+
+static NEXT: LazyLock<Mutex<HashMap<i8, Entry>>> =
+    LazyLock::new(Default::default);
+
+/// Applies `config`, before any lookup.
+pub fn configure(config: &CacheConfig) {
+    TTL.store(config.lookup_ttl, Ordering::Relaxed);
+    invalidate();
+}
+
+/// Woken on every [invalidate], so the bot loop picks up changes right away.
+static CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Drops every entry, called after writing weekends, sessions or the data
+/// attached to them.
+pub fn invalidate() {
+    if let Ok(mut next) = NEXT.lock() {
+        next.clear();
+    }
+    CHANGED.notify_one();
+}
+
+/// Resolves after the next [invalidate], or right away if one happened
+/// since the last call.
+pub async fn changed() {
+    CHANGED.notified().await;
+}
+
+fn cached(series: Series) -> Option<Option<Arc<FullWeekend>>> {
+    let ttl = Duration::from_secs(TTL.load(Ordering::Relaxed));
+    let next = NEXT.lock().ok()?;
+    let (taken, weekend) = next.get(&series.i8())?;
+    (taken.elapsed() < ttl).then(|| weekend.clone())
+}
+
+/// Like [fetch_next_full_weekend_for_series], served from the cache while
+/// it's enabled.
+pub async fn next_full_weekend(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Option<Arc<FullWeekend>>, sqlx::Error> {
+    if let Some(weekend) = cached(series) {
+        return Ok(weekend);
+    }
+    let weekend = fetch_next_full_weekend_for_series(db_conn, series)
+        .await?
+        .map(Arc::new);
+    if TTL.load(Ordering::Relaxed) > 0 {
+        if let Ok(mut next) = NEXT.lock() {
+            next.insert(series.i8(), (Instant::now(), weekend.clone()));
+        }
+    }
+    Ok(weekend)
+}
@@ -1,5 +1,45 @@
+pub mod alerts;
+pub mod audience;
+pub mod cache;
+pub mod cadence;
+pub mod changelog;
+pub mod clock;
+pub mod crash;
 pub mod database;
+pub mod dst;
+pub mod duration;
+pub mod facts;
+pub mod hashing;
 pub mod helpers;
+pub mod ical;
+pub mod instance;
+pub mod kinds;
+pub mod lookups;
+pub mod names;
+pub mod pacer;
+pub mod quarantine;
+pub mod reconcile;
+pub mod registry;
+pub mod render;
+pub mod rendered;
+pub mod retention;
+pub mod search;
+pub mod switches;
+pub mod templates;
+pub mod time;
 
+pub use cache::{cached_fetch, CacheKind};
+pub use cadence::Cadence;
 pub use database::*;
+pub use duration::{DurationError, SessionDuration};
+pub use hashing::{stable_hash, StableHasher};
 pub use helpers::*;
+pub use kinds::{
+    is_low_priority, is_valid_kind, CustomKind, KindCode, CUSTOM_KIND_START,
+};
+pub use names::{session_name, weekend_name};
+pub use pacer::Pacer;
+pub use time::{
+    is_in_notify_window, is_lights_out_due, is_milestone_due, parse_start,
+    session_end, week_of, DateFormat, WeekStart, NOTIFY_WINDOW,
+};
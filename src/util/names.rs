@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use f1_bot_types::{Series, Session};
+
+use crate::{
+    config::{SessionNameConfig, WeekendNameConfig},
+    util::KindCode,
+};
+
+/// Every series, in display order.
+pub const ALL_SERIES: [Series; 4] =
+    [Series::F1, Series::F2, Series::F3, Series::F1Academy];
+
+/// Config key for `series`, matching the discord config field prefixes.
+pub fn series_key(series: Series) -> &'static str {
+    match series {
+        Series::F1 => "f1",
+        Series::F2 => "f2",
+        Series::F3 => "f3",
+        Series::F1Academy => "f1a",
+    }
+}
+
+/// The series with the config key `key`.
+pub fn series_from_key(key: &str) -> Option<Series> {
+    ALL_SERIES.into_iter().find(|f| series_key(*f) == key)
+}
+
+/// Display name of `session` in the configured locale.
+///
+/// Looks for an override for the exact series first, then for one that
+/// applies to every series (`*`), and falls back to the stored title.
+pub fn session_name<'a>(
+    config: &'a SessionNameConfig,
+    series: Series,
+    session: &'a Session,
+) -> &'a str {
+    let Some(KindCode(kind)) = KindCode::of(&session.kind) else {
+        return &session.title;
+    };
+    let lookup = |key: &str| {
+        config.overrides.iter().find(|f| {
+            f.kind == kind && f.locale == config.locale && f.series == key
+        })
+    };
+    lookup(series_key(series))
+        .or_else(|| lookup("*"))
+        .map(|f| f.name.as_str())
+        .unwrap_or(&session.title)
+}
+
+/// Display name of the weekend officially called `name`.
+///
+/// A configured rename wins. Otherwise normalizing drops the `FORMULA 1`
+/// prefix, the title sponsors and a trailing year, and writes `GRAND PRIX`
+/// as `Grand Prix`: `Formula 1 Heineken Dutch Grand Prix 2026` becomes
+/// `Dutch Grand Prix`. Names that would end up empty are kept.
+pub fn weekend_name<'a>(
+    config: &'a WeekendNameConfig,
+    name: &'a str,
+) -> Cow<'a, str> {
+    let lowercase = name.to_lowercase();
+    if let Some(rename) =
+        config.renames.iter().find(|f| f.from.to_lowercase() == lowercase)
+    {
+        return Cow::Borrowed(&rename.to);
+    }
+    if !config.normalize {
+        return Cow::Borrowed(name);
+    }
+    let sponsors = config
+        .sponsors
+        .iter()
+        .map(|f| f.split_whitespace().collect::<Vec<_>>())
+        .filter(|f| !f.is_empty())
+        .collect::<Vec<_>>();
+    let words = name.split_whitespace().collect::<Vec<_>>();
+    let starts_with = |at: usize, phrase: &[&str]| {
+        words.len() >= at + phrase.len()
+            && words[at..at + phrase.len()]
+                .iter()
+                .zip(phrase)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    };
+    let mut at = match starts_with(0, &["formula", "1"]) {
+        true => 2,
+        false => 0,
+    };
+    let mut kept = vec![];
+    while at < words.len() {
+        if let Some(sponsor) = sponsors.iter().find(|f| starts_with(at, f)) {
+            at += sponsor.len();
+            continue;
+        }
+        let word = words[at];
+        let is_year = at == words.len() - 1
+            && word.len() == 4
+            && word.bytes().all(|f| f.is_ascii_digit());
+        if !is_year {
+            kept.push(match word {
+                _ if word.eq_ignore_ascii_case("grand") => "Grand",
+                _ if word.eq_ignore_ascii_case("prix") => "Prix",
+                word => word,
+            });
+        }
+        at += 1;
+    }
+    match kept.is_empty() {
+        true => Cow::Borrowed(name),
+        false => Cow::Owned(kept.join(" ")),
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+/// Spaces out bulk Discord operations so a long run can't trip rate limits.
+///
+/// Call [`Pacer::wait`] after every operation: it sleeps `spacing` between
+/// operations and `pause` after every `burst` of them.
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    burst: usize,
+    spacing: Duration,
+    pause: Duration,
+    done: usize,
+}
+
+impl Pacer {
+    pub const fn new(
+        burst: usize,
+        spacing: Duration,
+        pause: Duration,
+    ) -> Self {
+        Self {
+            burst,
+            spacing,
+            pause,
+            done: 0,
+        }
+    }
+
+    /// Pacing for posting messages into a single channel.
+    pub const fn messages() -> Self {
+        Self::new(5, Duration::from_millis(300), Duration::from_secs(5))
+    }
+
+    /// Pacing for deleting messages from a single channel.
+    pub const fn deletions() -> Self {
+        Self::new(5, Duration::from_millis(250), Duration::from_secs(2))
+    }
+
+    /// Number of operations recorded so far.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// Records one finished operation and waits until the next may run.
+    ///
+    /// Returns `true` when the operation completed a burst.
+    pub async fn wait(&mut self) -> bool {
+        self.done += 1;
+        if self.burst != 0 && self.done % self.burst == 0 {
+            tokio::time::sleep(self.pause).await;
+            true
+        } else {
+            tokio::time::sleep(self.spacing).await;
+            false
+        }
+    }
+}
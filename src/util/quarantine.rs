@@ -0,0 +1,61 @@
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+
+use sqlx::MySqlConnection;
+use tracing::warn;
+
+use super::{
+    fetch_custom_kinds, fetch_raw_session_kinds, is_valid_kind, kinds, lookups,
+    KindCode, RawSessionKind,
+};
+
+/// Sessions with a kind the bot can't handle, kept out of every scheduler
+/// and renderer until an admin fixes them.
+static QUARANTINE: LazyLock<Mutex<HashSet<i64>>> =
+    LazyLock::new(Default::default);
+
+pub fn is_quarantined(session: i64) -> bool {
+    QUARANTINE.lock().is_ok_and(|f| f.contains(&session))
+}
+
+/// Releases `session` right away, before the next scan.
+pub fn release(session: i64) {
+    if let Ok(mut quarantine) = QUARANTINE.lock() {
+        quarantine.remove(&session);
+    }
+    lookups::invalidate();
+}
+
+/// Rescans the sessions table and returns every quarantined session.
+///
+/// Sessions are only logged the first time they show up.
+pub async fn scan(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<RawSessionKind>, sqlx::Error> {
+    let custom = fetch_custom_kinds(db_conn).await?;
+    kinds::set_low_priority(&custom);
+    let bad = fetch_raw_session_kinds(db_conn)
+        .await?
+        .into_iter()
+        .filter(|f| !is_valid_kind(KindCode(f.kind), &custom))
+        .collect::<Vec<_>>();
+
+    if let Ok(mut quarantine) = QUARANTINE.lock() {
+        for session in bad.iter() {
+            if !quarantine.contains(&session.id) {
+                warn!(
+                    "Quarantined session {} `{}` with unknown kind {}",
+                    session.id, session.title, session.kind
+                );
+            }
+        }
+        let ids = bad.iter().map(|f| f.id).collect::<HashSet<_>>();
+        if *quarantine != ids {
+            *quarantine = ids;
+            lookups::invalidate();
+        }
+    }
+    Ok(bad)
+}
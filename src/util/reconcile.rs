@@ -0,0 +1,68 @@
+//! Drops tracked weekend and calendar messages left in a channel the config
+//! no longer points at, so the bot loop posts fresh ones in the new channel
+//! instead of editing the old ones.
+//!
+//! Notifications and other expiring messages are left alone, they are gone
+//! by the end of the session anyway.
+
+use f1_bot_types::{Message, MessageKind};
+use serenity::all::{CacheHttp, ChannelId};
+use sqlx::MySqlConnection;
+use tracing::{info, warn};
+
+use crate::{
+    config::Config,
+    error::Error,
+    util::{delete_message, fetch_messages},
+};
+
+/// Channel `message` belongs in, [None] for kinds that aren't reconciled and
+/// series without a channel.
+fn expected_channel(
+    config: &Config<'_>,
+    message: &Message,
+) -> Option<u64> {
+    let channel = match message.kind {
+        MessageKind::Weekend => config.channel(message.series),
+        MessageKind::Calendar => config.calendar_channel(message.series),
+        _ => return None,
+    };
+    (channel != 0).then_some(channel)
+}
+
+/// Removes every misplaced message, from Discord if it is still reachable
+/// and from the database either way. Returns the number of messages removed.
+pub async fn run(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    config: &Config<'_>,
+) -> Result<usize, Error> {
+    let mut removed = 0;
+    for message in fetch_messages(db_conn).await? {
+        let Some(expected) = expected_channel(config, &message) else {
+            continue;
+        };
+        let channel: u64 = message.channel.parse()?;
+        if channel == expected {
+            continue;
+        }
+        // the old channel may be gone or out of reach after a move, the row
+        // has to go regardless or it would be edited forever
+        if let Err(why) = ChannelId::new(channel)
+            .delete_message(http.http(), message.message.parse::<u64>()?)
+            .await
+        {
+            warn!(
+                "Couldn't delete message {} in <#{channel}>: {why}",
+                message.message
+            );
+        }
+        delete_message(db_conn, message.id).await?;
+        info!(
+            "Dropped {:?} message for {} from <#{channel}>, now <#{expected}>",
+            message.kind, message.series
+        );
+        removed += 1;
+    }
+    Ok(removed)
+}
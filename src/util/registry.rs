@@ -0,0 +1,83 @@
+//! The `series` table, per-series settings that can change at runtime.
+//!
+//! Rows override the names, channels and roles of the config file and can
+//! switch a series off entirely. Rows with ids outside [Series] are listed
+//! in `/status` but can't have weekends until `f1-bot-types` gains a
+//! dynamic series id, adding a championship is blocked on that.
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use f1_bot_types::Series;
+use sqlx::MySqlConnection;
+
+#[derive(Debug, Clone)]
+pub struct SeriesEntry {
+    pub id: i8,
+    pub name: String,
+    pub icon: String,
+    /// Overrides the configured notification channel.
+    pub channel: Option<u64>,
+    /// Overrides the configured role.
+    pub role: Option<u64>,
+    pub enabled: bool,
+}
+
+/// The `series` table, refreshed by the bot loop on every tick.
+static REGISTRY: LazyLock<Mutex<HashMap<i8, SeriesEntry>>> =
+    LazyLock::new(Default::default);
+
+fn with_entry<T>(
+    series: Series,
+    f: impl FnOnce(&SeriesEntry) -> Option<T>,
+) -> Option<T> {
+    REGISTRY.lock().ok()?.get(&series.i8()).and_then(f)
+}
+
+/// Display name of `series`, from its row if there is one.
+pub fn name(series: Series) -> String {
+    with_entry(series, |f| Some(f.name.clone()))
+        .unwrap_or_else(|| series.to_string())
+}
+
+pub fn channel(series: Series) -> Option<u64> {
+    with_entry(series, |f| f.channel)
+}
+
+pub fn role(series: Series) -> Option<u64> {
+    with_entry(series, |f| f.role)
+}
+
+/// Series without a row are enabled.
+pub fn is_enabled(series: Series) -> bool {
+    with_entry(series, |f| Some(f.enabled)).unwrap_or(true)
+}
+
+/// Every row, ordered by id.
+pub fn entries() -> Vec<SeriesEntry> {
+    let mut entries = REGISTRY
+        .lock()
+        .map(|f| f.values().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    entries.sort_by_key(|f| f.id);
+    entries
+}
+
+pub async fn fetch_series_entries(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<SeriesEntry>, sqlx::Error> {
+    sqlx::query_as!(SeriesEntry, "SELECT * FROM series ORDER BY id ASC")
+        .fetch_all(db_conn)
+        .await
+}
+
+/// Reloads the table, so edits take effect without a restart.
+pub async fn refresh(db_conn: &mut MySqlConnection) -> Result<(), sqlx::Error> {
+    let entries = fetch_series_entries(db_conn).await?;
+    if let Ok(mut registry) = REGISTRY.lock() {
+        *registry = entries.into_iter().map(|f| (f.id, f)).collect();
+    }
+    Ok(())
+}
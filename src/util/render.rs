@@ -0,0 +1,312 @@
+//! Weekend and calendar messages, as embeds or as the plain text they were
+//! before.
+
+use chrono::Utc;
+use f1_bot_types::{Series, Session, SessionStatus};
+use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter};
+
+use crate::{
+    config::DisplayConfig,
+    util::{
+        ical, is_low_priority, is_qualifying, is_race, is_session_over,
+        registry, session_name, templates, weekend_name, CalendarVerbosity,
+        FullWeekend, GuildSettings, TrackImage,
+    },
+};
+
+/// Embed colour of `series`.
+pub fn colour(series: Series) -> Colour {
+    match series {
+        Series::F1 => Colour::new(0xE10600),
+        Series::F2 => Colour::new(0x0090D0),
+        Series::F3 => Colour::new(0xE4002B),
+        Series::F1Academy => Colour::new(0xEB0A8C),
+    }
+}
+
+/// Time of `session` as shown in messages. In the weekend message it is
+/// struck through once over or cancelled and carries the forecast.
+fn session_time(
+    weekend: &FullWeekend,
+    session: &Session,
+    display: &DisplayConfig,
+    in_weekend: bool,
+) -> String {
+    let done = match in_weekend
+        && (is_session_over(session, Utc::now())
+            || session.status == SessionStatus::Cancelled)
+    {
+        true => "~~",
+        false => "",
+    };
+    let timestamp = session.start_date.timestamp();
+    let weather = match in_weekend {
+        true => weekend.forecast_str(session, display),
+        false => String::new(),
+    };
+    let time = templates::fill(
+        &templates::get().session,
+        &[
+            ("session.start", format!("<t:{timestamp}:f>").as_str()),
+            ("session.relative", format!("<t:{timestamp}:R>").as_str()),
+            (
+                "session.track_time",
+                weekend.track_time_str(session, display).as_str(),
+            ),
+            ("session.weather", weather.as_str()),
+        ],
+    );
+    format!("{done}{time}{done}")
+}
+
+/// Link adding `session` to a personal calendar, with
+/// `display.calendar_links` for sessions still to come. `plain` text
+/// suppresses the link preview.
+fn calendar_link(
+    weekend: &FullWeekend,
+    session: &Session,
+    display: &DisplayConfig,
+    plain: bool,
+) -> String {
+    if !display.calendar_links
+        || is_session_over(session, Utc::now())
+        || session.status == SessionStatus::Cancelled
+    {
+        return String::new();
+    }
+    let url = ical::google_calendar_url(&weekend.weekend, session);
+    match plain {
+        true => format!(" · [Add to calendar](<{url}>)"),
+        false => format!(" · [Add to calendar]({url})"),
+    }
+}
+
+/// Link to the feed of the series of `weekend`, with `display.feed_url`.
+fn feed_str(
+    weekend: &FullWeekend,
+    display: &DisplayConfig,
+    plain: bool,
+) -> String {
+    if display.feed_url.is_empty() {
+        return String::new();
+    }
+    let url = ical::feed_url(&display.feed_url, weekend.weekend.series);
+    match plain {
+        true => format!("\n-# [Subscribe to the calendar](<{url}>)"),
+        false => format!("\n-# [Subscribe to the calendar]({url})"),
+    }
+}
+
+/// `template` filled for the title of `weekend`, naming the series when
+/// `show_series`.
+fn title(
+    template: &str,
+    weekend: &FullWeekend,
+    show_series: bool,
+    display: &DisplayConfig,
+) -> String {
+    let series = registry::name(weekend.weekend.series);
+    let name = weekend_name(&display.weekend_names, &weekend.weekend.name);
+    let round = weekend.round_str();
+    let shared = match show_series {
+        true => format!(" ({series})"),
+        false => String::new(),
+    };
+    templates::fill(
+        template,
+        &[
+            ("series", series.as_str()),
+            ("series.shared", shared.as_str()),
+            ("weekend.icon", weekend.weekend.icon.as_str()),
+            ("weekend.name", &*name),
+            ("weekend.round", round.as_str()),
+            ("weekend.provisional", weekend.provisional_str()),
+        ],
+    )
+}
+
+/// Sessions shown in the weekend message, low-priority ones only when
+/// `verbose`.
+fn shown_sessions(
+    weekend: &FullWeekend,
+    verbose: bool,
+) -> impl Iterator<Item = (bool, &Session)> {
+    // the main race, the last one for weekends with a sprint race
+    let race = weekend.sessions.iter().rposition(is_race);
+    weekend
+        .sessions
+        .iter()
+        .enumerate()
+        .filter(move |(_, f)| verbose || !is_low_priority(&f.kind))
+        .map(move |(index, f)| (race == Some(index), f))
+}
+
+/// Hint on how to get the notification role of `series`.
+fn role_hint(series: Series) -> String {
+    let series = registry::name(series);
+    format!("Use Channels & Roles to get the {series}-notifications role")
+}
+
+/// The weekend message as text, low-priority sessions are only listed when
+/// `verbose`.
+pub fn weekend_text(
+    weekend: &FullWeekend,
+    extra: bool,
+    display: &DisplayConfig,
+    verbose: bool,
+) -> String {
+    let mut sessions_str = String::new();
+    for (is_race, session) in shown_sessions(weekend, verbose) {
+        sessions_str += &format!(
+            "\n> `{:>12}` {}{}",
+            session_name(&display.names, weekend.weekend.series, session),
+            session_time(weekend, session, display, true),
+            calendar_link(weekend, session, display, true)
+        );
+        if extra && is_race {
+            sessions_str += &weekend.penalties_str();
+        }
+    }
+    let extra_str = match extra {
+        true => &format!("\nUse <id:customize> to get the `{}-notifications` role\n**Times are in your Timezone**", weekend.weekend.series),
+        false => ""
+    };
+    format!(
+        "{}{}{}{}",
+        title(&templates::get().weekend_title, weekend, false, display),
+        sessions_str,
+        extra_str,
+        match extra {
+            true =>
+                weekend.facts_str(display) + &feed_str(weekend, display, true),
+            false => String::new(),
+        }
+    )
+}
+
+/// The weekend message as an embed, one field per session.
+pub fn weekend_embed(
+    weekend: &FullWeekend,
+    display: &DisplayConfig,
+    settings: &GuildSettings,
+) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(title(&templates::get().weekend_title, weekend, false, display))
+        .colour(colour(weekend.weekend.series))
+        .footer(CreateEmbedFooter::new(format!(
+            "{} · Times are in your timezone",
+            role_hint(weekend.weekend.series)
+        )));
+    for (is_race, session) in shown_sessions(weekend, settings.verbose) {
+        let mut value = session_time(weekend, session, display, true)
+            + &calendar_link(weekend, session, display, false);
+        if is_race {
+            value += &weekend.penalties_str();
+        }
+        embed = embed.field(
+            session_name(&display.names, weekend.weekend.series, session),
+            value,
+            false,
+        );
+    }
+    embed = match weekend.track_image() {
+        Some(TrackImage::Url(url)) => embed.image(url),
+        Some(image @ TrackImage::File(_)) => match image.file_name() {
+            Some(name) => embed.attachment(name),
+            None => embed,
+        },
+        None => embed,
+    };
+    let description = weekend.circuit_str()
+        + &weekend.facts_str(display)
+        + &weekend.footers_str(settings)
+        + &feed_str(weekend, display, false);
+    let description = description.trim_start();
+    match description.is_empty() {
+        true => embed,
+        false => embed.description(description),
+    }
+}
+
+/// Sessions of `weekend` listed in the calendar at `verbosity`.
+fn calendar_sessions(
+    weekend: &FullWeekend,
+    verbosity: CalendarVerbosity,
+) -> impl Iterator<Item = &Session> {
+    weekend.sessions.iter().filter(move |f| match verbosity {
+        CalendarVerbosity::Full => true,
+        CalendarVerbosity::Compact => is_race(f) || is_qualifying(f),
+        CalendarVerbosity::Minimal => false,
+    })
+}
+
+/// First to last day of `weekend`, empty for weekends without sessions.
+fn dates_str(weekend: &FullWeekend) -> String {
+    let (Some(first), Some(last)) =
+        (weekend.sessions.first(), weekend.sessions.last())
+    else {
+        return String::new();
+    };
+    let (first, last) =
+        (first.start_date.timestamp(), last.start_date.timestamp());
+    match first == last {
+        true => format!("<t:{first}:D>"),
+        false => format!("<t:{first}:D> – <t:{last}:D>"),
+    }
+}
+
+/// The calendar line of `weekend`, with `show_series` for channels shared by
+/// several series.
+pub fn calendar_text(
+    weekend: &FullWeekend,
+    show_series: bool,
+    display: &DisplayConfig,
+    verbosity: CalendarVerbosity,
+) -> String {
+    let mut sessions_str = String::new();
+    if verbosity == CalendarVerbosity::Minimal {
+        sessions_str = format!("\n> {}", dates_str(weekend));
+    }
+    for session in calendar_sessions(weekend, verbosity) {
+        sessions_str += &format!(
+            "\n> `{:>12}` {}",
+            session_name(&display.names, weekend.weekend.series, session),
+            session_time(weekend, session, display, false)
+        );
+    }
+    title(&templates::get().calendar_title, weekend, show_series, display)
+        + &sessions_str
+}
+
+/// The calendar message in front of the first weekend of `year`.
+pub fn season_separator(year: i32) -> String {
+    format!("## — {year} Season —")
+}
+
+/// The calendar entry of `weekend` as an embed.
+pub fn calendar_embed(
+    weekend: &FullWeekend,
+    show_series: bool,
+    display: &DisplayConfig,
+    verbosity: CalendarVerbosity,
+) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(title(
+            &templates::get().calendar_title,
+            weekend,
+            show_series,
+            display,
+        ))
+        .colour(colour(weekend.weekend.series));
+    if verbosity == CalendarVerbosity::Minimal {
+        embed = embed.description(dates_str(weekend));
+    }
+    for session in calendar_sessions(weekend, verbosity) {
+        embed = embed.field(
+            session_name(&display.names, weekend.weekend.series, session),
+            session_time(weekend, session, display, false),
+            true,
+        );
+    }
+    embed
+}
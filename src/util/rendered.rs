@@ -0,0 +1,41 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use super::stable_hash;
+
+/// Hash of the content last sent for each message row, so unchanged
+/// messages are skipped without reading Discord or `message_contents`.
+///
+/// Nothing here is authoritative, edits that fail because the message is
+/// gone are what keeps it in line with Discord.
+static RENDERED: LazyLock<Mutex<HashMap<u64, u64>>> =
+    LazyLock::new(Default::default);
+
+fn content_hash(content: &str) -> u64 {
+    stable_hash(&content)
+}
+
+/// Whether `content` is what message `id` was last edited to.
+pub fn is_current(
+    id: u64,
+    content: &str,
+) -> bool {
+    RENDERED.lock().is_ok_and(|f| f.get(&id) == Some(&content_hash(content)))
+}
+
+pub fn remember(
+    id: u64,
+    content: &str,
+) {
+    if let Ok(mut rendered) = RENDERED.lock() {
+        rendered.insert(id, content_hash(content));
+    }
+}
+
+pub fn forget(id: u64) {
+    if let Ok(mut rendered) = RENDERED.lock() {
+        rendered.remove(&id);
+    }
+}
@@ -0,0 +1,115 @@
+//! Removes old notification rows from the `messages` table, which would
+//! otherwise grow with every session.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use f1_bot_types::MessageKind;
+use sqlx::{Connection, MySqlConnection, MySqlPool};
+use tracing::{error, info};
+
+use crate::{
+    config::RetentionConfig,
+    util::{acquire, instance},
+};
+
+/// Time between pruning runs.
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Rows removed since startup and the time of the last run, read by
+/// `/status`.
+static PRUNED: AtomicU64 = AtomicU64::new(0);
+static LAST_RUN: AtomicU64 = AtomicU64::new(0);
+
+/// Rows pruned since startup and when the last run finished.
+pub fn stats() -> (u64, Option<DateTime<Utc>>) {
+    let last = LAST_RUN.load(Ordering::Relaxed);
+    let last =
+        (last != 0).then(|| DateTime::from_timestamp(last as i64, 0)).flatten();
+    (PRUNED.load(Ordering::Relaxed), last)
+}
+
+/// Deletes notification messages posted before `cutoff`, along with their
+/// stored content and history. Returns the number of messages removed.
+pub async fn prune_notifications(
+    db_conn: &mut MySqlConnection,
+    cutoff: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let kind = MessageKind::Notification.i8();
+    let mut tx = db_conn.begin().await?;
+    sqlx::query!(
+        "DELETE FROM message_contents WHERE message IN
+(SELECT id FROM messages WHERE kind = ? AND posted < ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?)",
+        kind,
+        cutoff,
+        instance::id()
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "DELETE FROM message_history WHERE message IN
+(SELECT id FROM messages WHERE kind = ? AND posted < ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?)",
+        kind,
+        cutoff,
+        instance::id()
+    )
+    .execute(&mut *tx)
+    .await?;
+    // tags go first, the subquery needs the rows they belong to
+    sqlx::query!(
+        "DELETE FROM message_instances WHERE instance = ? AND message IN
+(SELECT id FROM messages WHERE kind = ? AND posted < ?)",
+        instance::id(),
+        kind,
+        cutoff
+    )
+    .execute(&mut *tx)
+    .await?;
+    let result = sqlx::query!(
+        "DELETE FROM messages WHERE kind = ? AND posted < ?
+AND COALESCE((SELECT instance FROM message_instances WHERE message = messages.id), '') = ?",
+        kind,
+        cutoff,
+        instance::id()
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Prunes every [INTERVAL] while `config.notification_days` is above 0.
+pub async fn run(
+    pool: MySqlPool,
+    config: &RetentionConfig,
+) {
+    if config.notification_days == 0 {
+        return;
+    }
+    loop {
+        let cutoff =
+            Utc::now() - TimeDelta::days(config.notification_days as i64);
+        match acquire(&pool).await {
+            Ok(mut db_conn) => {
+                match prune_notifications(db_conn.as_mut(), cutoff).await {
+                    Ok(removed) => {
+                        PRUNED.fetch_add(removed, Ordering::Relaxed);
+                        LAST_RUN.store(
+                            Utc::now().timestamp() as u64,
+                            Ordering::Relaxed,
+                        );
+                        info!("Pruned {removed} notification messages");
+                    },
+                    Err(why) => error!("{why:#?}"),
+                }
+            },
+            Err(why) => error!("{why:#?}"),
+        }
+        tokio::time::sleep(INTERVAL).await;
+    }
+}
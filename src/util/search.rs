@@ -0,0 +1,62 @@
+//! Loose matching of weekend names, so "vegas" or "imla" find their round.
+
+/// `LIKE` pattern matching every name that contains the characters of
+/// `query` in order, the candidates [score] then ranks.
+pub fn like_pattern(query: &str) -> String {
+    let mut pattern = "%".to_owned();
+    for c in query.chars().filter(|f| !f.is_whitespace()) {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+        pattern.push('%');
+    }
+    pattern
+}
+
+/// How well `name` matches `query`, higher is better. [None] if the
+/// characters of `query` don't appear in `name` in order.
+///
+/// Whole-word and substring matches rank above scattered ones, earlier
+/// matches above later ones.
+pub fn score(
+    query: &str,
+    name: &str,
+) -> Option<u32> {
+    let query = query
+        .chars()
+        .filter(|f| !f.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    let name = name.chars().flat_map(char::to_lowercase).collect::<Vec<_>>();
+    if query.is_empty() {
+        return None;
+    }
+
+    if let Some(start) = name.windows(query.len()).position(|f| f == query) {
+        let word_start = start == 0 || !name[start - 1].is_alphanumeric();
+        let bonus = if word_start {
+            500
+        } else {
+            0
+        };
+        return Some(2000 + bonus - start.min(500) as u32);
+    }
+
+    // scattered match, every skipped character costs a point
+    let mut gaps = 0u32;
+    let mut rest = query.iter().peekable();
+    for c in name.iter() {
+        match rest.peek() {
+            Some(next) if *next == c => {
+                rest.next();
+            },
+            Some(_) if rest.len() < query.len() => gaps += 1,
+            _ => {},
+        }
+    }
+    match rest.peek() {
+        Some(_) => None,
+        None => Some(1000u32.saturating_sub(gaps)),
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use sqlx::MySqlConnection;
+
+/// A part of the bot loop that can be paused without a redeploy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Component {
+    Calendar,
+    WeekendMessages,
+    Notifications,
+    Stage,
+}
+
+impl Component {
+    pub const ALL: [Self; 4] = [
+        Self::Calendar,
+        Self::WeekendMessages,
+        Self::Notifications,
+        Self::Stage,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Calendar => "calendar",
+            Self::WeekendMessages => "weekend_messages",
+            Self::Notifications => "notifications",
+            Self::Stage => "stage",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+}
+
+/// `bot_state` row holding when the current `/pause` ends.
+const PAUSED_UNTIL: &str = "paused_until";
+
+/// Components disabled in the `disabled_components` table, refreshed by
+/// the bot loop on every tick.
+static DISABLED: LazyLock<Mutex<HashSet<Component>>> =
+    LazyLock::new(Default::default);
+
+/// End of the current `/pause`, refreshed along with [DISABLED].
+static PAUSE: Mutex<Option<DateTime<Utc>>> = Mutex::new(None);
+
+/// Whether `component` runs, it doesn't while it's disabled or the whole
+/// bot is paused.
+pub fn is_enabled(component: Component) -> bool {
+    !is_paused() && !DISABLED.lock().is_ok_and(|f| f.contains(&component))
+}
+
+/// End of the current `/pause`, [None] while the bot isn't paused.
+pub fn paused_until() -> Option<DateTime<Utc>> {
+    PAUSE.lock().ok().and_then(|f| *f).filter(|f| *f > Utc::now())
+}
+
+/// Whether a `/pause` holds back everything the bot posts.
+pub fn is_paused() -> bool {
+    paused_until().is_some()
+}
+
+async fn fetch_pause(
+    db_conn: &mut MySqlConnection
+) -> Result<Option<(String, DateTime<Utc>)>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT value FROM bot_state WHERE name = ?",
+        PAUSED_UNTIL
+    )
+    .fetch_optional(db_conn)
+    .await?;
+    // an unreadable value doesn't pause anything
+    Ok(row.and_then(|f| {
+        let until = DateTime::parse_from_rfc3339(&f.value).ok()?;
+        Some((f.value, until.with_timezone(&Utc)))
+    }))
+}
+
+/// Pauses the whole bot until `until`, across restarts.
+pub async fn pause(
+    db_conn: &mut MySqlConnection,
+    until: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO bot_state (name, value) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE value = VALUES(value)",
+        PAUSED_UNTIL,
+        until.to_rfc3339()
+    )
+    .execute(db_conn)
+    .await?;
+    if let Ok(mut pause) = PAUSE.lock() {
+        *pause = Some(until);
+    }
+    Ok(())
+}
+
+/// Ends the current pause, returns false if there was none.
+pub async fn resume(
+    db_conn: &mut MySqlConnection
+) -> Result<bool, sqlx::Error> {
+    let result =
+        sqlx::query!("DELETE FROM bot_state WHERE name = ?", PAUSED_UNTIL)
+            .execute(db_conn)
+            .await?;
+    if let Ok(mut pause) = PAUSE.lock() {
+        *pause = None;
+    }
+    Ok(result.rows_affected() > 0)
+}
+
+/// Clears a pause that ran out by `now`. Returns true for the one caller
+/// that cleared it, so the resume is announced once.
+pub async fn end_pause(
+    db_conn: &mut MySqlConnection,
+    now: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let Some((value, until)) = fetch_pause(db_conn).await? else {
+        return Ok(false);
+    };
+    if until > now {
+        return Ok(false);
+    }
+    // only the value read above, a new pause may have replaced it since
+    let result = sqlx::query!(
+        "DELETE FROM bot_state WHERE name = ? AND value = ?",
+        PAUSED_UNTIL,
+        value
+    )
+    .execute(db_conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Reloads the disabled components and the pause, so a toggle made by
+/// another process takes effect too.
+pub async fn refresh(db_conn: &mut MySqlConnection) -> Result<(), sqlx::Error> {
+    let names = sqlx::query_scalar!("SELECT name FROM disabled_components")
+        .fetch_all(&mut *db_conn)
+        .await?;
+    let disabled =
+        names.iter().filter_map(|f| Component::from_name(f)).collect();
+    if let Ok(mut current) = DISABLED.lock() {
+        *current = disabled;
+    }
+    let until = fetch_pause(db_conn).await?.map(|(_, until)| until);
+    if let Ok(mut pause) = PAUSE.lock() {
+        *pause = until;
+    }
+    Ok(())
+}
+
+/// Pauses or resumes `component`, `by` is recorded for `/feature list`.
+pub async fn set_enabled(
+    db_conn: &mut MySqlConnection,
+    component: Component,
+    enabled: bool,
+    by: &str,
+) -> Result<(), sqlx::Error> {
+    if enabled {
+        sqlx::query!(
+            "DELETE FROM disabled_components WHERE name = ?",
+            component.name()
+        )
+        .execute(db_conn)
+        .await?;
+    } else {
+        sqlx::query!(
+            "INSERT INTO disabled_components (name, disabled_by) VALUES (?, ?)
+ON DUPLICATE KEY UPDATE disabled_by = VALUES(disabled_by)",
+            component.name(),
+            by
+        )
+        .execute(db_conn)
+        .await?;
+    }
+    if let Ok(mut disabled) = DISABLED.lock() {
+        match enabled {
+            true => disabled.remove(&component),
+            false => disabled.insert(component),
+        };
+    }
+    Ok(())
+}
+
+/// A disabled component and who disabled it.
+#[derive(Debug)]
+pub struct DisabledComponent {
+    pub name: String,
+    pub disabled_by: String,
+    pub disabled_at: DateTime<Utc>,
+}
+
+pub async fn fetch_disabled(
+    db_conn: &mut MySqlConnection
+) -> Result<Vec<DisabledComponent>, sqlx::Error> {
+    sqlx::query_as!(
+        DisabledComponent,
+        "SELECT * FROM disabled_components ORDER BY name"
+    )
+    .fetch_all(db_conn)
+    .await
+}
@@ -0,0 +1,105 @@
+//! Placeholder templates for the message wording, configured in
+//! `[templates]`.
+//!
+//! Placeholders are written as `{weekend.name}`, `{{` and `}}` are literal
+//! braces. Every template is checked against the placeholders it supports
+//! when the config is loaded.
+
+use std::sync::OnceLock;
+
+use crate::config::TemplateConfig;
+
+static TEMPLATES: OnceLock<&'static TemplateConfig> = OnceLock::new();
+
+/// Uses `config` for every message rendered from now on.
+pub fn configure(config: &'static TemplateConfig) {
+    let _ = TEMPLATES.set(config);
+}
+
+/// The configured templates, the defaults before [configure].
+pub fn get() -> &'static TemplateConfig {
+    static DEFAULT: OnceLock<TemplateConfig> = OnceLock::new();
+    TEMPLATES
+        .get()
+        .copied()
+        .unwrap_or_else(|| DEFAULT.get_or_init(TemplateConfig::default))
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, String> {
+    let mut parts = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        parts.push(Part::Text(&rest[..start]));
+        let brace = &rest[start..start + 1];
+        if rest[start + 1..].starts_with(brace) {
+            parts.push(Part::Text(brace));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        if brace == "}" {
+            return Err("Unmatched `}`".to_owned());
+        }
+        let Some(end) = rest[start..].find('}') else {
+            return Err("Unclosed `{`".to_owned());
+        };
+        parts.push(Part::Placeholder(&rest[start + 1..start + end]));
+        rest = &rest[start + end + 1..];
+    }
+    parts.push(Part::Text(rest));
+    Ok(parts)
+}
+
+/// Rejects `template` if it doesn't parse or uses a placeholder not in
+/// `allowed`.
+pub fn check(
+    template: &str,
+    allowed: &[&str],
+) -> Result<(), String> {
+    for part in parse(template)? {
+        if let Part::Placeholder(name) = part {
+            if !allowed.contains(&name) {
+                return Err(format!("Unknown placeholder `{{{name}}}`"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `template` uses the placeholder `name`.
+pub fn uses(
+    template: &str,
+    name: &str,
+) -> bool {
+    parse(template).is_ok_and(|parts| {
+        parts.iter().any(|f| matches!(f, Part::Placeholder(p) if *p == name))
+    })
+}
+
+/// `template` with its placeholders replaced by the matching `values`,
+/// placeholders without a value are left empty.
+pub fn fill(
+    template: &str,
+    values: &[(&str, &str)],
+) -> String {
+    let Ok(parts) = parse(template) else {
+        // only reachable for templates that skipped [check]
+        return template.to_owned();
+    };
+    let mut filled = String::with_capacity(template.len());
+    for part in parts {
+        match part {
+            Part::Text(text) => filled += text,
+            Part::Placeholder(name) => {
+                if let Some((_, value)) = values.iter().find(|f| f.0 == name) {
+                    filled += value;
+                }
+            },
+        }
+    }
+    filled
+}
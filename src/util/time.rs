@@ -0,0 +1,158 @@
+//! Schedule math shared by the notifiers, taking `now` as an argument so it
+//! can be checked against arbitrary dates.
+
+use chrono::{
+    DateTime, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+use super::SessionDuration;
+
+/// How long before its start a session gets notified.
+pub const NOTIFY_WINDOW: TimeDelta = TimeDelta::minutes(5);
+
+/// End of a session.
+pub fn session_end(
+    start: DateTime<Utc>,
+    duration: SessionDuration,
+) -> DateTime<Utc> {
+    duration.end(start)
+}
+
+/// Whether a session starting at `start` should be notified at `now`.
+///
+/// That's the case from `NOTIFY_WINDOW + lead` before the start until a
+/// minute after it, so a slow loop iteration doesn't skip a session.
+pub fn is_in_notify_window(
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+    lead: TimeDelta,
+) -> bool {
+    let remaining = start.signed_duration_since(now);
+    remaining.num_minutes() >= 0 && remaining < NOTIFY_WINDOW + lead
+}
+
+/// Whether the milestone `offset_minutes` before `start` falls into the
+/// five minutes leading up to `now`.
+pub fn is_milestone_due(
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+    offset_minutes: u64,
+) -> bool {
+    let remaining = start.signed_duration_since(now).num_seconds();
+    let offset_secs = offset_minutes as i64 * 60;
+    remaining <= offset_secs && remaining > offset_secs - 300
+}
+
+/// Whether the lights out `delay` seconds after `start` were within the
+/// last `NOTIFY_WINDOW` at `now`.
+pub fn is_lights_out_due(
+    start: DateTime<Utc>,
+    delay: u64,
+    now: DateTime<Utc>,
+) -> bool {
+    let lights_out =
+        TimeDelta::try_seconds(delay.try_into().unwrap_or(i64::MAX))
+            .and_then(|f| start.checked_add_signed(f))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    now >= lights_out && now.signed_duration_since(lights_out) < NOTIFY_WINDOW
+}
+
+/// Parses a `YYYY-MM-DD HH:MM` time, or a bare `YYYY-MM-DD` for midnight,
+/// given in `tz`. Local times skipped by a clock change are [None], repeated
+/// ones resolve to the earlier instant.
+pub fn parse_start(
+    input: &str,
+    tz: Tz,
+) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    tz.from_local_datetime(&naive).earliest().map(|f| f.with_timezone(&Utc))
+}
+
+/// First day of the week in day-grouped views.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub const ALL: [Self; 2] = [Self::Monday, Self::Sunday];
+
+    /// The stored value.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Monday => "monday",
+            Self::Sunday => "sunday",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == value)
+    }
+
+    fn weekday(self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+/// How days are written out in headings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateFormat {
+    /// `Sunday, 9 March 2025`
+    #[default]
+    Long,
+    /// `Sunday, March 9, 2025`
+    Us,
+    /// `2025-03-09`
+    Iso,
+}
+
+impl DateFormat {
+    pub const ALL: [Self; 3] = [Self::Long, Self::Us, Self::Iso];
+
+    /// The stored value.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Long => "long",
+            Self::Us => "us",
+            Self::Iso => "iso",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == value)
+    }
+
+    /// `date` in this format.
+    pub fn format(
+        self,
+        date: NaiveDate,
+    ) -> String {
+        let pattern = match self {
+            Self::Long => "%A, %-d %B %Y",
+            Self::Us => "%A, %B %-d, %Y",
+            Self::Iso => "%Y-%m-%d",
+        };
+        date.format(pattern).to_string()
+    }
+}
+
+/// Days of the week `date` falls into, starting on `start`.
+pub fn week_of(
+    date: NaiveDate,
+    start: WeekStart,
+) -> impl Iterator<Item = NaiveDate> {
+    date.week(start.weekday()).first_day().iter_days().take(7)
+}
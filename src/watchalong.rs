@@ -0,0 +1,234 @@
+//! Community stream and watch-along links for live sessions. Trusted members
+//! submit them with `/submit-link`, an admin approves each one before it is
+//! posted into the thread under the session notification, and both are
+//! removed once the session is over.
+
+use chrono::Utc;
+use f1_bot_types::{MessageKind, Series, Session};
+use serenity::all::{
+    ButtonStyle, CacheHttp, ChannelId, ComponentInteraction, Context,
+    CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CreateThread, MessageId,
+};
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::info;
+
+use crate::{
+    error::Error,
+    util::{
+        delete_finished_watch_links, delete_watch_link, fetch_live_sessions,
+        fetch_notification_log_by_session, fetch_session, fetch_watch_link,
+        fetch_weekend, insert_expiring_message, insert_watch_link, session_end,
+        set_watch_link_posted, SessionDuration,
+    },
+};
+
+const APPROVE_PREFIX: &str = "watch_approve:";
+const REJECT_PREFIX: &str = "watch_reject:";
+
+/// The live session of `series`, the one that started last if several
+/// overlap.
+pub async fn live_session(
+    db_conn: &mut MySqlConnection,
+    series: Series,
+) -> Result<Option<Session>, Error> {
+    let live = fetch_live_sessions(db_conn, Utc::now()).await?;
+    for session in live.into_iter().rev() {
+        let Some(weekend) =
+            fetch_weekend(db_conn, session.weekend as u64).await?
+        else {
+            continue;
+        };
+        if weekend.series == series {
+            return Ok(Some(session));
+        }
+    }
+    Ok(None)
+}
+
+/// Stores a link for `session` and asks for approval in `admin_channel`.
+pub async fn submit(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    admin_channel: u64,
+    session: &Session,
+    label: &str,
+    url: &str,
+    submitter: u64,
+) -> Result<(), Error> {
+    let id =
+        insert_watch_link(db_conn, session.id, label, url, submitter).await?;
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{APPROVE_PREFIX}{id}"))
+            .label("Approve")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{REJECT_PREFIX}{id}"))
+            .label("Reject")
+            .style(ButtonStyle::Danger),
+    ]);
+    ChannelId::new(admin_channel)
+        .send_message(
+            http.http(),
+            CreateMessage::new()
+                .content(format!(
+                    "<@{submitter}> submitted a watch-along link for {}: \
+                     [{label}](<{url}>)",
+                    session.title
+                ))
+                .components(vec![buttons]),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Returns true for button ids created by [submit].
+pub fn is_watch_component(custom_id: &str) -> bool {
+    custom_id.starts_with(APPROVE_PREFIX)
+        || custom_id.starts_with(REJECT_PREFIX)
+}
+
+/// The thread under the notification of `session`, created as `name` if
+/// nobody started one yet.
+async fn live_thread(
+    db_conn: &mut MySqlConnection,
+    http: impl CacheHttp,
+    session: &Session,
+    name: String,
+) -> Result<Option<ChannelId>, Error> {
+    let Some(log) =
+        fetch_notification_log_by_session(db_conn, session.id).await?
+    else {
+        return Ok(None);
+    };
+    let channel = ChannelId::new(log.channel.parse()?);
+    let message = MessageId::new(log.message.parse()?);
+    let notification = channel.message(&http, message).await?;
+    if let Some(thread) = notification.thread {
+        return Ok(Some(thread.id));
+    }
+    let thread = channel
+        .create_thread_from_message(
+            http.http(),
+            message,
+            CreateThread::new(name),
+        )
+        .await?;
+    Ok(Some(thread.id))
+}
+
+/// Handles a click on one of the approval buttons.
+pub async fn handle_component(
+    ctx: &Context,
+    database: &MySqlPool,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = component.data.custom_id.as_str();
+    let (approve, id) = match custom_id.strip_prefix(APPROVE_PREFIX) {
+        Some(id) => (true, id),
+        None => (false, custom_id.trim_start_matches(REJECT_PREFIX)),
+    };
+    let id: u64 = id.parse()?;
+    let mut db_conn = database.acquire().await?;
+
+    let link = fetch_watch_link(db_conn.as_mut(), id).await?;
+    let session = match &link {
+        Some(link) if !link.approved => {
+            fetch_session(db_conn.as_mut(), link.session).await?
+        },
+        _ => None,
+    };
+    let weekend = match &session {
+        Some(session) => {
+            fetch_weekend(db_conn.as_mut(), session.weekend as u64).await?
+        },
+        None => None,
+    };
+    let (Some(link), Some(session), Some(weekend)) = (link, session, weekend)
+    else {
+        return respond(ctx, component, "*This link is no longer pending.*")
+            .await;
+    };
+    let end = session_end(session.start_date, SessionDuration::of(session));
+    if end <= Utc::now() {
+        delete_watch_link(db_conn.as_mut(), link.id).await?;
+        return respond(ctx, component, "*The session is already over.*").await;
+    }
+
+    if !approve {
+        delete_watch_link(db_conn.as_mut(), link.id).await?;
+        info!("Rejected watch link {} by {}", link.id, component.user.name);
+        let content = format!(
+            "{}\n*Rejected by <@{}>.*",
+            component.message.content, component.user.id
+        );
+        return respond(ctx, component, content).await;
+    }
+
+    let name = format!("{} {}", weekend.name, session.title);
+    let Some(thread) =
+        live_thread(db_conn.as_mut(), ctx, &session, name).await?
+    else {
+        return respond(ctx, component, "*The session was never notified.*")
+            .await;
+    };
+    let message = thread
+        .say(
+            &ctx.http,
+            format!(
+                "Watch along: [{}](<{}>), submitted by <@{}>",
+                link.label, link.url, link.submitter
+            ),
+        )
+        .await?;
+    set_watch_link_posted(
+        db_conn.as_mut(),
+        link.id,
+        thread.get(),
+        message.id.get(),
+    )
+    .await?;
+    insert_expiring_message(
+        db_conn.as_mut(),
+        thread.get(),
+        message.id.get(),
+        MessageKind::Custom,
+        weekend.series,
+        end,
+    )
+    .await?;
+    info!("Approved watch link {} by {}", link.id, component.user.name);
+    let content = format!(
+        "{}\n*Approved by <@{}>.*",
+        component.message.content, component.user.id
+    );
+    respond(ctx, component, content).await
+}
+
+/// Replaces the approval message with `content`, without the buttons.
+async fn respond(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    content: impl Into<String>,
+) -> Result<(), Error> {
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Forgets the links of sessions that are over, their posts in the live
+/// threads expire with the session.
+pub async fn tick(db_conn: &mut MySqlConnection) -> Result<(), Error> {
+    let removed = delete_finished_watch_links(db_conn, Utc::now()).await?;
+    if removed > 0 {
+        info!("Removed {removed} watch links of finished sessions");
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Session day forecasts from Open-Meteo for the next weekend of every
+//! series, shown in the weekend message with `display.weather`.
+
+use std::{collections::HashSet, time::Duration};
+
+use chrono::{NaiveDate, TimeDelta, Utc};
+use serde::Deserialize;
+use sqlx::{MySqlConnection, MySqlPool};
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    error::Error,
+    util::{
+        acquire, delete_forecasts_before, fetch_next_weekend_for_series,
+        fetch_weekend_circuit, fetch_weekend_meta, upsert_forecast, Circuit,
+        Forecast,
+    },
+};
+
+const BASE_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// Days Open-Meteo forecasts ahead, later weekends are skipped.
+const FORECAST_DAYS: i64 = 16;
+
+#[derive(Deserialize)]
+struct Response {
+    daily: Daily,
+}
+
+/// Daily values in the local time of the coordinates.
+#[derive(Deserialize)]
+struct Daily {
+    time: Vec<NaiveDate>,
+    temperature_2m_max: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+}
+
+async fn fetch_forecast(
+    client: &reqwest::Client,
+    circuit: &Circuit,
+) -> Result<Vec<Forecast>, Error> {
+    let response = client
+        .get(BASE_URL)
+        .query(&[
+            ("latitude", circuit.latitude.to_string()),
+            ("longitude", circuit.longitude.to_string()),
+            (
+                "daily",
+                "temperature_2m_max,precipitation_probability_max".to_owned(),
+            ),
+            ("timezone", "auto".to_owned()),
+            ("forecast_days", FORECAST_DAYS.to_string()),
+        ])
+        .send()
+        .await
+        .and_then(|f| f.error_for_status())
+        .map_err(|f| Error::NNF(f.into()))?;
+    let daily = response
+        .json::<Response>()
+        .await
+        .map_err(|f| Error::NNF(f.into()))?
+        .daily;
+    let fetched = Utc::now();
+    Ok(daily
+        .time
+        .into_iter()
+        .zip(daily.temperature_2m_max)
+        .zip(daily.precipitation_probability_max)
+        .filter_map(|((day, temperature), rain)| {
+            Some(Forecast {
+                circuit: circuit.id,
+                day,
+                temperature: temperature?,
+                rain_probability: rain.unwrap_or(0.0).clamp(0.0, 100.0) as u8,
+                fetched,
+            })
+        })
+        .collect())
+}
+
+/// Refreshes the forecasts of the circuits hosting the next weekend of each
+/// active series. Returns the number of circuits refreshed.
+async fn refresh(
+    db_conn: &mut MySqlConnection,
+    client: &reqwest::Client,
+    config: &Config<'_>,
+) -> Result<usize, Error> {
+    let now = Utc::now();
+    delete_forecasts_before(db_conn, now.date_naive() - TimeDelta::days(1))
+        .await?;
+    // support series race at the same circuits, fetch each of them once
+    let mut done = HashSet::new();
+    for series in config.active_series() {
+        let Some(weekend) =
+            fetch_next_weekend_for_series(db_conn, series).await?
+        else {
+            continue;
+        };
+        if weekend.start_date > now + TimeDelta::days(FORECAST_DAYS) {
+            continue;
+        }
+        let meta = fetch_weekend_meta(db_conn, weekend.id).await?;
+        let Some(circuit) =
+            fetch_weekend_circuit(db_conn, &weekend, &meta).await?
+        else {
+            continue;
+        };
+        if !done.insert(circuit.id) {
+            continue;
+        }
+        for forecast in fetch_forecast(client, &circuit).await? {
+            upsert_forecast(db_conn, &forecast).await?;
+        }
+    }
+    Ok(done.len())
+}
+
+/// Refreshes the forecasts every `config.weather.interval` seconds,
+/// starting right away.
+pub async fn run(
+    pool: MySqlPool,
+    config: &Config<'_>,
+) {
+    if config.weather.interval == 0 {
+        return;
+    }
+    let client = reqwest::Client::new();
+    loop {
+        match acquire(&pool).await {
+            Ok(mut db_conn) => {
+                match refresh(db_conn.as_mut(), &client, config).await {
+                    Ok(0) => {},
+                    Ok(count) => {
+                        info!("Refreshed forecasts for {count} circuits")
+                    },
+                    Err(why) => error!("Couldn't refresh forecasts: {why}"),
+                }
+            },
+            Err(why) => error!("{why:#?}"),
+        }
+        tokio::time::sleep(Duration::from_secs(config.weather.interval)).await;
+    }
+}
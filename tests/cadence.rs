@@ -0,0 +1,81 @@
+//! Polling intervals of the syncs around sessions.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use f1_notif_bot::{
+    config::CadenceConfig,
+    util::{cadence::Phase, Cadence},
+};
+
+fn utc(
+    day: u32,
+    hour: u32,
+    minute: u32,
+) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 5, day, hour, minute, 0).unwrap()
+}
+
+/// A one hour session on the 10th at 14:00.
+fn session() -> [(DateTime<Utc>, DateTime<Utc>); 1] {
+    let start = utc(10, 14, 0);
+    [(start, start + TimeDelta::hours(1))]
+}
+
+fn minutes(minutes: u64) -> Duration {
+    Duration::from_secs(minutes * 60)
+}
+
+#[test]
+fn phases_follow_the_session() {
+    let cadence = Cadence::new(600, &CadenceConfig::default());
+    let sessions = session();
+    assert_eq!(cadence.phase(&sessions, utc(10, 14, 30)), Phase::Near);
+    assert_eq!(cadence.phase(&sessions, utc(10, 13, 45)), Phase::Near);
+    assert_eq!(cadence.phase(&sessions, utc(10, 15, 20)), Phase::Near);
+    assert_eq!(cadence.phase(&sessions, utc(10, 12, 0)), Phase::Weekend);
+    assert_eq!(cadence.phase(&sessions, utc(11, 10, 0)), Phase::Weekend);
+    assert_eq!(cadence.phase(&sessions, utc(5, 12, 0)), Phase::Idle);
+    assert_eq!(cadence.phase(&[], utc(10, 14, 30)), Phase::Idle);
+}
+
+#[test]
+fn polls_faster_near_sessions() {
+    let cadence = Cadence::new(60 * 60, &CadenceConfig::default());
+    let sessions = session();
+    assert_eq!(cadence.interval(&sessions, utc(10, 14, 30)), minutes(5));
+    // shorter intervals are kept
+    let live = Cadence::new(10, &CadenceConfig::default());
+    assert_eq!(
+        live.interval(&sessions, utc(10, 14, 30)),
+        Duration::from_secs(10)
+    );
+}
+
+#[test]
+fn backs_off_between_weekends() {
+    let cadence = Cadence::new(10, &CadenceConfig::default());
+    assert_eq!(cadence.interval(&session(), utc(5, 12, 0)), minutes(60));
+    // longer intervals are kept
+    let slow = Cadence::new(6 * 60 * 60, &CadenceConfig::default());
+    assert_eq!(slow.interval(&[], utc(5, 12, 0)), minutes(6 * 60));
+}
+
+#[test]
+fn wakes_up_for_the_next_window() {
+    let cadence = Cadence::new(60 * 60, &CadenceConfig::default());
+    let sessions = session();
+    // the weekend starts 24 hours ahead, the near window 30 minutes ahead
+    assert_eq!(cadence.interval(&sessions, utc(9, 13, 50)), minutes(10));
+    assert_eq!(cadence.interval(&sessions, utc(10, 13, 10)), minutes(20));
+}
+
+#[test]
+fn near_can_be_disabled() {
+    let config = CadenceConfig {
+        near: 0,
+        ..Default::default()
+    };
+    let cadence = Cadence::new(60 * 60, &config);
+    assert_eq!(cadence.interval(&session(), utc(10, 14, 30)), minutes(60));
+}
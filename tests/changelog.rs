@@ -0,0 +1,53 @@
+//! Picking the changelog sections to announce after an update.
+
+use f1_notif_bot::util::changelog::{changes_since, parse_version, CHANGELOG};
+
+const LOG: &str = "# Changelog
+
+## 0.3.0
+
+- Third
+
+## 0.2.1
+
+- Second
+
+## 0.2.0
+
+- First
+";
+
+#[test]
+fn sections_between_versions() {
+    assert_eq!(
+        changes_since(LOG, "0.2.0", "0.3.0"),
+        vec![("0.3.0", "- Third"), ("0.2.1", "- Second")]
+    );
+}
+
+#[test]
+fn unreleased_sections_are_skipped() {
+    assert_eq!(
+        changes_since(LOG, "0.2.0", "0.2.1"),
+        vec![("0.2.1", "- Second")]
+    );
+}
+
+#[test]
+fn nothing_for_same_or_older_version() {
+    assert!(changes_since(LOG, "0.3.0", "0.3.0").is_empty());
+    assert!(changes_since(LOG, "0.3.0", "0.2.0").is_empty());
+}
+
+#[test]
+fn versions_compare_numerically() {
+    assert!(parse_version("0.10.0") > parse_version("0.9.1"));
+    assert_eq!(parse_version("v1.2"), Some(vec![1, 2]));
+    assert_eq!(parse_version("1.x"), None);
+}
+
+#[test]
+fn bundled_changelog_has_this_version() {
+    let version = env!("CARGO_PKG_VERSION");
+    assert!(!changes_since(CHANGELOG, "0.0.0", version).is_empty());
+}
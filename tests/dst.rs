@@ -0,0 +1,125 @@
+//! Session times around the DST changes European and Australian rounds
+//! cross every season.
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::{
+    Australia::Melbourne,
+    Europe::{Budapest, London},
+    Tz,
+};
+use f1_notif_bot::util::dst::suspicious_jump;
+use proptest::prelude::*;
+
+fn utc(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn copied_time_across_spring_change() {
+    // 15:00 GMT, then the same UTC time after the clocks went forward
+    assert_eq!(
+        suspicious_jump(
+            utc(2026, 3, 22, 15, 0),
+            London,
+            utc(2026, 4, 5, 15, 0),
+            London
+        ),
+        Some(TimeDelta::hours(1))
+    );
+}
+
+#[test]
+fn adjusted_time_across_spring_change() {
+    // 15:00 local both times
+    assert_eq!(
+        suspicious_jump(
+            utc(2026, 3, 22, 15, 0),
+            London,
+            utc(2026, 4, 5, 14, 0),
+            London
+        ),
+        None
+    );
+}
+
+#[test]
+fn copied_time_across_autumn_change() {
+    assert_eq!(
+        suspicious_jump(
+            utc(2026, 10, 18, 13, 0),
+            Budapest,
+            utc(2026, 11, 1, 13, 0),
+            Budapest
+        ),
+        Some(TimeDelta::hours(-1))
+    );
+}
+
+#[test]
+fn copied_time_across_southern_change() {
+    // Melbourne leaves daylight saving in April
+    assert_eq!(
+        suspicious_jump(
+            utc(2026, 3, 29, 4, 0),
+            Melbourne,
+            utc(2026, 4, 12, 4, 0),
+            Melbourne
+        ),
+        Some(TimeDelta::hours(-1))
+    );
+}
+
+#[test]
+fn copied_time_between_zones() {
+    // Silverstone on BST to the Hungaroring on CEST
+    assert_eq!(
+        suspicious_jump(
+            utc(2026, 7, 5, 14, 0),
+            London,
+            utc(2026, 7, 19, 14, 0),
+            Budapest
+        ),
+        Some(TimeDelta::hours(1))
+    );
+}
+
+proptest! {
+    #[test]
+    fn same_offset_never_jumps(
+        days in 0i64..365,
+        gap in 1i64..21,
+        minute in 0i64..1_440,
+        zone in prop::sample::select(vec![London, Budapest, Melbourne]),
+    ) {
+        let previous = utc(2026, 1, 1, 0, 0)
+            + TimeDelta::days(days)
+            + TimeDelta::minutes(minute);
+        let current = previous + TimeDelta::days(gap);
+        let offset = |f: DateTime<Utc>, tz: Tz| {
+            use chrono::Offset;
+            f.with_timezone(&tz).offset().fix()
+        };
+        let jump = suspicious_jump(previous, zone, current, zone);
+        // only a change of offset between the two can be suspicious
+        prop_assert_eq!(
+            jump.is_some(),
+            offset(previous, zone) != offset(current, zone)
+        );
+    }
+
+    #[test]
+    fn different_utc_times_never_jump(
+        days in 0i64..365,
+        minute in 1i64..1_440,
+    ) {
+        let previous = utc(2026, 1, 1, 12, 0) + TimeDelta::days(days);
+        let current = previous + TimeDelta::days(14) + TimeDelta::minutes(minute);
+        prop_assert_eq!(suspicious_jump(previous, London, current, London), None);
+    }
+}
@@ -0,0 +1,25 @@
+//! Stored hashes have to come out the same in every build.
+
+use std::hash::Hasher;
+
+use f1_notif_bot::util::{stable_hash, StableHasher};
+
+#[test]
+fn matches_the_fnv_reference() {
+    for (input, expected) in [
+        ("", 0xcbf2_9ce4_8422_2325),
+        ("a", 0xaf63_dc4c_8601_ec8c),
+        ("foobar", 0x8594_4171_f739_67e8),
+    ] {
+        let mut hasher = StableHasher::new();
+        hasher.write(input.as_bytes());
+        assert_eq!(hasher.finish(), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn integers_hash_little_endian() {
+    let mut hasher = StableHasher::new();
+    hasher.write(&0x0102_0304u32.to_le_bytes());
+    assert_eq!(stable_hash(&0x0102_0304u32), hasher.finish());
+}
@@ -0,0 +1,67 @@
+//! Liveness and readiness reported by `/healthz` and `/readyz`.
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use f1_notif_bot::health::Health;
+
+fn utc(
+    minute: u32,
+    second: u32,
+) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 5, 10, 14, minute, second).unwrap()
+}
+
+const STALE_AFTER: TimeDelta = TimeDelta::minutes(5);
+
+#[test]
+fn a_ticking_connected_bot_is_ready() {
+    let health = Health::at(
+        true,
+        Some(utc(10, 0)),
+        Some(utc(10, 0)),
+        utc(0, 0),
+        STALE_AFTER,
+        utc(11, 0),
+    );
+    assert!(health.live);
+    assert!(health.ready);
+}
+
+#[test]
+fn a_starting_bot_is_live_but_not_ready() {
+    let health =
+        Health::at(false, None, None, utc(0, 0), STALE_AFTER, utc(2, 0));
+    assert!(health.live);
+    assert!(!health.ready);
+    // a loop that never started is wedged too
+    let health =
+        Health::at(true, None, None, utc(0, 0), STALE_AFTER, utc(6, 0));
+    assert!(!health.live);
+}
+
+#[test]
+fn a_stuck_loop_is_not_live() {
+    let health = Health::at(
+        true,
+        Some(utc(10, 0)),
+        Some(utc(10, 0)),
+        utc(0, 0),
+        STALE_AFTER,
+        utc(15, 1),
+    );
+    assert!(!health.live);
+    assert!(!health.ready);
+}
+
+#[test]
+fn a_disconnected_bot_is_not_ready() {
+    let health = Health::at(
+        false,
+        Some(utc(10, 0)),
+        Some(utc(10, 0)),
+        utc(0, 0),
+        STALE_AFTER,
+        utc(11, 0),
+    );
+    assert!(health.live);
+    assert!(!health.ready);
+}
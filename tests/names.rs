@@ -0,0 +1,57 @@
+//! Weekend names as shown instead of the official ones.
+
+use f1_notif_bot::{
+    config::{WeekendNameConfig, WeekendRename},
+    util::weekend_name,
+};
+
+fn config() -> WeekendNameConfig {
+    WeekendNameConfig {
+        renames: vec![WeekendRename {
+            from: "FORMULA 1 GRAN PREMIO DE LA CIUDAD DE MÉXICO 2026"
+                .to_owned(),
+            to: "Mexico City Grand Prix".to_owned(),
+        }],
+        normalize: true,
+        sponsors: vec!["Heineken".to_owned(), "Qatar Airways".to_owned()],
+    }
+}
+
+#[test]
+fn sponsors_and_prefix_are_dropped() {
+    let config = config();
+    assert_eq!(
+        weekend_name(&config, "FORMULA 1 HEINEKEN DUTCH GRAND PRIX 2026"),
+        "DUTCH Grand Prix"
+    );
+    assert_eq!(
+        weekend_name(&config, "Formula 1 Qatar Airways Qatar Grand Prix"),
+        "Qatar Grand Prix"
+    );
+}
+
+#[test]
+fn renames_win() {
+    assert_eq!(
+        weekend_name(
+            &config(),
+            "Formula 1 Gran Premio de la Ciudad de México 2026"
+        ),
+        "Mexico City Grand Prix"
+    );
+}
+
+#[test]
+fn names_are_kept_without_normalizing() {
+    let config = WeekendNameConfig::default();
+    assert_eq!(
+        weekend_name(&config, "FORMULA 1 HEINEKEN DUTCH GRAND PRIX 2026"),
+        "FORMULA 1 HEINEKEN DUTCH GRAND PRIX 2026"
+    );
+    let config = WeekendNameConfig {
+        sponsors: vec!["Heineken".to_owned()],
+        normalize: true,
+        ..WeekendNameConfig::default()
+    };
+    assert_eq!(weekend_name(&config, "Heineken"), "Heineken");
+}
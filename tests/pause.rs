@@ -0,0 +1,25 @@
+//! Durations accepted by `/pause`.
+
+use chrono::TimeDelta;
+use f1_notif_bot::commands::pause::parse_duration;
+
+#[test]
+fn durations_take_a_unit() {
+    assert_eq!(parse_duration("30m"), Some(TimeDelta::minutes(30)));
+    assert_eq!(parse_duration("2h"), Some(TimeDelta::hours(2)));
+    assert_eq!(parse_duration(" 1D "), Some(TimeDelta::days(1)));
+    assert_eq!(parse_duration("45 min"), Some(TimeDelta::minutes(45)));
+}
+
+#[test]
+fn bare_numbers_are_minutes() {
+    assert_eq!(parse_duration("15"), Some(TimeDelta::minutes(15)));
+}
+
+#[test]
+fn nonsense_is_rejected() {
+    assert_eq!(parse_duration("0m"), None);
+    assert_eq!(parse_duration("soon"), None);
+    assert_eq!(parse_duration("2w"), None);
+    assert_eq!(parse_duration(""), None);
+}
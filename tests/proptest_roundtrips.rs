@@ -0,0 +1,181 @@
+//! Round-trips of the stored enum values and the schedule math, run against
+//! arbitrary values rather than the handful of sessions on the calendar.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use f1_bot_types::{Series, SessionKind, SessionStatus};
+use f1_notif_bot::{
+    sync::SessionSource,
+    util::{
+        is_in_notify_window, is_milestone_due, is_valid_kind, session_end,
+        Backoff, CustomKind, DurationError, KindCode, SessionDuration,
+        CUSTOM_KIND_START, MAX_RETRY_DELAY, NOTIFY_WINDOW,
+    },
+};
+use proptest::prelude::*;
+
+/// Instants between 2000 and 2100, the range the calendar can hold.
+fn instant() -> impl Strategy<Value = DateTime<Utc>> {
+    (946_684_800i64..4_102_444_800i64)
+        .prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+proptest! {
+    #[test]
+    fn series_roundtrip(value in Series::F1.i8()..=Series::F1Academy.i8()) {
+        let series = Series::from(value);
+        prop_assert_eq!(series.i8(), value);
+        prop_assert_eq!(Series::from(series.i8()).to_string(), series.to_string());
+    }
+
+    #[test]
+    fn status_roundtrip(index in 0usize..4) {
+        let statuses = [
+            SessionStatus::Open,
+            SessionStatus::Delayed,
+            SessionStatus::Finished,
+            SessionStatus::Cancelled,
+        ];
+        let status = &statuses[index];
+        prop_assert_eq!(SessionStatus::from(status.i8()).i8(), status.i8());
+    }
+
+    #[test]
+    fn kind_roundtrip(value in any::<i8>()) {
+        // Every known kind keeps its value, everything else is unsupported.
+        match KindCode::of(&SessionKind::from(value)) {
+            Some(code) => prop_assert_eq!(code.0, value),
+            None => prop_assert!(!is_valid_kind(KindCode(value), &[])),
+        }
+    }
+
+    #[test]
+    fn custom_kinds_need_registration(value in CUSTOM_KIND_START..=i8::MAX) {
+        let code = KindCode(value);
+        prop_assert!(code.is_custom());
+        prop_assert!(!is_valid_kind(code, &[]));
+        let custom = [CustomKind { code: value, name: "Shakedown".into() }];
+        prop_assert!(is_valid_kind(code, &custom));
+    }
+
+    #[test]
+    fn source_roundtrip(value in any::<i8>()) {
+        match SessionSource::try_from(value) {
+            Ok(source) => {
+                prop_assert!((0..=2).contains(&value));
+                prop_assert_eq!(source.i8(), value);
+            }
+            Err(_) => prop_assert!(!(0..=2).contains(&value)),
+        }
+    }
+
+    #[test]
+    fn session_end_never_precedes_start(
+        start in instant(),
+        duration in any::<i64>(),
+    ) {
+        let end = session_end(start, SessionDuration::stored(duration));
+        prop_assert!(end >= start);
+        if (0..=86_400 * 7).contains(&duration) {
+            prop_assert_eq!(end, start + TimeDelta::seconds(duration));
+        }
+        if duration <= 0 {
+            prop_assert_eq!(end, start);
+        }
+    }
+
+    #[test]
+    fn session_duration_validation(secs in any::<i64>()) {
+        match SessionDuration::from_secs(secs) {
+            Ok(duration) => {
+                prop_assert!((0..=86_400).contains(&secs));
+                prop_assert_eq!(duration.secs(), secs);
+                prop_assert_eq!(SessionDuration::stored(secs), duration);
+            }
+            Err(DurationError::Negative) => prop_assert!(secs < 0),
+            Err(DurationError::TooLong) => prop_assert!(secs > 86_400),
+        }
+    }
+
+    #[test]
+    fn notify_window_bounds(
+        now in instant(),
+        offset in -3_600i64..3_600,
+        lead in 0i64..120,
+    ) {
+        let start = now + TimeDelta::seconds(offset);
+        let lead = TimeDelta::minutes(lead);
+        let limit = (NOTIFY_WINDOW + lead).num_seconds();
+        prop_assert_eq!(
+            is_in_notify_window(start, now, lead),
+            offset > -60 && offset < limit
+        );
+    }
+
+    #[test]
+    fn window_grows_with_lead(
+        now in instant(),
+        offset in -3_600i64..3_600,
+        lead in 0i64..120,
+    ) {
+        let start = now + TimeDelta::seconds(offset);
+        if is_in_notify_window(start, now, TimeDelta::zero()) {
+            prop_assert!(is_in_notify_window(start, now, TimeDelta::minutes(lead)));
+        }
+    }
+
+    #[test]
+    fn milestone_window_is_five_minutes(
+        start in instant(),
+        offset in 1u64..=1_440,
+    ) {
+        // The loop wakes just after the milestone and again at any point
+        // later, so sampled every five seconds it stays due for exactly five
+        // minutes. `milestones_sent` keeps that to a single message.
+        let from = start - TimeDelta::minutes(offset as i64 + 10);
+        let due = (0..(offset as i64 + 20) * 12)
+            .map(|step| from + TimeDelta::seconds(step * 5))
+            .filter(|now| is_milestone_due(start, *now, offset))
+            .count();
+        prop_assert_eq!(due, 60);
+    }
+
+    #[test]
+    fn window_ignores_local_time(
+        now in instant(),
+        offset in -3_600i64..3_600,
+        zone in prop::sample::select(vec![
+            "Europe/London",
+            "America/Sao_Paulo",
+            "Australia/Melbourne",
+            "America/Mexico_City",
+        ]),
+    ) {
+        // Tracks in DST zones convert to local time and back without
+        // shifting the instant the window is checked against.
+        let tz: Tz = zone.parse().unwrap();
+        let start = now + TimeDelta::seconds(offset);
+        let local = start.with_timezone(&tz);
+        prop_assert_eq!(local.with_timezone(&Utc), start);
+        prop_assert_eq!(
+            is_in_notify_window(local.with_timezone(&Utc), now, TimeDelta::zero()),
+            is_in_notify_window(start, now, TimeDelta::zero())
+        );
+    }
+
+    #[test]
+    fn backoff_grows_and_stays_capped(
+        delay in 1u64..5_000,
+        attempt in 0u32..64,
+    ) {
+        let backoff = Backoff { retries: 5, delay: Duration::from_millis(delay) };
+        let base = Duration::from_millis(delay)
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_DELAY);
+        let actual = backoff.delay(attempt);
+        prop_assert!(actual >= base);
+        prop_assert!(actual <= base + base / 4);
+    }
+}
@@ -0,0 +1,55 @@
+//! Filling and checking `[templates]` entries.
+
+use f1_notif_bot::{
+    config::TemplateConfig,
+    util::templates::{check, fill, uses},
+};
+
+#[test]
+fn placeholders_are_filled() {
+    assert_eq!(
+        fill(
+            "{weekend.name} {session.name}",
+            &[("weekend.name", "Monaco"), ("session.name", "Race"),]
+        ),
+        "Monaco Race"
+    );
+}
+
+#[test]
+fn doubled_braces_are_literal() {
+    assert_eq!(fill("{{{role}}}", &[("role", "x")]), "{x}");
+}
+
+#[test]
+fn unknown_placeholders_are_rejected() {
+    assert!(check("{weekend.nmae}", &["weekend.name"]).is_err());
+    assert!(check("{weekend.name", &["weekend.name"]).is_err());
+    assert!(check("weekend}", &[]).is_err());
+}
+
+#[test]
+fn role_placeholder_is_found() {
+    assert!(uses("{role} {weekend.name}", "role"));
+    assert!(!uses("{{role}} {weekend.name}", "role"));
+}
+
+#[test]
+fn defaults_are_valid() {
+    let defaults = TemplateConfig::default();
+    assert!(check(
+        &defaults.notification,
+        TemplateConfig::NOTIFICATION_PLACEHOLDERS
+    )
+    .is_ok());
+    assert!(check(&defaults.weekend_title, TemplateConfig::TITLE_PLACEHOLDERS)
+        .is_ok());
+    assert!(check(
+        &defaults.calendar_title,
+        TemplateConfig::TITLE_PLACEHOLDERS
+    )
+    .is_ok());
+    assert!(
+        check(&defaults.session, TemplateConfig::SESSION_PLACEHOLDERS).is_ok()
+    );
+}
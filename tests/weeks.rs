@@ -0,0 +1,59 @@
+//! Day-grouped views and their week start and date format settings.
+
+use chrono::NaiveDate;
+use f1_notif_bot::util::{week_of, DateFormat, GuildSettings, WeekStart};
+
+fn date(
+    year: i32,
+    month: u32,
+    day: u32,
+) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn weeks_start_on_the_configured_day() {
+    // a Wednesday
+    let day = date(2025, 3, 12);
+    let monday = week_of(day, WeekStart::Monday).collect::<Vec<_>>();
+    assert_eq!(monday.len(), 7);
+    assert_eq!(monday[0], date(2025, 3, 10));
+    assert_eq!(monday[6], date(2025, 3, 16));
+    let sunday = week_of(day, WeekStart::Sunday).collect::<Vec<_>>();
+    assert_eq!(sunday[0], date(2025, 3, 9));
+    assert_eq!(sunday[6], date(2025, 3, 15));
+}
+
+#[test]
+fn the_first_day_starts_its_own_week() {
+    let sunday = date(2025, 3, 9);
+    assert_eq!(week_of(sunday, WeekStart::Sunday).next(), Some(sunday));
+    assert_eq!(
+        week_of(sunday, WeekStart::Monday).next(),
+        Some(date(2025, 3, 3))
+    );
+}
+
+#[test]
+fn dates_are_written_in_the_chosen_format() {
+    let day = date(2025, 3, 9);
+    assert_eq!(DateFormat::Long.format(day), "Sunday, 9 March 2025");
+    assert_eq!(DateFormat::Us.format(day), "Sunday, March 9, 2025");
+    assert_eq!(DateFormat::Iso.format(day), "2025-03-09");
+}
+
+#[test]
+fn date_settings_round_trip() {
+    let mut settings = GuildSettings::default();
+    assert!(settings.set("week_start", "sunday"));
+    assert!(settings.set("date_format", "iso"));
+    assert!(!settings.set("date_format", "%Y"));
+    assert_eq!(settings.week_start, WeekStart::Sunday);
+    assert_eq!(settings.date_format, DateFormat::Iso);
+    let mut copy = GuildSettings::default();
+    for (name, value) in settings.pairs() {
+        assert!(copy.set(name, &value));
+    }
+    assert_eq!(copy.week_start, WeekStart::Sunday);
+    assert_eq!(copy.date_format, DateFormat::Iso);
+}